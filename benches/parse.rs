@@ -0,0 +1,41 @@
+//! Parsing benchmarks, for short inputs and for long (zero-padded or
+//! space-aligned) log tokens.
+//!
+//! To measure the speedup of the `swar` feature over the scalar scanning, save
+//! a baseline without it and compare:
+//!
+//! ```sh
+//! cargo bench --bench parse -- --save-baseline scalar
+//! cargo bench --bench parse --features swar -- --baseline scalar
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+const SHORT: &[&str] = &["1.5kB", "12MB", "512", "3.25GB", "0.5kB", "1TB"];
+const LONG: &[&str] = &[
+    "0000000000001.500000000 kB",
+    "        12.000000000000 MB",
+    "00000000000000000000512",
+    "3.2500000000000000000 GB",
+];
+
+fn parse(c: &mut Criterion) {
+    for (name, inputs) in [("short", SHORT), ("long", LONG)] {
+        let mut group = c.benchmark_group(format!("parse/{name}"));
+        group.throughput(Throughput::Bytes(inputs.iter().map(|input| input.len() as u64).sum()));
+        group.bench_function("byte", |b| {
+            b.iter(|| {
+                for input in inputs {
+                    let _ = black_box(bity::byte::parse(black_box(input)));
+                }
+            })
+        });
+        group.bench_function("bulk", |b| {
+            b.iter(|| bity::bulk::parse_bytes(black_box(inputs.iter().copied())))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,483 @@
+//! Field attributes generating the serde wiring of
+//! [`bity`](https://docs.rs/bity).
+//!
+//! This crate is re-exported by `bity` when its `derive` feature is enabled,
+//! refer to `bity::bity` for its documentation.
+
+#![warn(
+    clippy::all,
+    clippy::todo,
+    clippy::str_to_string,
+    rust_2018_idioms,
+    future_incompatible,
+    nonstandard_style,
+    missing_debug_implementations,
+    missing_docs
+)]
+#![deny(unreachable_pub)]
+
+use std::mem;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    ext::IdentExt, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Field,
+    Ident, Lit, LitStr, PathArguments, Type,
+};
+
+/// Units with `u64` values, supporting every option.
+const UNITS: &[&str] = &[
+    "si",
+    "bit",
+    "byte",
+    "packet",
+    "bps",
+    "byteps",
+    "pps",
+    "bpp",
+    "hz",
+    "watt",
+    "ops",
+    "percent",
+    "compression",
+];
+
+/// Units with other values (`i64` or `Duration`) and their module, only
+/// supporting plain fields without options.
+const OTHER_UNITS: &[(&str, &str)] = &[("db", "db"), ("dbm", "db::dbm"), ("duration", "duration")];
+
+/// Replace the `#[bity(...)]` attributes of the fields of a struct or an enum
+/// with the matching `#[serde(...)]` attributes.
+///
+/// It must be placed before `#[derive(Serialize, Deserialize)]`. Refer to
+/// `bity::bity` for the supported options.
+#[proc_macro_attribute]
+pub fn bity(args: TokenStream, input: TokenStream) -> TokenStream {
+    expand(args.into(), input.into()).into()
+}
+
+fn expand(args: TokenStream2, input: TokenStream2) -> TokenStream2 {
+    if !args.is_empty() {
+        return Error::new_spanned(args, "`#[bity]` doesn't take any option").to_compile_error();
+    }
+    let mut input = match syn::parse2::<DeriveInput>(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    // Fields with the name of their container (and variant), to name helpers.
+    let container = input.ident.unraw();
+    let fields = match &mut input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter_mut()
+            .enumerate()
+            .map(|(index, field)| (container.clone(), index, field))
+            .collect::<Vec<_>>(),
+        Data::Enum(data) => data
+            .variants
+            .iter_mut()
+            .flat_map(|variant| {
+                let container = format_ident!("{}_{}", container, variant.ident.unraw());
+                variant
+                    .fields
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(index, field)| (container.clone(), index, field))
+            })
+            .collect(),
+        Data::Union(data) => data
+            .fields
+            .named
+            .iter_mut()
+            .enumerate()
+            .map(|(index, field)| (container.clone(), index, field))
+            .collect(),
+    };
+
+    let mut helpers = TokenStream2::new();
+    let mut errors = None::<Error>;
+    for (container, index, field) in fields {
+        if let Err(err) = expand_field(&container, index, field, &mut helpers) {
+            match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+        }
+    }
+
+    let errors = errors.map(|errors| errors.to_compile_error());
+    quote! {
+        #input
+        #helpers
+        #errors
+    }
+}
+
+/// Options of a `#[bity(...)]` field attribute.
+#[derive(Default)]
+struct Options {
+    unit: Option<Ident>,
+    strict: bool,
+    as_int: bool,
+    min: Option<Lit>,
+    max: Option<Lit>,
+}
+
+impl Options {
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut options = Self::default();
+        attr.parse_nested_meta(|meta| {
+            let ident = meta
+                .path
+                .get_ident()
+                .ok_or_else(|| meta.error("expected a unit or an option"))?;
+            match ident.to_string().as_str() {
+                "strict" => options.strict = true,
+                "as_int" => options.as_int = true,
+                "min" => options.min = Some(meta.value()?.parse()?),
+                "max" => options.max = Some(meta.value()?.parse()?),
+                "iec" => {
+                    return Err(meta.error("IEC prefixes are not supported, only metric ones are"))
+                }
+                _ if options.unit.is_none() => options.unit = Some(ident.clone()),
+                _ => return Err(meta.error(format_args!("unknown option `{ident}`"))),
+            }
+            Ok(())
+        })?;
+        Ok(options)
+    }
+}
+
+/// How a field wraps its value.
+#[derive(PartialEq, Eq)]
+enum Kind {
+    Plain,
+    Option,
+    Vec,
+}
+
+impl Kind {
+    fn of(ty: &Type) -> Self {
+        let Type::Path(path) = ty else {
+            return Kind::Plain;
+        };
+        let Some(segment) = path.path.segments.last() else {
+            return Kind::Plain;
+        };
+        match &segment.arguments {
+            PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                match segment.ident.to_string().as_str() {
+                    "Option" => Kind::Option,
+                    "Vec" => Kind::Vec,
+                    _ => Kind::Plain,
+                }
+            }
+            _ => Kind::Plain,
+        }
+    }
+}
+
+fn expand_field(
+    container: &Ident,
+    index: usize,
+    field: &mut Field,
+    helpers: &mut TokenStream2,
+) -> syn::Result<()> {
+    let (attrs, others) = mem::take(&mut field.attrs)
+        .into_iter()
+        .partition::<Vec<_>, _>(|attr| attr.path().is_ident("bity"));
+    field.attrs = others;
+    let attr = match attrs.as_slice() {
+        [] => return Ok(()),
+        [attr] => attr,
+        [_, attr, ..] => return Err(Error::new_spanned(attr, "duplicate `bity` attribute")),
+    };
+
+    let options = Options::parse(attr)?;
+    let unit = options
+        .unit
+        .as_ref()
+        .ok_or_else(|| Error::new_spanned(attr, "missing unit, like `#[bity(byte)]`"))?;
+    let name = unit.to_string();
+    let kind = Kind::of(&field.ty);
+    let bounded = options.min.is_some() || options.max.is_some();
+
+    let module = match OTHER_UNITS.iter().find(|(other, _)| *other == name) {
+        Some((_, module)) => {
+            if kind != Kind::Plain || options.strict || options.as_int || bounded {
+                return Err(Error::new_spanned(
+                    attr,
+                    format_args!("`{name}` only supports plain fields, without options"),
+                ));
+            }
+            format!("::bity::{module}")
+        }
+        None if UNITS.contains(&name.as_str()) => format!("::bity::{name}"),
+        None => {
+            return Err(Error::new_spanned(
+                unit,
+                format_args!(
+                    "unknown unit `{name}`, expected one of {}",
+                    UNITS
+                        .iter()
+                        .chain(OTHER_UNITS.iter().map(|(other, _)| other))
+                        .map(|unit| format!("`{unit}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ))
+        }
+    };
+
+    if options.strict && options.as_int {
+        return Err(Error::new_spanned(attr, "`strict` and `as_int` can't be combined"));
+    }
+    if (options.strict || options.as_int || bounded) && kind != Kind::Plain {
+        return Err(Error::new_spanned(
+            attr,
+            "`strict`, `as_int`, `min` and `max` are only supported on plain `u64` fields",
+        ));
+    }
+
+    let serde: Attribute = if bounded {
+        if options.strict || options.as_int {
+            return Err(Error::new_spanned(
+                attr,
+                "`min` and `max` can't be combined with `strict` or `as_int`",
+            ));
+        }
+        let field_name = match &field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => index.to_string(),
+        };
+        let function = format_ident!("__bity_deserialize_{}_{}", container, field_name);
+        let parse: syn::Path = syn::parse_str(&format!("{module}::parse"))?;
+        let format: syn::Path = syn::parse_str(&format!("{module}::format"))?;
+        let expecting: syn::Path = syn::parse_str(&format!("{module}::EXPECTING"))?;
+        let units: syn::Path = syn::parse_str(&format!("{module}::UNITS"))?;
+        let numbers: syn::Path = syn::parse_str(&format!("{module}::NUMBERS"))?;
+        let parse_const: syn::Path = syn::parse_str(&format!("{module}::parse_const"))?;
+        if let (Some(Lit::Int(min)), Some(Lit::Int(max))) = (&options.min, &options.max) {
+            if min.base10_parse::<u64>()? > max.base10_parse::<u64>()? {
+                return Err(Error::new_spanned(
+                    attr,
+                    "the minimum bound is greater than the maximum one",
+                ));
+            }
+        }
+        let min = bound(options.min.as_ref(), &parse_const, "minimum", 0)?;
+        let max = bound(options.max.as_ref(), &parse_const, "maximum", u64::MAX)?;
+        let message =
+            format!("the minimum bound of `{field_name}` is greater than the maximum one");
+        let check = quote_spanned! {attr.span()=>
+            const _: () = ::core::assert!(MIN <= MAX, #message);
+        };
+        helpers.extend(quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            fn #function<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: ::bity::__private::serde::Deserializer<'de>,
+            {
+                const MIN: u64 = #min;
+                const MAX: u64 = #max;
+                #check
+                ::bity::__private::deserialize_bounded_with(
+                    deserializer,
+                    #expecting,
+                    #units,
+                    #numbers,
+                    MIN,
+                    MAX,
+                    #parse,
+                    #format,
+                )
+            }
+        });
+        let serialize = format!("{module}::serialize");
+        let deserialize = function.to_string();
+        parse_quote!(#[serde(serialize_with = #serialize, deserialize_with = #deserialize)])
+    } else {
+        let with = match kind {
+            Kind::Plain if options.strict => format!("{module}::strict"),
+            Kind::Plain if options.as_int => format!("{module}::as_int"),
+            Kind::Plain => module,
+            Kind::Option => format!("{module}::option"),
+            Kind::Vec => format!("{module}::vec"),
+        };
+        if kind == Kind::Option {
+            parse_quote!(#[serde(default, with = #with)])
+        } else {
+            parse_quote!(#[serde(with = #with)])
+        }
+    };
+    field.attrs.push(serde);
+    Ok(())
+}
+
+/// Constant expression evaluating to a bound, parsed at compile time using
+/// `parse_const` if it's a string.
+fn bound(
+    bound: Option<&Lit>,
+    parse_const: &syn::Path,
+    name: &str,
+    default: u64,
+) -> syn::Result<TokenStream2> {
+    match bound {
+        None => Ok(Literal::u64_suffixed(default).into_token_stream()),
+        Some(Lit::Int(int)) => Ok(Literal::u64_suffixed(int.base10_parse()?).into_token_stream()),
+        Some(Lit::Str(input)) => {
+            let message =
+                LitStr::new(&format!("invalid {name} bound {:?}", input.value()), input.span());
+            Ok(quote_spanned! {input.span()=>
+                match #parse_const(#input) {
+                    ::core::option::Option::Some(bound) => bound,
+                    ::core::option::Option::None => ::core::panic!(#message),
+                }
+            })
+        }
+        Some(lit) => Err(Error::new_spanned(
+            lit,
+            format_args!("expected an integer or a string {name} bound"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    fn expand(input: proc_macro2::TokenStream) -> String {
+        super::expand(proc_macro2::TokenStream::new(), input).to_string()
+    }
+
+    #[test]
+    fn expand_fields() {
+        let expanded = expand(quote! {
+            struct Configuration {
+                #[bity(byte)]
+                quota: u64,
+                #[bity(bps)]
+                limit: Option<u64>,
+                #[bity(pps)]
+                tiers: Vec<u64>,
+                #[bity(byte, strict)]
+                strict: u64,
+                #[bity(db)]
+                margin: i64,
+                name: String,
+            }
+        });
+        assert!(!expanded.contains("bity ("));
+        assert!(expanded.contains(r#"# [serde (with = "::bity::byte")] quota"#));
+        assert!(expanded.contains(r#"# [serde (default , with = "::bity::bps::option")] limit"#));
+        assert!(expanded.contains(r#"# [serde (with = "::bity::pps::vec")] tiers"#));
+        assert!(expanded.contains(r#"# [serde (with = "::bity::byte::strict")] strict"#));
+        assert!(expanded.contains(r#"# [serde (with = "::bity::db")] margin"#));
+        assert!(!expanded.contains("compile_error"));
+    }
+
+    #[test]
+    fn expand_bounds() {
+        let expanded = expand(quote! {
+            struct Configuration(#[bity(byte, max = "10GB")] u64);
+        });
+        assert!(expanded.contains(
+            r#"# [serde (serialize_with = "::bity::byte::serialize" , deserialize_with = "__bity_deserialize_Configuration_0")]"#
+        ));
+        assert!(expanded.contains("fn __bity_deserialize_Configuration_0"));
+        assert!(expanded.contains("const MIN : u64 = 0u64"));
+        assert!(expanded.contains(r#"match :: bity :: byte :: parse_const ("10GB")"#));
+        assert!(expanded.contains("assert ! (MIN <= MAX"));
+        assert!(!expanded.contains("compile_error"));
+    }
+
+    #[test]
+    fn expand_enum() {
+        let expanded = expand(quote! {
+            enum Limit {
+                Disk { #[bity(byte, min = 1_000)] size: u64 },
+                Memory { #[bity(byte, min = 1_000)] size: u64 },
+            }
+        });
+        assert!(expanded.contains("fn __bity_deserialize_Limit_Disk_size"));
+        assert!(expanded.contains("fn __bity_deserialize_Limit_Memory_size"));
+        assert!(expanded.contains("const MIN : u64 = 1000u64"));
+        assert!(!expanded.contains("compile_error"));
+    }
+
+    #[test]
+    fn expand_errors() {
+        for (input, message) in [
+            (
+                quote!(
+                    struct S {
+                        #[bity(bps, iec)]
+                        rate: u64,
+                    }
+                ),
+                "IEC prefixes are not supported",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(bytes)]
+                        size: u64,
+                    }
+                ),
+                "unknown unit `bytes`",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(max = 12)]
+                        size: u64,
+                    }
+                ),
+                "missing unit",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(byte, strict)]
+                        size: Option<u64>,
+                    }
+                ),
+                "only supported on plain `u64` fields",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(duration, max = 12)]
+                        timeout: Duration,
+                    }
+                ),
+                "`duration` only supports plain fields",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(byte, max = 1.5)]
+                        size: u64,
+                    }
+                ),
+                "expected an integer",
+            ),
+            (
+                quote!(
+                    struct S {
+                        #[bity(byte, min = 1_000, max = 10)]
+                        size: u64,
+                    }
+                ),
+                "the minimum bound is greater than the maximum one",
+            ),
+        ] {
+            let expanded = expand(input);
+            assert!(expanded.contains("compile_error"), "{expanded}");
+            assert!(expanded.contains(message), "{expanded}");
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/* C bindings of bity, available with the `ffi` cargo feature. */
+
+#ifndef BITY_H
+#define BITY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Size of a buffer large enough for any formatted value, including the NUL
+ * terminator. */
+#define BITY_FORMAT_BUFFER_LEN 41
+
+typedef enum bity_status {
+    BITY_OK = 0,
+    BITY_NOT_ASCII = 1,
+    BITY_EMPTY_INPUT = 2,
+    BITY_INVALID_UNIT = 3,
+    BITY_MULTIPLE_UNITS = 4,
+    BITY_NEGATIVE_VALUE = 5,
+    BITY_FRACTION_TOO_PRECISE = 6,
+    BITY_TRAILING_GARBAGE = 7,
+    BITY_INVALID_NUMBER = 8,
+    BITY_NULL_POINTER = 100,
+    BITY_BUFFER_TOO_SMALL = 101,
+} bity_status;
+
+/* Name of a status, like "invalid_unit", or "unknown" for unknown codes. */
+const char *bity_status_str(int status);
+
+#define BITY_FAMILY(family, type)                                              \
+    bity_status bity_##family##_parse(const char *input, type *output);        \
+    bity_status bity_##family##_format(type input, char *buffer,               \
+                                       size_t capacity, size_t *len);
+
+BITY_FAMILY(si, uint64_t)
+BITY_FAMILY(bit, uint64_t)
+BITY_FAMILY(byte, uint64_t)
+BITY_FAMILY(packet, uint64_t)
+BITY_FAMILY(bps, uint64_t)
+BITY_FAMILY(byteps, uint64_t)
+BITY_FAMILY(pps, uint64_t)
+BITY_FAMILY(bpp, uint64_t)
+BITY_FAMILY(hz, uint64_t)
+BITY_FAMILY(watt, uint64_t)
+BITY_FAMILY(ops, uint64_t)
+BITY_FAMILY(percent, uint64_t)
+BITY_FAMILY(compression, uint64_t)
+BITY_FAMILY(db, int64_t)
+BITY_FAMILY(dbm, int64_t)
+/* Durations are exchanged as nanoseconds. */
+BITY_FAMILY(duration, uint64_t)
+
+#undef BITY_FAMILY
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BITY_H */
@@ -0,0 +1,168 @@
+//! [`arbitrary`](https://docs.rs/arbitrary) support, for fuzz targets.
+//!
+//! [`Limit`], [`RelativeOrAbsolute`], [`Prefix`] and [`Period`] implement
+//! [`Arbitrary`], and [`valid_input`] generates strings accepted by the
+//! parsing functions of a unit, to fuzz parsing and formatting round-trips or
+//! structures holding string fields.
+//!
+//! # Examples
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use bity::{limit::ByteLimit, unit::Byteps};
+//!
+//! #[derive(Debug, Arbitrary)]
+//! struct Configuration {
+//!     quota: ByteLimit,
+//!     #[arbitrary(with = bity::arbitrary::valid_input::<Byteps>)]
+//!     rate: String,
+//! }
+//!
+//! let mut u = Unstructured::new(&[0x2a; 64]);
+//! let configuration = Configuration::arbitrary(&mut u).unwrap();
+//! assert!(bity::byteps::parse(&configuration.rate).is_ok());
+//! ```
+
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, si::Prefix, unit::Unit, Period};
+
+/// Whitespaces accepted around the number and the unit.
+const WHITESPACES: &[&str] = &["", " ", "  ", "\t"];
+
+/// Generate a string accepted by `U::parse`.
+///
+/// An arbitrary value (of an arbitrary magnitude) is formatted using
+/// `U::format`, and whitespaces are inserted around the number and the unit.
+/// It can be used as the `with` function of `#[arbitrary(...)]` field
+/// attributes.
+///
+/// Values that `U::parse` can't parse back once formatted are rejected with
+/// [`Error::IncorrectFormat`].
+///
+/// # Examples
+/// ```
+/// use arbitrary::Unstructured;
+/// use bity::{arbitrary::valid_input, unit::Byte};
+///
+/// let mut u = Unstructured::new(b"fuzzer provided bytes");
+/// let input = valid_input::<Byte>(&mut u).unwrap();
+/// assert!(bity::byte::parse(&input).is_ok());
+/// ```
+pub fn valid_input<U: Unit>(u: &mut Unstructured<'_>) -> Result<String> {
+    // Pick the magnitude first, so all the prefixes are equally exercised.
+    let max = 10u64.saturating_pow(u.int_in_range(0..=20)?);
+    let formatted = U::format(u.int_in_range(0..=max)?);
+    // Some units can't parse back their largest values (like compression
+    // ratios, parsed into ppm before being divided).
+    if U::parse(&formatted).is_err() {
+        return Err(Error::IncorrectFormat);
+    }
+    let (number, unit) = formatted.split_at(
+        formatted
+            .bytes()
+            .position(|b| !b.is_ascii_digit() && b != b'.')
+            .unwrap_or(formatted.len()),
+    );
+    let mut input = String::new();
+    input.push_str(u.choose(WHITESPACES)?);
+    input.push_str(number);
+    if !unit.is_empty() {
+        input.push_str(u.choose(WHITESPACES)?);
+        input.push_str(unit);
+    }
+    input.push_str(u.choose(WHITESPACES)?);
+    Ok(input)
+}
+
+impl<'a, U> Arbitrary<'a> for Limit<U> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Option::<u64>::arbitrary(u)?.into())
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Option::<u64>::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<'a, U> Arbitrary<'a> for RelativeOrAbsolute<U> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            Ok(RelativeOrAbsolute::relative(u.arbitrary()?))
+        } else {
+            Ok(RelativeOrAbsolute::absolute(u.arbitrary()?))
+        }
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <(bool, u64)>::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Prefix {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[
+            Prefix::None,
+            Prefix::Kilo,
+            Prefix::Mega,
+            Prefix::Giga,
+            Prefix::Tera,
+            Prefix::Peta,
+            Prefix::Exa,
+        ])
+        .copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Period {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[
+            Period::Second,
+            Period::Minute,
+            Period::Hour,
+            Period::Day,
+            Period::Week,
+            Period::Month,
+            Period::Year,
+        ])
+        .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::Unstructured;
+
+    use crate::unit::{Si, Unit};
+
+    fn check<U: Unit>(data: &[u8]) {
+        let mut u = Unstructured::new(data);
+        let mut valid = 0;
+        while !u.is_empty() {
+            if let Ok(input) = super::valid_input::<U>(&mut u) {
+                assert!(U::parse(&input).is_ok(), "{input:?}");
+                valid += 1;
+            }
+        }
+        assert!(valid > 0);
+    }
+
+    #[test]
+    fn valid_input() {
+        let data = (0..4_096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        check::<Si>(&data);
+        #[cfg(feature = "byte")]
+        check::<crate::unit::Byte>(&data);
+        #[cfg(feature = "byteps")]
+        check::<crate::unit::Byteps>(&data);
+        #[cfg(feature = "percent")]
+        check::<crate::unit::Percent>(&data);
+        #[cfg(feature = "compression")]
+        check::<crate::unit::Compression>(&data);
+    }
+}
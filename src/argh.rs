@@ -0,0 +1,188 @@
+//! [`argh`](https://docs.rs/argh) argument parsing.
+//!
+//! [`Limit`] and [`RelativeOrAbsolute`] implement [`FromArgValue`], so they
+//! can be used as option types directly. Integers (or
+//! [`Duration`](std::time::Duration)s) of a unit family are parsed using the
+//! functions of this module, with `#[argh(option, from_str_fn(...))]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use argh::FromArgs;
+//! use bity::limit::ByteLimit;
+//!
+//! #[derive(FromArgs)]
+//! /// Upload files.
+//! struct Args {
+//!     /// maximum upload rate
+//!     #[argh(option, from_str_fn(bity::argh::byteps))]
+//!     rate: u64,
+//!     /// maximum size of the uploaded files
+//!     #[argh(option)]
+//!     limit: ByteLimit,
+//! }
+//!
+//! let args = Args::from_args(&["upload"], &["--rate", "5MB/s", "--limit", "512MB"]).unwrap();
+//! assert_eq!(args.rate, 5_000_000);
+//! assert_eq!(args.limit.value(), Some(512_000_000));
+//!
+//! let err = Args::from_args(&["upload"], &["--rate", "5XB/s", "--limit", "none"])
+//!     .err()
+//!     .unwrap();
+//! assert_eq!(
+//!     err.output,
+//!     "Error parsing option '--rate' with value '5XB/s': invalid unit \"XB\"\n"
+//! );
+//! ```
+
+use argh::FromArgValue;
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, unit::Unit};
+
+macro_rules! parsers {
+    ($($(#[doc = $doc:literal])* $(#[cfg($cfg:meta)])? $name:ident => $parse:path: $value:ty,)*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            pub fn $name(input: &str) -> Result<$value, String> {
+                $parse(input).map_err(|err| err.to_string())
+            }
+        )*
+    };
+}
+
+parsers! {
+    /// Parse an argument using [`si::parse`](crate::si::parse).
+    si => crate::si::parse: u64,
+    /// Parse an argument using [`bit::parse`](crate::bit::parse).
+    #[cfg(feature = "bit")]
+    bit => crate::bit::parse: u64,
+    /// Parse an argument using [`byte::parse`](crate::byte::parse).
+    #[cfg(feature = "byte")]
+    byte => crate::byte::parse: u64,
+    /// Parse an argument using [`packet::parse`](crate::packet::parse).
+    #[cfg(feature = "packet")]
+    packet => crate::packet::parse: u64,
+    /// Parse an argument using [`bps::parse`](crate::bps::parse).
+    #[cfg(feature = "bps")]
+    bps => crate::bps::parse: u64,
+    /// Parse an argument using [`byteps::parse`](crate::byteps::parse).
+    #[cfg(feature = "byteps")]
+    byteps => crate::byteps::parse: u64,
+    /// Parse an argument using [`pps::parse`](crate::pps::parse).
+    #[cfg(feature = "pps")]
+    pps => crate::pps::parse: u64,
+    /// Parse an argument using [`bpp::parse`](crate::bpp::parse).
+    #[cfg(feature = "bpp")]
+    bpp => crate::bpp::parse: u64,
+    /// Parse an argument using [`hz::parse`](crate::hz::parse).
+    #[cfg(feature = "hz")]
+    hz => crate::hz::parse: u64,
+    /// Parse an argument using [`watt::parse`](crate::watt::parse).
+    #[cfg(feature = "watt")]
+    watt => crate::watt::parse: u64,
+    /// Parse an argument using [`ops::parse`](crate::ops::parse).
+    #[cfg(feature = "ops")]
+    ops => crate::ops::parse: u64,
+    /// Parse an argument using [`percent::parse`](crate::percent::parse).
+    #[cfg(feature = "percent")]
+    percent => crate::percent::parse: u64,
+    /// Parse an argument using
+    /// [`compression::parse`](crate::compression::parse).
+    #[cfg(feature = "compression")]
+    compression => crate::compression::parse: u64,
+    /// Parse an argument using [`db::parse`](crate::db::parse).
+    #[cfg(feature = "db")]
+    db => crate::db::parse: i64,
+    /// Parse an argument using [`db::parse_dbm`](crate::db::parse_dbm).
+    #[cfg(feature = "db")]
+    dbm => crate::db::parse_dbm: i64,
+    /// Parse an argument using [`duration::parse`](crate::duration::parse).
+    #[cfg(feature = "duration")]
+    duration => crate::duration::parse: std::time::Duration,
+}
+
+impl<U: Unit> FromArgValue for Limit<U> {
+    fn from_arg_value(value: &str) -> Result<Self, String> {
+        Limit::parse(value).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Unit> FromArgValue for RelativeOrAbsolute<U> {
+    fn from_arg_value(value: &str) -> Result<Self, String> {
+        RelativeOrAbsolute::parse(value).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use argh::FromArgs;
+
+    use crate::{
+        limit::Limit,
+        relative::RelativeOrAbsolute,
+        unit::{Bps, Byte},
+    };
+
+    #[derive(FromArgs, Debug)]
+    /// Test arguments.
+    struct Args {
+        /// size
+        #[argh(option, from_str_fn(super::byte))]
+        size: Option<u64>,
+        /// power
+        #[argh(option, from_str_fn(super::dbm))]
+        power: Option<i64>,
+        /// limit
+        #[argh(option)]
+        limit: Option<Limit<Bps>>,
+        /// reserved
+        #[argh(option)]
+        reserved: Option<RelativeOrAbsolute<Byte>>,
+    }
+
+    #[test]
+    fn parse() {
+        let args = Args::from_args(
+            &["app"],
+            &[
+                "--size",
+                "1.5kB",
+                "--power",
+                "-20dBm",
+                "--limit",
+                "none",
+                "--reserved",
+                "5%",
+            ],
+        )
+        .unwrap();
+        assert_eq!(args.size, Some(1_500));
+        assert_eq!(args.power, Some(-20_000));
+        assert_eq!(args.limit, Some(Limit::unlimited()));
+        assert_eq!(args.reserved, Some(RelativeOrAbsolute::relative(50_000)));
+
+        let args = Args::from_args(&["app"], &["--limit", "1Mb/s", "--reserved", "1GB"]).unwrap();
+        assert_eq!(args.limit, Some(Limit::new(1_000_000)));
+        assert_eq!(args.reserved, Some(RelativeOrAbsolute::absolute(1_000_000_000)));
+    }
+
+    #[test]
+    fn error() {
+        let err = Args::from_args(&["app"], &["--size", "12kx"]).unwrap_err();
+        assert_eq!(
+            err.output,
+            "Error parsing option '--size' with value '12kx': invalid unit \"kx\"\n"
+        );
+        assert!(err.status.is_err());
+
+        let err = Args::from_args(&["app"], &["--limit", "-1kbps"]).unwrap_err();
+        assert_eq!(
+            err.output,
+            "Error parsing option '--limit' with value '-1kbps': negative number \"-1\"\n"
+        );
+    }
+}
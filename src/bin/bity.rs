@@ -0,0 +1,447 @@
+//! Command line interface of bity, enabled by the `cli` feature.
+//!
+//! ```sh
+//! $ bity parse byte 1.5kB
+//! 1500
+//! $ bity format bps 69200
+//! 69.2kb/s
+//! $ bity convert 12Mb -t B
+//! 1.5MB
+//! $ du -b | bity filter --to byte
+//! 12.34kB ./src
+//! ```
+//!
+//! Inputs are read from the standard input (one per line) if none is given.
+
+use std::{
+    io::{self, BufRead, Write},
+    num::NonZeroUsize,
+    process::ExitCode,
+    time::Duration,
+};
+
+use bity::{bit, bps, byte, byteps, si::Prefix};
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// SI prefix, data, packets, data-rate, packet-rate string parser and
+/// formater.
+#[derive(Parser, Debug)]
+#[command(version)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Parse strings into integers.
+    Parse {
+        /// Unit module used to parse the inputs.
+        unit: Unit,
+        /// Strings to parse, read from the standard input if missing.
+        inputs: Vec<String>,
+    },
+    /// Format integers into strings.
+    Format {
+        /// Unit module used to format the inputs.
+        unit: Unit,
+        /// Integers to format, read from the standard input if missing.
+        inputs: Vec<String>,
+    },
+    /// Convert data sizes and data-rates between bits and bytes.
+    ///
+    /// The unit of the inputs is guessed from their suffix. Conversions to
+    /// bytes are rounded down.
+    Convert {
+        /// Unit of the output.
+        #[arg(short, long, value_enum)]
+        to: Target,
+        /// Fixed SI prefix of the output (like `k` or `M`).
+        #[arg(short, long, value_parser = parse_prefix)]
+        prefix: Option<Prefix>,
+        /// Data sizes or data-rates to convert, read from the standard input
+        /// if missing.
+        inputs: Vec<String>,
+    },
+    /// Convert fields of the lines of the standard input, like `numfmt`.
+    ///
+    /// Other fields and delimiters are kept as is. Lines with an invalid (or
+    /// missing) field are printed unchanged, and reported.
+    Filter {
+        /// Unit module used to format the integer fields.
+        #[arg(
+            long,
+            value_enum,
+            required_unless_present = "from",
+            conflicts_with = "from"
+        )]
+        to: Option<Unit>,
+        /// Unit module used to parse the fields into integers.
+        #[arg(long, value_enum)]
+        from: Option<Unit>,
+        /// Fields to convert, starting at 1 and separated by commas.
+        #[arg(short, long, value_delimiter = ',', default_value = "1")]
+        field: Vec<NonZeroUsize>,
+        /// Field delimiter, instead of whitespaces.
+        #[arg(short, long)]
+        delimiter: Option<char>,
+        /// Number of header lines printed unchanged.
+        #[arg(long, default_value_t = 0)]
+        header: usize,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Unit {
+    Si,
+    Bit,
+    Byte,
+    Packet,
+    Bps,
+    Byteps,
+    Pps,
+    Bpp,
+    Hz,
+    Watt,
+    Ops,
+    Percent,
+    Compression,
+    Db,
+    Dbm,
+    Duration,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+enum Target {
+    /// Bits.
+    #[value(name = "b")]
+    Bit,
+    /// Bytes.
+    #[value(name = "B")]
+    Byte,
+    /// Bits per second.
+    #[value(name = "b/s", alias = "bps")]
+    Bps,
+    /// Bytes per second.
+    #[value(name = "B/s", alias = "Bps")]
+    Byteps,
+}
+
+fn parse_prefix(input: &str) -> Result<Prefix, String> {
+    Ok(match input {
+        "" => Prefix::None,
+        "k" | "K" => Prefix::Kilo,
+        "M" | "m" => Prefix::Mega,
+        "G" | "g" => Prefix::Giga,
+        "T" | "t" => Prefix::Tera,
+        "P" | "p" => Prefix::Peta,
+        "E" | "e" => Prefix::Exa,
+        _ => return Err(format!(r#"invalid prefix "{input}""#)),
+    })
+}
+
+fn parse(unit: Unit, input: &str) -> Result<String, String> {
+    let value = match unit {
+        Unit::Si => bity::si::parse(input).map(|value| value.to_string()),
+        Unit::Bit => bity::bit::parse(input).map(|value| value.to_string()),
+        Unit::Byte => bity::byte::parse(input).map(|value| value.to_string()),
+        Unit::Packet => bity::packet::parse(input).map(|value| value.to_string()),
+        Unit::Bps => bity::bps::parse(input).map(|value| value.to_string()),
+        Unit::Byteps => bity::byteps::parse(input).map(|value| value.to_string()),
+        Unit::Pps => bity::pps::parse(input).map(|value| value.to_string()),
+        Unit::Bpp => bity::bpp::parse(input).map(|value| value.to_string()),
+        Unit::Hz => bity::hz::parse(input).map(|value| value.to_string()),
+        Unit::Watt => bity::watt::parse(input).map(|value| value.to_string()),
+        Unit::Ops => bity::ops::parse(input).map(|value| value.to_string()),
+        Unit::Percent => bity::percent::parse(input).map(|value| value.to_string()),
+        Unit::Compression => bity::compression::parse(input).map(|value| value.to_string()),
+        Unit::Db => bity::db::parse(input).map(|value| value.to_string()),
+        Unit::Dbm => bity::db::parse_dbm(input).map(|value| value.to_string()),
+        // Durations are printed as (fractional) seconds.
+        Unit::Duration => bity::duration::parse(input).map(|value| {
+            let fraction = format!("{:09}", value.subsec_nanos());
+            let fraction = fraction.trim_end_matches('0');
+            if fraction.is_empty() {
+                value.as_secs().to_string()
+            } else {
+                format!("{}.{fraction}", value.as_secs())
+            }
+        }),
+    };
+    value.map_err(|err| err.to_string())
+}
+
+fn format(unit: Unit, input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let unsigned = || {
+        input
+            .parse::<u64>()
+            .map_err(|_| format!(r#"invalid integer "{input}""#))
+    };
+    let signed = || {
+        input
+            .parse::<i64>()
+            .map_err(|_| format!(r#"invalid integer "{input}""#))
+    };
+    Ok(match unit {
+        Unit::Si => bity::si::format(unsigned()?),
+        Unit::Bit => bity::bit::format(unsigned()?),
+        Unit::Byte => bity::byte::format(unsigned()?),
+        Unit::Packet => bity::packet::format(unsigned()?),
+        Unit::Bps => bity::bps::format(unsigned()?),
+        Unit::Byteps => bity::byteps::format(unsigned()?),
+        Unit::Pps => bity::pps::format(unsigned()?),
+        Unit::Bpp => bity::bpp::format(unsigned()?),
+        Unit::Hz => bity::hz::format(unsigned()?),
+        Unit::Watt => bity::watt::format(unsigned()?),
+        Unit::Ops => bity::ops::format(unsigned()?),
+        Unit::Percent => bity::percent::format(unsigned()?),
+        Unit::Compression => bity::compression::format(unsigned()?),
+        Unit::Db => bity::db::format(signed()?),
+        Unit::Dbm => bity::db::format_dbm(signed()?),
+        Unit::Duration => bity::duration::format(Duration::from_secs(unsigned()?)),
+    })
+}
+
+fn convert(to: Target, prefix: Option<Prefix>, input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    let volume = bity::strip_per_second(trimmed);
+    if !volume.ends_with(['b', 'B']) {
+        return Err(format!(
+            r#"can't guess the unit of "{trimmed}", it must end with b, B, b/s or B/s"#
+        ));
+    }
+    let is_rate = volume != trimmed;
+    if is_rate != matches!(to, Target::Bps | Target::Byteps) {
+        return Err(format!(r#"can't convert "{trimmed}" between data sizes and data-rates"#));
+    }
+
+    // Both parsers accept bytes, and convert them into bits.
+    let bits = if is_rate {
+        bps::parse(trimmed)
+    } else {
+        bit::parse(trimmed)
+    }
+    .map_err(|err| err.to_string())?;
+    Ok(match (to, prefix) {
+        (Target::Bit, None) => bit::format(bits),
+        (Target::Bit, Some(prefix)) => bit::format_with_prefix(bits, prefix),
+        (Target::Byte, None) => byte::format(bits / 8),
+        (Target::Byte, Some(prefix)) => byte::format_with_prefix(bits / 8, prefix),
+        (Target::Bps, None) => bps::format(bits),
+        (Target::Bps, Some(prefix)) => bps::format_with_prefix(bits, prefix),
+        (Target::Byteps, None) => byteps::format(bits / 8),
+        (Target::Byteps, Some(prefix)) => byteps::format_with_prefix(bits / 8, prefix),
+    })
+}
+
+/// Byte ranges of the fields of a line, separated by `delimiter` or by
+/// whitespaces.
+fn field_spans(line: &str, delimiter: Option<char>) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    match delimiter {
+        Some(delimiter) => {
+            let mut start = 0;
+            for field in line.split(delimiter) {
+                spans.push((start, start + field.len()));
+                start += field.len() + delimiter.len_utf8();
+            }
+        }
+        None => {
+            let mut start = None;
+            for (i, c) in line.char_indices() {
+                match (c.is_whitespace(), start) {
+                    (true, Some(field_start)) => {
+                        spans.push((field_start, i));
+                        start = None;
+                    }
+                    (false, None) => start = Some(i),
+                    _ => {}
+                }
+            }
+            if let Some(field_start) = start {
+                spans.push((field_start, line.len()));
+            }
+        }
+    }
+    spans
+}
+
+fn filter_line(
+    line: &str,
+    fields: &[NonZeroUsize],
+    delimiter: Option<char>,
+    convert: impl Fn(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let spans = field_spans(line, delimiter);
+    if let Some(field) = fields.iter().find(|field| field.get() > spans.len()) {
+        return Err(format!("missing field {field}"));
+    }
+
+    let mut output = String::with_capacity(line.len());
+    let mut end = 0;
+    for (i, &(start, stop)) in spans.iter().enumerate() {
+        if fields.iter().any(|field| field.get() == i + 1) {
+            output.push_str(&line[end..start]);
+            output.push_str(&convert(&line[start..stop])?);
+            end = stop;
+        }
+    }
+    output.push_str(&line[end..]);
+    Ok(output)
+}
+
+fn filter(
+    convert: impl Fn(&str) -> Result<String, String>,
+    fields: &[NonZeroUsize],
+    delimiter: Option<char>,
+    header: usize,
+) -> ExitCode {
+    let mut stdout = io::stdout().lock();
+    let mut code = ExitCode::SUCCESS;
+    for (i, line) in io::stdin().lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("bity: {err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        let output = if i < header {
+            line
+        } else {
+            filter_line(&line, fields, delimiter, &convert).unwrap_or_else(|err| {
+                eprintln!("bity: line {}: {err}", i + 1);
+                code = ExitCode::FAILURE;
+                line
+            })
+        };
+        // Stop silently if the output is closed (like `| head`).
+        if writeln!(stdout, "{output}").is_err() {
+            break;
+        }
+    }
+    code
+}
+
+fn main() -> ExitCode {
+    type Run = Box<dyn Fn(&str) -> Result<String, String>>;
+    let (inputs, run): (_, Run) = match Args::parse().command {
+        Command::Parse { unit, inputs } => (inputs, Box::new(move |input| parse(unit, input))),
+        Command::Format { unit, inputs } => (inputs, Box::new(move |input| format(unit, input))),
+        Command::Convert { to, prefix, inputs } => {
+            (inputs, Box::new(move |input| convert(to, prefix, input)))
+        }
+        Command::Filter {
+            to,
+            from,
+            field,
+            delimiter,
+            header,
+        } => {
+            let convert: Run = match from {
+                Some(unit) => Box::new(move |input| parse(unit, input)),
+                // `--to` is required without `--from`.
+                None => {
+                    let unit = to.unwrap_or(Unit::Si);
+                    Box::new(move |input| format(unit, input))
+                }
+            };
+            return filter(convert, &field, delimiter, header);
+        }
+    };
+
+    let inputs: Box<dyn Iterator<Item = io::Result<String>>> = if inputs.is_empty() {
+        Box::new(io::stdin().lock().lines())
+    } else {
+        Box::new(inputs.into_iter().map(Ok))
+    };
+
+    let mut code = ExitCode::SUCCESS;
+    for input in inputs {
+        let input = match input {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("bity: {err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        match run(&input) {
+            Ok(output) => println!("{output}"),
+            Err(err) => {
+                eprintln!("bity: {err}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use clap::CommandFactory;
+
+    use super::{Args, Target, Unit};
+    use bity::si::Prefix;
+
+    #[test]
+    fn args() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse(Unit::Byte, "1.5kB").unwrap(), "1500");
+        assert_eq!(super::parse(Unit::Dbm, "-20dBm").unwrap(), "-20000");
+        assert_eq!(super::parse(Unit::Duration, "1m30s").unwrap(), "90");
+        assert_eq!(super::parse(Unit::Duration, "250ms").unwrap(), "0.25");
+        assert_eq!(super::parse(Unit::Bps, "12kx").unwrap_err(), r#"invalid unit "kx""#);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(Unit::Bps, "69200").unwrap(), "69.2kb/s");
+        assert_eq!(super::format(Unit::Db, "-1500").unwrap(), "-1.5dB");
+        assert_eq!(super::format(Unit::Duration, "5400").unwrap(), "1h30m");
+        assert_eq!(super::format(Unit::Byte, "1kB").unwrap_err(), r#"invalid integer "1kB""#);
+    }
+
+    #[test]
+    fn filter_line() {
+        let fields = [NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(3).unwrap()];
+        let format = |input: &str| super::format(Unit::Byte, input);
+        assert_eq!(
+            super::filter_line("1500  ./src\t12000000", &fields, None, format).unwrap(),
+            "1.5kB  ./src\t12MB"
+        );
+        assert_eq!(
+            super::filter_line("1500,a,,3", &fields, Some(','), format).unwrap_err(),
+            r#"invalid integer """#
+        );
+        assert_eq!(
+            super::filter_line("1500 a", &fields, None, format).unwrap_err(),
+            "missing field 3"
+        );
+
+        let parse = |input: &str| super::parse(Unit::Bps, input);
+        let fields = [NonZeroUsize::new(2).unwrap()];
+        assert_eq!(
+            super::filter_line("eth0;1.5Gb/s;up", &fields, Some(';'), parse).unwrap(),
+            "eth0;1500000000;up"
+        );
+    }
+
+    #[test]
+    fn convert() {
+        assert_eq!(super::convert(Target::Byte, None, "12Mb").unwrap(), "1.5MB");
+        assert_eq!(super::convert(Target::Bit, None, "1.5MB").unwrap(), "12Mb");
+        assert_eq!(super::convert(Target::Byteps, None, "8kbps").unwrap(), "1kB/s");
+        assert_eq!(super::convert(Target::Bps, Some(Prefix::Kilo), "1MB/s").unwrap(), "8000kb/s");
+        assert_eq!(super::convert(Target::Byte, None, "12b").unwrap(), "1B");
+        assert!(super::convert(Target::Byte, None, "12M").is_err());
+        assert!(super::convert(Target::Byte, None, "12Mb/s").is_err());
+        assert!(super::convert(Target::Bit, None, "12xB").is_err());
+    }
+}
@@ -20,6 +20,7 @@
 //! "bity::bit::deserialize")]` and `#[serde(with = "bity::bit")]` attributes.
 //!
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use indoc::indoc;
 //! use serde::{Deserialize, Serialize};
 //!
@@ -55,9 +56,28 @@
 //!         max-size = "180b"
 //!     "#}
 //! );
+//! # }
 //! ```
 
-use crate::{error::Error, si};
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    error::Error,
+    si::{self, Prefix},
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?[bB]?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["b", "B"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "b".len();
 
 /// Parse a data SI prefixed string into a number.
 ///
@@ -84,6 +104,20 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
     si::parse_with_additional_units(input, &[("b", 1), ("B", 8)])
 }
 
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::bit::parse_const;
+///
+/// assert_eq!(parse_const("1.5kB"), Some(12_000));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    si::parse_const_with_additional_units(input, &[("b", 1), ("B", 8)])
+}
+
 /// Format an integer into a data SI prefixed string (bit oriented).
 ///
 /// This is equivalent to colling `format!("{}b", si::format(input))`.
@@ -99,11 +133,68 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// assert_eq!(format(12_000), "12kb");
 /// ```
 pub fn format(input: u64) -> String {
-    format!("{}b", si::format(input))
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (bit oriented) using a given SI
+/// prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}b", si::format_with_prefix(input,
+/// prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{bit::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300Mb");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push('b');
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::bit::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_234).unwrap();
+/// assert_eq!(output, "1.23kb");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("b")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::bit::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_234).unwrap();
+/// assert_eq!(&buffer[..len], b"1.23kb");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
 }
 
 #[cfg(feature = "serde")]
 crate::impl_serde!(
+    expecting: r#"a data size in bits like "1.5kb", "12Mb" or 1500"#,
+    example: 5_000_000,
     ser:
     /// Serialize a given `u64` into a SI prefixed data string.
     ///
@@ -166,6 +257,9 @@ crate::impl_serde!(
     /// ```
 );
 
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
@@ -212,7 +306,7 @@ mod tests {
 
         // Invalid units.
         assert!(matches!(super::parse("12Q"), Err(Error::InvalidUnit("Q"))));
-        assert!(matches!(super::parse("12kk"), Err(Error::InvalidUnit("kk"))));
+        assert!(matches!(super::parse("12kk"), Err(Error::MultipleUnits("kk"))));
     }
 
     #[test]
@@ -0,0 +1,182 @@
+//! Block (and sector) count conversions for storage provisioning.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{block, byte};
+//!
+//! let block_size = block::parse_size("4KiB").unwrap();
+//! assert_eq!(block::blocks(byte::parse("1GB").unwrap(), block_size), 244_141);
+//! assert_eq!(byte::format(block::bytes_of_blocks(1_000, block_size)), "4.09MB");
+//!
+//! let sector_size = block::parse_size("512-byte sectors").unwrap();
+//! assert_eq!(block::blocks(byte::parse("1MB").unwrap(), sector_size), 1_954);
+//! ```
+
+use crate::{byte, error::check_number, Error};
+
+/// IEC (binary) suffixes accepted by [`parse_size`].
+const IEC_UNITS: &[(&str, u64)] = &[
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+];
+
+/// Suffixes describing a size, accepted by [`parse_size`].
+const SIZE_SUFFIXES: &[&str] = &[
+    "-byte sectors",
+    "-byte sector",
+    "-byte blocks",
+    "-byte block",
+];
+
+/// Parse a block (or sector) size.
+///
+/// Accepted inputs are:
+/// - `512-byte sectors` style inputs (`sector`, `sectors`, `block` or
+///   `blocks`), with an integer number of bytes
+/// - IEC (binary) sizes, with an integer and one of the `KiB`, `MiB`, `GiB`
+///   or `TiB` units
+/// - any input accepted by [`byte::parse`]
+///
+/// # Examples
+/// ```
+/// use bity::block::parse_size;
+///
+/// assert_eq!(parse_size("512-byte sectors").unwrap(), 512);
+/// assert_eq!(parse_size("4096-byte block").unwrap(), 4_096);
+/// assert_eq!(parse_size("4KiB").unwrap(), 4_096);
+/// assert_eq!(parse_size("1MiB").unwrap(), 1_048_576);
+/// assert_eq!(parse_size("4kB").unwrap(), 4_000);
+/// assert_eq!(parse_size("512").unwrap(), 512);
+/// ```
+pub fn parse_size(input: &str) -> Result<u64, Error<'_>> {
+    if !input.is_ascii() {
+        return Err(Error::NotAscii);
+    }
+
+    let input = input.trim();
+    if let Some(value) = SIZE_SUFFIXES
+        .iter()
+        .find_map(|suffix| input.strip_suffix(suffix))
+    {
+        let value = value.trim();
+        check_number(value)?;
+        return value
+            .parse()
+            .map_err(|err| Error::ParseIntError(value, Some(err)));
+    }
+    for &(unit, factor) in IEC_UNITS {
+        if let Some(value) = input.strip_suffix(unit) {
+            let value = value.trim();
+            check_number(value)?;
+            return value
+                .parse::<u64>()
+                .map_err(|err| Error::ParseIntError(value, Some(err)))?
+                .checked_mul(factor)
+                .ok_or(Error::ParseIntError(value, None));
+        }
+    }
+    byte::parse(input)
+}
+
+/// Compute the number of blocks needed to store a number of bytes.
+///
+/// The result is rounded up. A zero block size is treated as an infinite
+/// number of blocks and gives `u64::MAX`, except for zero bytes which always
+/// gives zero blocks.
+///
+/// # Examples
+/// ```
+/// use bity::block::blocks;
+///
+/// assert_eq!(blocks(8_192, 4_096), 2);
+/// assert_eq!(blocks(8_193, 4_096), 3);
+/// assert_eq!(blocks(0, 4_096), 0);
+/// ```
+pub fn blocks(bytes: u64, block_size: u64) -> u64 {
+    match (bytes, block_size) {
+        (0, _) => 0,
+        (_, 0) => u64::MAX,
+        _ => bytes.div_ceil(block_size),
+    }
+}
+
+/// Compute the number of bytes occupied by a number of blocks.
+///
+/// The result saturates to `u64::MAX` instead of overflowing. Use
+/// [`checked_bytes_of_blocks`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use bity::block::bytes_of_blocks;
+///
+/// assert_eq!(bytes_of_blocks(2, 4_096), 8_192);
+/// assert_eq!(bytes_of_blocks(u64::MAX, 512), u64::MAX);
+/// ```
+pub fn bytes_of_blocks(blocks: u64, block_size: u64) -> u64 {
+    blocks.saturating_mul(block_size)
+}
+
+/// Like [`bytes_of_blocks`] but returns `None` if the number of bytes doesn't
+/// fit in an `u64`.
+///
+/// # Examples
+/// ```
+/// use bity::block::checked_bytes_of_blocks;
+///
+/// assert_eq!(checked_bytes_of_blocks(2, 4_096), Some(8_192));
+/// assert_eq!(checked_bytes_of_blocks(u64::MAX, 512), None);
+/// ```
+pub fn checked_bytes_of_blocks(blocks: u64, block_size: u64) -> Option<u64> {
+    blocks.checked_mul(block_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse_size() {
+        assert_eq!(super::parse_size("512-byte sectors").unwrap(), 512);
+        assert_eq!(super::parse_size(" 512 -byte sector ").unwrap(), 512);
+        assert_eq!(super::parse_size("4096-byte blocks").unwrap(), 4_096);
+        assert_eq!(super::parse_size("4KiB").unwrap(), 4_096);
+        assert_eq!(super::parse_size("4 KiB").unwrap(), 4_096);
+        assert_eq!(super::parse_size("2GiB").unwrap(), 2_147_483_648);
+        assert_eq!(super::parse_size("1TiB").unwrap(), 1_099_511_627_776);
+        assert_eq!(super::parse_size("4.5kB").unwrap(), 4_500);
+        assert_eq!(super::parse_size("4096").unwrap(), 4_096);
+
+        assert!(matches!(super::parse_size("1.5KiB"), Err(Error::ParseIntError("1.5", Some(_)))));
+        assert!(matches!(
+            super::parse_size("4kB-byte sectors"),
+            Err(Error::TrailingGarbage("kB"))
+        ));
+        assert!(matches!(
+            super::parse_size("18446744073709551615TiB"),
+            Err(Error::ParseIntError(_, None))
+        ));
+        assert!(matches!(super::parse_size("4kiB"), Err(Error::InvalidUnit("kiB"))));
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(super::blocks(1, 512), 1);
+        assert_eq!(super::blocks(512, 512), 1);
+        assert_eq!(super::blocks(513, 512), 2);
+        assert_eq!(super::blocks(u64::MAX, 1), u64::MAX);
+        assert_eq!(super::blocks(0, 0), 0);
+        assert_eq!(super::blocks(1, 0), u64::MAX);
+    }
+
+    #[test]
+    fn bytes_of_blocks() {
+        assert_eq!(super::bytes_of_blocks(3, 512), 1_536);
+        assert_eq!(super::bytes_of_blocks(0, 512), 0);
+        assert_eq!(super::bytes_of_blocks(u64::MAX, 2), u64::MAX);
+        assert_eq!(super::checked_bytes_of_blocks(u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(super::checked_bytes_of_blocks(u64::MAX, 2), None);
+    }
+}
@@ -0,0 +1,325 @@
+//! SI prefixed average packet size (bytes per packet) parsing and formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::bpp::{format, parse};
+//!
+//! assert_eq!(parse("512B/p").unwrap(), 512);
+//! assert_eq!(parse("1.2kB/packet").unwrap(), 1_200);
+//! assert_eq!(parse("64B").unwrap(), 64);
+//!
+//! assert_eq!(format(512), "512B/p");
+//! assert_eq!(format(1_500), "1.5kB/p");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::bpp::serialize")]`, `#[serde(deserialize_with =
+//! "bity::bpp::deserialize")]` and `#[serde(with = "bity::bpp")]` attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::bpp")]
+//!     average_packet_size: u64,
+//!     #[serde(with = "bity::bpp")]
+//!     mtu: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         average-packet-size = "512B/p"
+//!         mtu = 1_500
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         average_packet_size: 512,
+//!         mtu: 1_500,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         average_packet_size: 512,
+//!         mtu: 1_500,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         average-packet-size = "512B/p"
+//!         mtu = "1.5kB/p"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    byte,
+    error::Error,
+    si::{self, Prefix},
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str =
+    r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?B?\s*(?:/packet|/pkt|/p)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["B/p", "B/pkt", "B/packet"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "B/p".len();
+
+/// Parse an average packet size SI prefixed string into a number of bytes.
+///
+/// The `/p`, `/pkt` and `/packet` suffixes are accepted and stripped before
+/// calling [`byte::parse`], refer to it to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::bpp::parse;
+///
+/// assert_eq!(parse("512B/p").unwrap(), 512);
+/// assert_eq!(parse("1.2kB/pkt").unwrap(), 1_200);
+/// assert_eq!(parse("1.2kB/packet").unwrap(), 1_200);
+/// assert_eq!(parse("512").unwrap(), 512);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    let input = input.trim();
+    byte::parse(
+        ["/packet", "/pkt", "/p"]
+            .iter()
+            .find_map(|suffix| input.strip_suffix(suffix))
+            .unwrap_or(input),
+    )
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::bpp::parse_const;
+///
+/// assert_eq!(parse_const("1.5kB/p"), Some(1_500));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    let input = input.trim_ascii();
+    let suffixes = ["/packet", "/pkt", "/p"];
+    let mut i = 0;
+    while i < suffixes.len() {
+        if let Some(stripped) = crate::strip_suffix_const(input, suffixes[i]) {
+            return byte::parse_const(stripped);
+        }
+        i += 1;
+    }
+    byte::parse_const(input)
+}
+
+/// Format an integer into an average packet size SI prefixed string.
+///
+/// This is equivalent to colling `format!("{}/p", byte::format(input))`.
+///
+/// Refer to [`si::format`](crate::si::format) and [`byte::format`] to learn
+/// the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::bpp::format;
+///
+/// assert_eq!(format(64), "64B/p");
+/// assert_eq!(format(1_234), "1.23kB/p");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (bytes per packet) using a given
+/// SI prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}/p",
+/// byte::format_with_prefix(input, prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{bpp::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(1_500, Prefix::Kilo), "1.5kB/p");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("B/p");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::bpp::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_500).unwrap();
+/// assert_eq!(output, "1.5kB/p");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    byte::format_into(output, input)?;
+    output.write_str("/p")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::bpp::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_500).unwrap();
+/// assert_eq!(&buffer[..len], b"1.5kB/p");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Compute the average packet size (in bytes) of a data-rate (in bytes per
+/// second) and a packet-rate.
+///
+/// The result is rounded to the nearest integer. A zero packet-rate is treated
+/// as an infinite packet size and gives `u64::MAX`, except for a zero
+/// data-rate which always gives a zero packet size.
+///
+/// This is the inverse of [`pps::to_bandwidth`](crate::pps::to_bandwidth).
+///
+/// # Examples
+/// ```
+/// use bity::{bpp, byteps, pps};
+///
+/// let size = bpp::from_rates(byteps::parse("95.23MB/s").unwrap(), pps::parse("1.488Mpps").unwrap());
+/// assert_eq!(bpp::format(size), "64B/p");
+/// assert_eq!(bpp::from_rates(1_000, 0), u64::MAX);
+/// ```
+pub fn from_rates(bandwidth: u64, packet_rate: u64) -> u64 {
+    crate::pps::from_bandwidth(bandwidth, packet_rate)
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a packet size like "512B/p", "1.2kB/packet" or 1500"#,
+    example: 1_500,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed average packet size string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::bpp::serialize")]` and `#[serde(with = "bity::bpp")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::bpp::serialize")]
+    ///     average_packet_size: u64,
+    ///     #[serde(with = "bity::bpp")]
+    ///     mtu: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         average_packet_size: 512,
+    ///         mtu: 1_500,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         average-packet-size = "512B/p"
+    ///         mtu = "1.5kB/p"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed average packet size string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::bpp::deserialize")]` and `#[serde(with = "bity::bpp")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::bpp::deserialize")]
+    ///     average_packet_size: u64,
+    ///     #[serde(with = "bity::bpp")]
+    ///     mtu: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             average-packet-size = "512B/p"
+    ///             mtu = 1_500
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         average_packet_size: 512,
+    ///         mtu: 1_500,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("512B/p").unwrap(), 512);
+        assert_eq!(super::parse(" 512 B/p ").unwrap(), 512);
+        assert_eq!(super::parse("1.2kB/pkt").unwrap(), 1_200);
+        assert_eq!(super::parse("1.2kB/packet").unwrap(), 1_200);
+        assert_eq!(super::parse("1.2kB").unwrap(), 1_200);
+        assert_eq!(super::parse("12").unwrap(), 12);
+
+        assert!(matches!(super::parse("12b/p"), Err(Error::InvalidUnit("b"))));
+        assert!(matches!(super::parse("12B/s"), Err(Error::InvalidUnit("B/s"))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123B/p");
+        assert_eq!(super::format(1_234), "1.23kB/p");
+        assert_eq!(super::format(9_000), "9kB/p");
+    }
+
+    #[test]
+    fn from_rates() {
+        assert_eq!(super::from_rates(1_500_000, 1_000), 1_500);
+        assert_eq!(super::from_rates(1_000, 3), 333);
+        assert_eq!(super::from_rates(0, 0), 0);
+        assert_eq!(super::from_rates(1, 0), u64::MAX);
+    }
+}
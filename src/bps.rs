@@ -22,6 +22,7 @@
 //! "bity::bps::deserialize")]` and `#[serde(with = "bity::bps")]` attributes.
 //!
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use indoc::indoc;
 //! use serde::{Deserialize, Serialize};
 //!
@@ -63,9 +64,31 @@
 //!         highest = "12kb/s"
 //!     "#}
 //! );
+//! # }
 //! ```
 
-use crate::{bit, error::Error};
+use alloc::{format, string::String};
+use core::{fmt, time::Duration};
+
+use crate::{
+    bit,
+    error::Error,
+    si::{self, Prefix},
+    IntoDuration, Period,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str =
+    r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?[bB]?\s*(?:/s|ps)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["b/s", "bps", "B/s", "Bps"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "b/s".len();
 
 /// Parse a data-rate SI prefixed string into a number.
 ///
@@ -89,6 +112,20 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
     bit::parse(crate::strip_per_second(input))
 }
 
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::bps::parse_const;
+///
+/// assert_eq!(parse_const("1.5Mb/s"), Some(1_500_000));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    bit::parse_const(crate::strip_per_second_const(input))
+}
+
 /// Format an integer into a data-rate SI prefixed string (bit oriented).
 ///
 /// This is equivalent to colling `format!("{}/s", bit::format(input))`.
@@ -105,11 +142,239 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// assert_eq!(format(12_000), "12kb/s");
 /// ```
 pub fn format(input: u64) -> String {
-    format!("{}/s", bit::format(input))
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (data-rate, bit oriented) using
+/// a given SI prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}/s",
+/// bit::format_with_prefix(input, prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{bps::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300Mb/s");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("b/s");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::bps::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 12_300).unwrap();
+/// assert_eq!(output, "12.3kb/s");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    bit::format_into(output, input)?;
+    output.write_str("/s")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::bps::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 12_300).unwrap();
+/// assert_eq!(&buffer[..len], b"12.3kb/s");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Compute the data-rate (per-second) of a volume of bits transferred over a
+/// given duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. A zero duration is treated as an infinite rate,
+/// except for a zero volume which always gives a zero rate.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bit, bps};
+///
+/// let rate = bps::from_volume(bit::parse("12Gb").unwrap(), Duration::from_secs(95));
+/// assert_eq!(rate, 126_315_789);
+/// assert_eq!(bps::format(rate), "126.31Mb/s");
+/// ```
+pub fn from_volume(volume: u64, duration: impl IntoDuration) -> u64 {
+    crate::rate::from_volume(volume, duration.into_duration())
+}
+
+/// Compute the volume of bits transferred at a given data-rate over a given
+/// duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. Use [`checked_volume_over`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bit, bps};
+///
+/// let volume = bps::volume_over(bps::parse("50Mb/s").unwrap(), Duration::from_secs(6 * 3_600));
+/// assert_eq!(bit::format(volume), "1.08Tb");
+/// assert_eq!(bps::volume_over(u64::MAX, Duration::from_secs(2)), u64::MAX);
+/// ```
+pub fn volume_over(rate: u64, duration: impl IntoDuration) -> u64 {
+    checked_volume_over(rate, duration).unwrap_or(u64::MAX)
+}
+
+/// Like [`volume_over`] but returns `None` if the volume doesn't fit in an
+/// `u64`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::bps::checked_volume_over;
+///
+/// assert_eq!(checked_volume_over(1_000, Duration::from_millis(1_500)), Some(1_500));
+/// assert_eq!(checked_volume_over(u64::MAX, Duration::from_secs(2)), None);
+/// ```
+pub fn checked_volume_over(rate: u64, duration: impl IntoDuration) -> Option<u64> {
+    crate::rate::checked_volume_over(rate, duration.into_duration())
+}
+
+/// Compute the time needed to transfer a volume of bits at a given data-rate.
+///
+/// The result is rounded to the nearest nanosecond. A zero rate is treated as
+/// an infinite transfer time and gives `Duration::MAX`, except for a zero
+/// volume which always gives `Duration::ZERO`.
+///
+/// # Examples
+/// ```
+/// use bity::{bit, bps};
+///
+/// let time = bps::time_to_transfer(bit::parse("100Gb").unwrap(), bps::parse("42.9Mb/s").unwrap());
+/// assert_eq!(time.as_secs(), 2_331);
+/// ```
+pub fn time_to_transfer(volume: u64, rate: u64) -> Duration {
+    crate::rate::time_to_transfer(volume, rate)
+}
+
+/// Format the time needed to transfer a volume of bits at a given
+/// data-rate, alongside the volume and the rate.
+///
+/// The duration is formatted in a compact humantime-like style, truncated to
+/// the second.
+///
+/// # Examples
+/// ```
+/// use bity::bps::format_time_to_transfer;
+///
+/// assert_eq!(
+///     format_time_to_transfer(100_000_000_000, 42_900_000),
+///     "100Gb in 38m51s at 42.9Mb/s"
+/// );
+/// ```
+#[cfg(feature = "duration")]
+pub fn format_time_to_transfer(volume: u64, rate: u64) -> String {
+    format!(
+        "{} in {} at {}",
+        bit::format(volume),
+        crate::rate::format_duration(time_to_transfer(volume, rate)),
+        format(rate)
+    )
+}
+
+/// Compute the volume of bits transferred over a whole [`Period`] at a
+/// given sustained data-rate.
+///
+/// This is equivalent to calling `volume_over(rate, period.duration())`, refer
+/// to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{bit, bps, Period};
+///
+/// assert_eq!(bit::format(bps::volume_per(512_000, Period::Month)), "1.32Tb");
+/// ```
+pub fn volume_per(rate: u64, period: Period) -> u64 {
+    volume_over(rate, period.duration())
+}
+
+/// Compute the sustained data-rate (per-second) needed to transfer a volume of
+/// bits over a whole [`Period`].
+///
+/// This is equivalent to calling `from_volume(volume, period.duration())`,
+/// refer to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{bit, bps, Period};
+///
+/// assert_eq!(bps::from_volume_per(bit::parse("3Gb").unwrap(), Period::Day), 34_722);
+/// ```
+pub fn from_volume_per(volume: u64, period: Period) -> u64 {
+    from_volume(volume, period.duration())
+}
+
+/// Format a per-second data-rate into a SI prefixed string using a different
+/// time base.
+///
+/// This is equivalent to calling `format!("{}/{}",
+/// bit::format(volume_per(input, period)), period.symbol())`.
+///
+/// # Examples
+/// ```
+/// use bity::{bps::format_per, Period};
+///
+/// assert_eq!(format_per(1_000_000, Period::Second), "1Mb/s");
+/// assert_eq!(format_per(1_000_000, Period::Hour), "3.6Gb/h");
+/// assert_eq!(format_per(1_000_000, Period::Day), "86.4Gb/d");
+/// ```
+pub fn format_per(input: u64, period: Period) -> String {
+    format!("{}/{}", bit::format(volume_per(input, period)), period.symbol())
+}
+
+/// Compute the bandwidth-delay product (in bytes) of a data-rate (in bits per
+/// second) and a round-trip time.
+///
+/// The result is rounded up to the next byte and saturates to `u64::MAX`
+/// instead of overflowing.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bps, byte};
+///
+/// let bdp = bps::bandwidth_delay_product(bps::parse("10Gb/s").unwrap(), Duration::from_millis(80));
+/// assert_eq!(byte::format(bdp), "100MB");
+/// ```
+pub fn bandwidth_delay_product(rate: u64, rtt: impl IntoDuration) -> u64 {
+    u128::from(rate)
+        .checked_mul(rtt.into_duration().as_nanos())
+        .and_then(|bits| u64::try_from(bits.div_ceil(1_000_000_000 * 8)).ok())
+        .unwrap_or(u64::MAX)
 }
 
 #[cfg(feature = "serde")]
 crate::impl_serde!(
+    expecting: r#"a data-rate like "1.5kb/s", "12Mbps" or 1500"#,
+    example: 5_000_000,
     ser:
     /// Serialize a given `u64` into a SI prefixed data-rate string.
     ///
@@ -176,6 +441,9 @@ crate::impl_serde!(
     /// ```
 );
 
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -195,4 +463,20 @@ mod tests {
         assert_eq!(super::format(1_234), "1.23kb/s");
         assert_eq!(super::format(12_000), "12kb/s");
     }
+
+    #[test]
+    fn bandwidth_delay_product() {
+        use std::time::Duration;
+
+        assert_eq!(
+            super::bandwidth_delay_product(10_000_000_000, Duration::from_millis(80)),
+            100_000_000
+        );
+        assert_eq!(super::bandwidth_delay_product(1_000_000, Duration::from_millis(1)), 125);
+        assert_eq!(super::bandwidth_delay_product(1, Duration::from_secs(1)), 1); // Round up.
+        assert_eq!(super::bandwidth_delay_product(1_000_000, Duration::ZERO), 0);
+        assert_eq!(super::bandwidth_delay_product(u64::MAX, Duration::from_secs(8)), u64::MAX);
+        assert_eq!(super::bandwidth_delay_product(u64::MAX, Duration::from_secs(16)), u64::MAX);
+        assert_eq!(super::bandwidth_delay_product(u64::MAX, Duration::MAX), u64::MAX);
+    }
 }
@@ -0,0 +1,253 @@
+//! Histogram bucket boundaries, with human readable labels.
+//!
+//! Boundaries are rounded to their formatted label, so histograms built from
+//! the same parameters share identical boundaries and labels, ready to be used
+//! as Prometheus `le` labels. Values are classified into named ranges of
+//! buckets (like `1MB-10MB`) using [`bucket_of`], to group them by magnitude.
+//!
+//! # Examples
+//!
+//! ```
+//! let buckets = bity::buckets::exponential("1kB", "1GB", 10).unwrap();
+//!
+//! assert_eq!(
+//!     buckets.bounds(),
+//!     [1_000, 10_000, 100_000, 1_000_000, 10_000_000, 100_000_000, 1_000_000_000]
+//! );
+//! assert_eq!(
+//!     buckets.labels(),
+//!     ["1kB", "10kB", "100kB", "1MB", "10MB", "100MB", "1GB"]
+//! );
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use crate::{byte, error::Error};
+
+/// Histogram bucket boundaries (inclusive upper bounds) and their labels,
+/// created by [`exponential`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Buckets {
+    bounds: Vec<u64>,
+    labels: Vec<String>,
+}
+
+impl Buckets {
+    /// Create buckets from upper bounds, sorted and deduplicated, labeled
+    /// using [`byte::format`].
+    pub fn new(bounds: impl IntoIterator<Item = u64>) -> Self {
+        let mut bounds = bounds.into_iter().collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+        let labels = bounds.iter().map(|&bound| byte::format(bound)).collect();
+        Self { bounds, labels }
+    }
+
+    /// Upper bounds of the buckets, in ascending order.
+    pub fn bounds(&self) -> &[u64] {
+        &self.bounds
+    }
+
+    /// Labels of the upper bounds (like `1MB`), in ascending order.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Upper bounds of the buckets as floats, as expected by most metrics
+    /// libraries.
+    ///
+    /// # Examples
+    /// ```
+    /// let buckets = bity::buckets::exponential("1MB", "4MB", 2).unwrap();
+    ///
+    /// assert_eq!(buckets.to_f64(), [1e6, 2e6, 4e6]);
+    /// ```
+    pub fn to_f64(&self) -> Vec<f64> {
+        self.bounds.iter().map(|&bound| bound as f64).collect()
+    }
+
+    /// Iterate over the upper bounds and their labels.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.bounds
+            .iter()
+            .copied()
+            .zip(self.labels.iter().map(String::as_str))
+    }
+
+    /// Range of bounds containing `value`, refer to [`bucket_of`].
+    pub fn bucket_of(&self, value: u64) -> BucketLabel<'_> {
+        let index = self.bounds.partition_point(|&bound| bound < value);
+        BucketLabel {
+            index,
+            lower: index
+                .checked_sub(1)
+                .map(|index| self.labels[index].as_str()),
+            upper: self.labels.get(index).map(String::as_str),
+        }
+    }
+}
+
+/// Range of [`Buckets`] bounds containing a value, created by [`bucket_of`].
+///
+/// It's displayed as `<lower>-<upper>` (like `1MB-10MB`), `<=<first>` up to
+/// the first bound, or `><last>` above the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BucketLabel<'a> {
+    index: usize,
+    lower: Option<&'a str>,
+    upper: Option<&'a str>,
+}
+
+impl<'a> BucketLabel<'a> {
+    /// Index of the range, from `0` (up to the first bound) to the number of
+    /// bounds (above the last bound).
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Label of the exclusive lower bound, if the value is above the first
+    /// bound.
+    pub fn lower(&self) -> Option<&'a str> {
+        self.lower
+    }
+
+    /// Label of the inclusive upper bound, if the value isn't above the last
+    /// bound.
+    pub fn upper(&self) -> Option<&'a str> {
+        self.upper
+    }
+}
+
+impl Display for BucketLabel<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) => write!(f, "{lower}-{upper}"),
+            (None, Some(upper)) => write!(f, "<={upper}"),
+            (Some(lower), None) => write!(f, ">{lower}"),
+            (None, None) => f.write_str("*"),
+        }
+    }
+}
+
+/// Create buckets from `start` to `end` (byte strings, like `1kB`), each
+/// bound being `factor` times the previous one.
+///
+/// Bounds are rounded down to their label, and `end` is always the last
+/// bound. `factor` is at least `2`.
+///
+/// # Examples
+/// ```
+/// let buckets = bity::buckets::exponential("1kB", "1MB", 4).unwrap();
+///
+/// assert_eq!(
+///     buckets.labels(),
+///     ["1kB", "4kB", "16kB", "64kB", "256kB", "1MB"]
+/// );
+/// assert_eq!(buckets.bounds()[4], 256_000);
+///
+/// // Precision is limited by the formatting.
+/// let buckets = bity::buckets::exponential("1", "10kB", 3).unwrap();
+/// assert_eq!(buckets.labels()[7..], ["2.18kB", "6.54kB", "10kB"]);
+///
+/// assert!(bity::buckets::exponential("1kB", "1XB", 10).is_err());
+/// ```
+pub fn exponential<'a>(start: &'a str, end: &'a str, factor: u64) -> Result<Buckets, Error<'a>> {
+    let (start, end) = (byte::parse(start)?, byte::parse(end)?);
+    let factor = factor.max(2);
+    let mut bounds = Vec::new();
+    let last = byte::format(end);
+    let mut bound = start;
+    while bound < end {
+        bounds.push(bound);
+        let label = byte::format(bound.max(1).saturating_mul(factor));
+        // Near `u64::MAX`, the rounded bound stops advancing and shares the
+        // label of `end`.
+        if label == last {
+            break;
+        }
+        // Formatted values are always parsed back.
+        bound = byte::parse(&label).unwrap_or(end);
+    }
+    bounds.push(end);
+    Ok(Buckets::new(bounds))
+}
+
+/// Classify `value` into the range of `buckets` bounds containing it, for
+/// grouping values by magnitude.
+///
+/// Ranges exclude their lower bound and include their upper bound, like
+/// Prometheus `le` buckets.
+///
+/// # Examples
+/// ```
+/// use bity::buckets::{bucket_of, exponential};
+///
+/// let buckets = exponential("1kB", "1GB", 10).unwrap();
+///
+/// assert_eq!(bucket_of(5_200_000, &buckets).to_string(), "1MB-10MB");
+/// assert_eq!(bucket_of(1_000_000, &buckets).to_string(), "100kB-1MB");
+/// assert_eq!(bucket_of(512, &buckets).to_string(), "<=1kB");
+/// assert_eq!(bucket_of(5_000_000_000, &buckets).to_string(), ">1GB");
+/// ```
+pub fn bucket_of(value: u64, buckets: &Buckets) -> BucketLabel<'_> {
+    buckets.bucket_of(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Buckets;
+
+    #[test]
+    fn exponential() {
+        let buckets = super::exponential("1kB", "1GB", 10).unwrap();
+        assert_eq!(buckets.bounds().len(), 7);
+        assert!(buckets
+            .iter()
+            .all(|(bound, label)| crate::byte::parse(label).ok() == Some(bound)));
+
+        assert_eq!(super::exponential("0", "4", 0).unwrap().bounds(), [0, 2, 4]);
+        assert_eq!(super::exponential("1kB", "1kB", 2).unwrap().bounds(), [1_000]);
+        assert_eq!(super::exponential("1MB", "1kB", 2).unwrap().bounds(), [1_000]);
+        assert_eq!(
+            super::exponential("1EB", "18EB", 10).unwrap().labels(),
+            ["1EB", "10EB", "18EB"]
+        );
+        assert_eq!(
+            super::exponential("10EB", "18446744073709551615", 10)
+                .unwrap()
+                .bounds(),
+            [10_000_000_000_000_000_000, u64::MAX]
+        );
+        assert_eq!(
+            super::exponential("1EB", &u64::MAX.to_string(), 2)
+                .unwrap()
+                .labels(),
+            ["1EB", "2EB", "4EB", "8EB", "16EB", "18.44EB"]
+        );
+        assert!(super::exponential("-1kB", "1GB", 10).is_err());
+    }
+
+    #[test]
+    fn new() {
+        let buckets = Buckets::new([1_500, 100, 1_500]);
+        assert_eq!(buckets.bounds(), [100, 1_500]);
+        assert_eq!(buckets.labels(), ["100B", "1.5kB"]);
+    }
+
+    #[test]
+    fn bucket_of() {
+        let buckets = Buckets::new([1_000, 1_000_000]);
+        let bucket = super::bucket_of(1_000, &buckets);
+        assert_eq!((bucket.index(), bucket.lower(), bucket.upper()), (0, None, Some("1kB")));
+        assert_eq!(bucket.to_string(), "<=1kB");
+        let bucket = super::bucket_of(1_001, &buckets);
+        assert_eq!((bucket.index(), bucket.lower(), bucket.upper()), (1, Some("1kB"), Some("1MB")));
+        assert_eq!(bucket.to_string(), "1kB-1MB");
+        assert_eq!(super::bucket_of(1_000_000, &buckets).index(), 1);
+        let bucket = super::bucket_of(1_000_001, &buckets);
+        assert_eq!((bucket.index(), bucket.lower(), bucket.upper()), (2, Some("1MB"), None));
+        assert_eq!(bucket.to_string(), ">1MB");
+        assert_eq!(super::bucket_of(0, &Buckets::new([])).to_string(), "*");
+    }
+}
@@ -0,0 +1,137 @@
+//! Parsing of many inputs at once, like the size tokens of log lines.
+//!
+//! With the `rayon` feature, the `par_*` functions parse the inputs in
+//! parallel, using the global [`rayon`](https://docs.rs/rayon) thread pool.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::bulk;
+//!
+//! let log = "GET /index.html 12kB\nGET /video.mp4 1.5GB\nGET /robots.txt 12x";
+//! let sizes = bulk::parse_bytes(log.lines().filter_map(|line| line.split(' ').nth(2)));
+//! assert_eq!(sizes[0].as_ref().ok(), Some(&12_000));
+//! assert_eq!(sizes[1].as_ref().ok(), Some(&1_500_000_000));
+//! assert!(sizes[2].is_err());
+//! ```
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+#[cfg(feature = "byte")]
+use crate::byte;
+use crate::Error;
+
+/// Parse data sizes (byte oriented), keeping the results in the order of the
+/// inputs.
+///
+/// Refer to [`byte::parse`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::bulk::parse_bytes;
+///
+/// let sizes = parse_bytes(["1.5kB", "12MB", "3"]);
+/// assert_eq!(sizes.into_iter().collect::<Result<Vec<_>, _>>().unwrap(), [1_500, 12_000_000, 3]);
+/// ```
+#[cfg(feature = "byte")]
+pub fn parse_bytes<'a>(inputs: impl IntoIterator<Item = &'a str>) -> Vec<Result<u64, Error<'a>>> {
+    parse_with(inputs, byte::parse)
+}
+
+/// Parse inputs using any parsing function (like [`bps::parse`](crate::bps::parse)),
+/// keeping the results in the order of the inputs.
+///
+/// # Examples
+/// ```
+/// use bity::{bps, bulk::parse_with};
+///
+/// let rates = parse_with(["1.5kb/s", "12Mbps"], bps::parse);
+/// assert_eq!(rates[1].as_ref().ok(), Some(&12_000_000));
+/// ```
+pub fn parse_with<'a, T>(
+    inputs: impl IntoIterator<Item = &'a str>,
+    parse: impl Fn(&'a str) -> Result<T, Error<'a>>,
+) -> Vec<Result<T, Error<'a>>> {
+    inputs.into_iter().map(parse).collect()
+}
+
+/// Parallel version of [`parse_bytes`], enabled by the `rayon` feature.
+///
+/// # Examples
+/// ```
+/// use bity::bulk::par_parse_bytes;
+///
+/// let inputs = vec!["1.5kB"; 10_000];
+/// let sizes = par_parse_bytes(inputs);
+/// assert!(sizes.iter().all(|size| size.as_ref().ok() == Some(&1_500)));
+/// ```
+#[cfg(all(feature = "rayon", feature = "byte"))]
+pub fn par_parse_bytes<'a>(
+    inputs: impl IntoParallelIterator<Item = &'a str>,
+) -> Vec<Result<u64, Error<'a>>> {
+    par_parse_with(inputs, byte::parse)
+}
+
+/// Parallel version of [`parse_with`], enabled by the `rayon` feature.
+///
+/// # Examples
+/// ```
+/// use bity::{bulk::par_parse_with, duration};
+///
+/// let timeouts = par_parse_with(vec!["1m30s", "250ms"], duration::parse);
+/// assert_eq!(timeouts[0].as_ref().unwrap().as_secs(), 90);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_parse_with<'a, T: Send>(
+    inputs: impl IntoParallelIterator<Item = &'a str>,
+    parse: impl Fn(&'a str) -> Result<T, Error<'a>> + Sync + Send,
+) -> Vec<Result<T, Error<'a>>> {
+    inputs.into_par_iter().map(parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "byte")]
+    use crate::Error;
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn parse_bytes() {
+        let sizes = super::parse_bytes(["1.5kB", "", "12kx", "3"]);
+        assert!(matches!(sizes[0], Ok(1_500)));
+        assert!(matches!(sizes[1], Err(Error::EmptyInput)));
+        assert!(matches!(sizes[2], Err(Error::InvalidUnit("kx"))));
+        assert!(matches!(sizes[3], Ok(3)));
+        assert!(super::parse_bytes([]).is_empty());
+    }
+
+    #[cfg(feature = "pps")]
+    #[test]
+    fn parse_with() {
+        let inputs = ["1.5kpps", "12pps"];
+        let rates = super::parse_with(inputs.iter().copied(), crate::pps::parse);
+        assert!(matches!(rates[..], [Ok(1_500), Ok(12)]));
+    }
+
+    #[cfg(all(feature = "rayon", feature = "byte"))]
+    #[test]
+    fn par_parse() {
+        let inputs = (0..10_000).map(|i| format!("{i}kB")).collect::<Vec<_>>();
+        let sizes = super::par_parse_bytes(inputs.iter().map(String::as_str).collect::<Vec<_>>());
+        assert_eq!(sizes.len(), 10_000);
+        assert!(sizes
+            .into_iter()
+            .enumerate()
+            .all(|(i, size)| size.ok() == Some(i as u64 * 1_000)));
+        assert_eq!(
+            super::par_parse_with(vec!["12x"], crate::byte::parse)[0]
+                .as_ref()
+                .unwrap_err()
+                .kind(),
+            crate::ErrorKind::InvalidUnit
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! SI prefixed data parsing and formatting (byte oriented).
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::byte::{format, parse};
+//!
+//! assert_eq!(parse("12.3kB").unwrap(), 12_300);
+//! assert_eq!(parse("0.12kB").unwrap(), 120);
+//!
+//! assert_eq!(format(1_234), "1.23kB");
+//! assert_eq!(format(123_456), "123.45kB");
+//! assert_eq!(format(12_345_678), "12.34MB");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::byte::serialize")]`, `#[serde(deserialize_with =
+//! "bity::byte::deserialize")]` and `#[serde(with = "bity::byte")]`
+//! attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::byte")]
+//!     disk_quota: u64,
+//!     #[serde(with = "bity::byte")]
+//!     max_upload: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         disk-quota = "1.5kB"
+//!         max-upload = 180
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         disk_quota: 1_500,
+//!         max_upload: 180,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         disk_quota: 1_500,
+//!         max_upload: 180,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         disk-quota = "1.5kB"
+//!         max-upload = "180B"
+//!     "#}
+//! );
+//! # }
+//! ```
+//!
+//! `Option<u64>`, `Vec<u64>`, maps of `u64` (like `HashMap<String, u64>`) and
+//! maps keyed by `u64` (like `BTreeMap<u64, String>`) fields can use the
+//! `bity::byte::option`, `bity::byte::vec`, `bity::byte::map_values` and
+//! `bity::byte::map_keys` submodules (available for every unit module). The
+//! `option` one also maps the `"unlimited"` string to `None`. The
+//! `bity::byte::as_int` submodule always serializes plain integers, while still
+//! accepting SI prefixed strings, and the `bity::byte::strict` one rejects bare
+//! integers (which could be mistaken for bits). The `bity::byte::structured`
+//! submodule serializes `{ value, unit }` maps (like `{ "value": 88.1, "unit":
+//! "TB" }`) instead of strings, such maps being accepted by every deserializer.
+//! Bounds can be enforced with
+//! `#[serde(with = "bity::byte::Bounded::<1_000, 1_000_000_000>")]`, and
+//! `#[serde(with = "bity::byte::Quantized::<2>")]` rounds values to two
+//! significant digits when serializing (lossy, `5_099_876` becoming `5.1MB`).
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use std::collections::BTreeMap;
+//!
+//! use indoc::indoc;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(default, with = "bity::byte::option")]
+//!     disk_quota: Option<u64>,
+//!     #[serde(default, with = "bity::byte::option")]
+//!     max_upload: Option<u64>,
+//!     #[serde(default, with = "bity::byte::option")]
+//!     max_download: Option<u64>,
+//!     #[serde(with = "bity::byte::vec")]
+//!     tiers: Vec<u64>,
+//!     #[serde(with = "bity::byte::map_keys")]
+//!     buckets: BTreeMap<u64, String>,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         disk-quota = "1.5kB"
+//!         max-upload = "unlimited"
+//!         tiers = ["1GB", "10GB", "100GB"]
+//!
+//!         [buckets]
+//!         "1kB" = "small"
+//!         "64kB" = "large"
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         disk_quota: Some(1_500),
+//!         max_upload: None,
+//!         max_download: None,
+//!         tiers: vec![1_000_000_000, 10_000_000_000, 100_000_000_000],
+//!         buckets: BTreeMap::from([(1_000, "small".to_owned()), (64_000, "large".to_owned())]),
+//!     }
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    si::{self, Prefix},
+    Error,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?B?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["B"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "B".len();
+
+/// Parse a data SI prefixed string into a number.
+///
+/// This is equivalent to colling `si::parse_with_additional_units(input,
+/// &[("B", 1)])`.
+///
+/// Refer to [`si::parse`] and [`si::parse_with_additional_units`] to learn the
+/// rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::byte::parse;
+///
+/// assert_eq!(parse("12B").unwrap(), 12);
+/// assert_eq!(parse("12.345kB").unwrap(), 12_345);
+/// assert_eq!(parse("12").unwrap(), 12);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    si::parse_with_additional_units(input, &[("B", 1)])
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::byte::parse_const;
+///
+/// assert_eq!(parse_const("1.5GB"), Some(1_500_000_000));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    si::parse_const_with_additional_units(input, &[("B", 1)])
+}
+
+/// Format an integer into a data SI prefixed string (byte oriented).
+///
+/// This is equivalent to colling `format!("{}B", si::format(input))`.
+///
+/// Refer to [`si::format`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::byte::format;
+///
+/// assert_eq!(format(12), "12B");
+/// assert_eq!(format(1_234), "1.23kB");
+/// assert_eq!(format(12_000), "12kB");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (byte oriented) using a given SI
+/// prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}B", si::format_with_prefix(input,
+/// prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{byte::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300MB");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push('B');
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::byte::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_234).unwrap();
+/// assert_eq!(output, "1.23kB");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("B")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::byte::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_234).unwrap();
+/// assert_eq!(&buffer[..len], b"1.23kB");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a data size in bytes like "1.5kB", "12MB" or 1500"#,
+    example: 5_000_000,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed data string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::byte::serialize")]` and `#[serde(with = "bity::byte")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::byte::serialize")]
+    ///     disk_quota: u64,
+    ///     #[serde(with = "bity::byte")]
+    ///     max_upload: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         disk_quota: 1_500,
+    ///         max_upload: 180,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         disk-quota = "1.5kB"
+    ///         max-upload = "180B"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed data string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::byte::deserialize")]` and `#[serde(with = "bity::byte")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::byte::deserialize")]
+    ///     disk_quota: u64,
+    ///     #[serde(with = "bity::byte")]
+    ///     max_upload: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             disk-quota = "1.5kB"
+    ///             max-upload = 180
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         disk_quota: 1_500,
+    ///         max_upload: 180,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12B").unwrap(), 12);
+        assert_eq!(super::parse("12.345kB").unwrap(), 12_345);
+        assert_eq!(super::parse("12").unwrap(), 12);
+
+        // Bits are not accepted.
+        assert!(matches!(super::parse("12b"), Err(Error::InvalidUnit("b"))));
+        assert!(matches!(super::parse("12kb"), Err(Error::InvalidUnit("kb"))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123B");
+        assert_eq!(super::format(1_234), "1.23kB");
+        assert_eq!(super::format(12_000), "12kB");
+    }
+}
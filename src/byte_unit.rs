@@ -0,0 +1,38 @@
+//! [`byte-unit`](https://docs.rs/byte-unit) conversions between
+//! [`ByteLimit`]s and [`Byte`]s.
+//!
+//! Unlimited limits can't be converted, nor sizes above `u64::MAX` (with the
+//! `u128` feature of `byte-unit`).
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::limit::ByteLimit;
+//! use byte_unit::Byte;
+//!
+//! assert_eq!(ByteLimit::try_from(Byte::from_u64(5_000_000)), Ok(ByteLimit::new(5_000_000)));
+//! assert_eq!(Byte::try_from(ByteLimit::new(1_500)), Ok(Byte::from_u64(1_500)));
+//! assert!(Byte::try_from(ByteLimit::unlimited()).is_err());
+//! ```
+
+use core::num::TryFromIntError;
+
+use byte_unit::Byte;
+
+use crate::limit::{ByteLimit, Limit, UnlimitedError};
+
+impl TryFrom<Byte> for ByteLimit {
+    type Error = TryFromIntError;
+
+    fn try_from(size: Byte) -> Result<Self, Self::Error> {
+        u64::try_from(size.as_u128()).map(Limit::new)
+    }
+}
+
+impl TryFrom<ByteLimit> for Byte {
+    type Error = UnlimitedError;
+
+    fn try_from(limit: ByteLimit) -> Result<Self, Self::Error> {
+        limit.value().map(Byte::from_u64).ok_or(UnlimitedError)
+    }
+}
@@ -0,0 +1,445 @@
+//! SI prefixed data-rate parsing and formatting (byte oriented).
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::byteps::{format, parse};
+//!
+//! assert_eq!(parse("12.3kB/s").unwrap(), 12_300);
+//! assert_eq!(parse("0.12kBps").unwrap(), 120);
+//! assert_eq!(parse("12B").unwrap(), 12);
+//! assert_eq!(parse("12").unwrap(), 12);
+//!
+//! assert_eq!(format(1_234), "1.23kB/s");
+//! assert_eq!(format(123_456), "123.45kB/s");
+//! assert_eq!(format(12_345_678), "12.34MB/s");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::byteps::serialize")]`, `#[serde(deserialize_with =
+//! "bity::byteps::deserialize")]` and `#[serde(with = "bity::byteps")]`
+//! attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::byteps")]
+//!     bandwidth: u64,
+//!     #[serde(with = "bity::byteps")]
+//!     nic: u64,
+//!     #[serde(with = "bity::byteps")]
+//!     highest: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         bandwidth = "5.1MB/s"
+//!         nic = "180kB"
+//!         highest = 12_000
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         bandwidth: 5_100_000,
+//!         nic: 180_000,
+//!         highest: 12_000,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         bandwidth: 5_100_000,
+//!         nic: 180_000,
+//!         highest: 12_000,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         bandwidth = "5.1MB/s"
+//!         nic = "180kB/s"
+//!         highest = "12kB/s"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::{format, string::String};
+use core::{fmt, time::Duration};
+
+use crate::{
+    byte,
+    error::Error,
+    si::{self, Prefix},
+    IntoDuration, Period,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str =
+    r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?B?\s*(?:/s|ps)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["B/s", "Bps"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "B/s".len();
+
+/// Parse a data-rate SI prefixed string into a number.
+///
+/// This is equivalent to colling `byte::parse(strip_per_second(input))`.
+///
+/// Refer to [`si::parse`](crate::si::parse) and [`byte::parse`] to learn the
+/// rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::byteps::parse;
+///
+/// assert_eq!(parse("12B/s").unwrap(), 12);
+/// assert_eq!(parse("12Bps").unwrap(), 12);
+/// assert_eq!(parse("12.345kB/s").unwrap(), 12_345);
+/// assert_eq!(parse("12.345kBps").unwrap(), 12_345);
+/// assert_eq!(parse("12B").unwrap(), 12);
+/// assert_eq!(parse("12").unwrap(), 12);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    byte::parse(crate::strip_per_second(input))
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::byteps::parse_const;
+///
+/// assert_eq!(parse_const("1.5MB/s"), Some(1_500_000));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    byte::parse_const(crate::strip_per_second_const(input))
+}
+
+/// Format an integer into a data-rate SI prefixed string (byte oriented).
+///
+/// This is equivalent to colling `format!("{}/s", byte::format(input))`.
+///
+/// Refer to [`si::format`](crate::si::format) and [`byte::format`] to learn
+/// the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::byteps::format;
+///
+/// assert_eq!(format(12), "12B/s");
+/// assert_eq!(format(1_234), "1.23kB/s");
+/// assert_eq!(format(12_000), "12kB/s");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (data-rate, byte oriented) using
+/// a given SI prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}/s",
+/// byte::format_with_prefix(input, prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{byteps::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300MB/s");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("B/s");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::byteps::{format_into, MAX_FORMATTED_LEN};
+///
+/// let mut output = heapless::String::<MAX_FORMATTED_LEN>::new();
+/// format_into(&mut output, 12_300).unwrap();
+/// assert_eq!(output, "12.3kB/s");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    byte::format_into(output, input)?;
+    output.write_str("/s")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::byteps::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 12_300).unwrap();
+/// assert_eq!(&buffer[..len], b"12.3kB/s");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Compute the data-rate (per-second) of a volume of bytes transferred over a
+/// given duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. A zero duration is treated as an infinite rate,
+/// except for a zero volume which always gives a zero rate.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{byte, byteps};
+///
+/// let rate = byteps::from_volume(byte::parse("12GB").unwrap(), Duration::from_secs(95));
+/// assert_eq!(rate, 126_315_789);
+/// assert_eq!(byteps::format(rate), "126.31MB/s");
+/// ```
+pub fn from_volume(volume: u64, duration: impl IntoDuration) -> u64 {
+    crate::rate::from_volume(volume, duration.into_duration())
+}
+
+/// Compute the volume of bytes transferred at a given data-rate over a given
+/// duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. Use [`checked_volume_over`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{byte, byteps};
+///
+/// let volume = byteps::volume_over(byteps::parse("50MB/s").unwrap(), Duration::from_secs(6 * 3_600));
+/// assert_eq!(byte::format(volume), "1.08TB");
+/// assert_eq!(byteps::volume_over(u64::MAX, Duration::from_secs(2)), u64::MAX);
+/// ```
+pub fn volume_over(rate: u64, duration: impl IntoDuration) -> u64 {
+    checked_volume_over(rate, duration).unwrap_or(u64::MAX)
+}
+
+/// Like [`volume_over`] but returns `None` if the volume doesn't fit in an
+/// `u64`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::byteps::checked_volume_over;
+///
+/// assert_eq!(checked_volume_over(1_000, Duration::from_millis(1_500)), Some(1_500));
+/// assert_eq!(checked_volume_over(u64::MAX, Duration::from_secs(2)), None);
+/// ```
+pub fn checked_volume_over(rate: u64, duration: impl IntoDuration) -> Option<u64> {
+    crate::rate::checked_volume_over(rate, duration.into_duration())
+}
+
+/// Compute the time needed to transfer a volume of bytes at a given data-rate.
+///
+/// The result is rounded to the nearest nanosecond. A zero rate is treated as
+/// an infinite transfer time and gives `Duration::MAX`, except for a zero
+/// volume which always gives `Duration::ZERO`.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, byteps};
+///
+/// let time = byteps::time_to_transfer(byte::parse("100GB").unwrap(), byteps::parse("42.9MB/s").unwrap());
+/// assert_eq!(time.as_secs(), 2_331);
+/// ```
+pub fn time_to_transfer(volume: u64, rate: u64) -> Duration {
+    crate::rate::time_to_transfer(volume, rate)
+}
+
+/// Format the time needed to transfer a volume of bytes at a given
+/// data-rate, alongside the volume and the rate.
+///
+/// The duration is formatted in a compact humantime-like style, truncated to
+/// the second.
+///
+/// # Examples
+/// ```
+/// use bity::byteps::format_time_to_transfer;
+///
+/// assert_eq!(
+///     format_time_to_transfer(100_000_000_000, 42_900_000),
+///     "100GB in 38m51s at 42.9MB/s"
+/// );
+/// ```
+#[cfg(feature = "duration")]
+pub fn format_time_to_transfer(volume: u64, rate: u64) -> String {
+    format!(
+        "{} in {} at {}",
+        byte::format(volume),
+        crate::rate::format_duration(time_to_transfer(volume, rate)),
+        format(rate)
+    )
+}
+
+/// Compute the volume of bytes transferred over a whole [`Period`] at a
+/// given sustained data-rate.
+///
+/// This is equivalent to calling `volume_over(rate, period.duration())`, refer
+/// to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, byteps, Period};
+///
+/// assert_eq!(byte::format(byteps::volume_per(64_000, Period::Month)), "165.88GB");
+/// ```
+pub fn volume_per(rate: u64, period: Period) -> u64 {
+    volume_over(rate, period.duration())
+}
+
+/// Compute the sustained data-rate (per-second) needed to transfer a volume of
+/// bytes over a whole [`Period`].
+///
+/// This is equivalent to calling `from_volume(volume, period.duration())`,
+/// refer to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, byteps, Period};
+///
+/// assert_eq!(byteps::from_volume_per(byte::parse("3GB").unwrap(), Period::Day), 34_722);
+/// ```
+pub fn from_volume_per(volume: u64, period: Period) -> u64 {
+    from_volume(volume, period.duration())
+}
+
+/// Format a per-second data-rate into a SI prefixed string using a different
+/// time base.
+///
+/// This is equivalent to calling `format!("{}/{}",
+/// byte::format(volume_per(input, period)), period.symbol())`.
+///
+/// # Examples
+/// ```
+/// use bity::{byteps::format_per, Period};
+///
+/// assert_eq!(format_per(1_000_000, Period::Second), "1MB/s");
+/// assert_eq!(format_per(1_000_000, Period::Hour), "3.6GB/h");
+/// assert_eq!(format_per(1_000_000, Period::Day), "86.4GB/d");
+/// ```
+pub fn format_per(input: u64, period: Period) -> String {
+    format!("{}/{}", byte::format(volume_per(input, period)), period.symbol())
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a data-rate like "1.5kB/s", "12MBps" or 1500"#,
+    example: 5_000_000,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed data-rate string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::byteps::serialize")]` and `#[serde(with = "bity::byteps")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(with = "bity::byteps")]
+    ///     bandwidth: u64,
+    ///     #[serde(serialize_with = "bity::byteps::serialize")]
+    ///     nic: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         bandwidth: 5_100_000,
+    ///         nic: 180_000,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         bandwidth = "5.1MB/s"
+    ///         nic = "180kB/s"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed data-rate string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::byteps::deserialize")]` and `#[serde(with = "bity::byteps")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(with = "bity::byteps")]
+    ///     bandwidth: u64,
+    ///     #[serde(deserialize_with = "bity::byteps::deserialize")]
+    ///     nic: u64,
+    ///     #[serde(deserialize_with = "bity::byteps::deserialize")]
+    ///     highest: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             bandwidth = "5.1MB/s"
+    ///             nic = "180kB"
+    ///             highest = 12_000
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         bandwidth: 5_100_000,
+    ///         nic: 180_000,
+    ///         highest: 12_000,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12B/s").unwrap(), 12);
+        assert_eq!(super::parse("12Bps").unwrap(), 12);
+        assert_eq!(super::parse("12.345kB/s").unwrap(), 12_345);
+        assert_eq!(super::parse("12.345kBps").unwrap(), 12_345);
+
+        assert_eq!(super::parse("12B").unwrap(), 12);
+        assert_eq!(super::parse("12").unwrap(), 12);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123B/s");
+        assert_eq!(super::format(1_234), "1.23kB/s");
+        assert_eq!(super::format(12_000), "12kB/s");
+    }
+}
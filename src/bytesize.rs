@@ -0,0 +1,31 @@
+//! [`bytesize`](https://docs.rs/bytesize) conversions: [`ByteLimit`]s are
+//! created from [`ByteSize`]s, and converted back unless unlimited.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::limit::ByteLimit;
+//! use bytesize::ByteSize;
+//!
+//! assert_eq!(ByteLimit::from(ByteSize::mb(5)), ByteLimit::new(5_000_000));
+//! assert_eq!(ByteSize::try_from(ByteLimit::new(1_500)), Ok(ByteSize::b(1_500)));
+//! assert!(ByteSize::try_from(ByteLimit::unlimited()).is_err());
+//! ```
+
+use bytesize::ByteSize;
+
+use crate::limit::{ByteLimit, Limit, UnlimitedError};
+
+impl From<ByteSize> for ByteLimit {
+    fn from(size: ByteSize) -> Self {
+        Limit::new(size.as_u64())
+    }
+}
+
+impl TryFrom<ByteLimit> for ByteSize {
+    type Error = UnlimitedError;
+
+    fn try_from(limit: ByteLimit) -> Result<Self, Self::Error> {
+        limit.value().map(ByteSize::b).ok_or(UnlimitedError)
+    }
+}
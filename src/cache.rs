@@ -0,0 +1,154 @@
+//! Memoization of parsed inputs, for services re-parsing the same strings on
+//! every reload or request.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{byte, cache::CachedParser};
+//!
+//! let mut sizes = CachedParser::new(byte::parse, 256);
+//! for _ in 0..3 {
+//!     assert_eq!(sizes.parse("1.5GB").unwrap(), 1_500_000_000);
+//! }
+//! assert_eq!(sizes.len(), 1);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::Error;
+
+/// A parsing function, like [`byte::parse`](crate::byte::parse).
+pub type Parse<T> = for<'a> fn(&'a str) -> Result<T, Error<'a>>;
+
+/// A parsing function wrapped with a bounded cache of its successful results,
+/// keyed by input string.
+///
+/// When full, the least recently used entry is evicted to make room for a new
+/// one. Eviction is linear in the capacity, which is meant to stay small (a
+/// few hundred entries), while lookups are constant time.
+///
+/// Invalid inputs aren't cached, so their errors can borrow the input.
+#[derive(Debug, Clone)]
+pub struct CachedParser<T> {
+    parse: Parse<T>,
+    capacity: usize,
+    entries: HashMap<String, Entry<T>>,
+    tick: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    value: T,
+    used: u64,
+}
+
+impl<T: Clone> CachedParser<T> {
+    /// Wrap a parsing function with a cache of at most `capacity` entries.
+    ///
+    /// A zero capacity disables the cache.
+    pub fn new(parse: Parse<T>, capacity: usize) -> Self {
+        Self {
+            parse,
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Parse an input, or return its cached result.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use bity::{cache::CachedParser, duration, Error};
+    ///
+    /// let mut timeouts = CachedParser::new(duration::parse, 16);
+    /// assert_eq!(timeouts.parse("1m30s").unwrap(), Duration::from_secs(90));
+    /// assert!(matches!(timeouts.parse("1hr"), Err(Error::InvalidUnit("hr"))));
+    /// assert_eq!(timeouts.len(), 1);
+    /// ```
+    pub fn parse<'a>(&mut self, input: &'a str) -> Result<T, Error<'a>> {
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(input) {
+            entry.used = self.tick;
+            return Ok(entry.value.clone());
+        }
+
+        let value = (self.parse)(input)?;
+        if self.capacity == 0 {
+            return Ok(value);
+        }
+        if self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(input, _)| input.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            input.to_owned(),
+            Entry {
+                value: value.clone(),
+                used: self.tick,
+            },
+        );
+        Ok(value)
+    }
+
+    /// Maximum number of cached entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all the cached entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(all(test, feature = "byte", feature = "bps"))]
+mod tests {
+    use super::CachedParser;
+    use crate::Error;
+
+    #[test]
+    fn parse() {
+        let mut parser = CachedParser::new(crate::byte::parse, 2);
+        assert_eq!(parser.parse("1kB").unwrap(), 1_000);
+        assert_eq!(parser.parse("2kB").unwrap(), 2_000);
+        assert_eq!(parser.parse("1kB").unwrap(), 1_000);
+        assert!(matches!(parser.parse("3kx"), Err(Error::InvalidUnit("kx"))));
+        assert_eq!(parser.len(), 2);
+
+        // "2kB" is the least recently used.
+        assert_eq!(parser.parse("3kB").unwrap(), 3_000);
+        assert_eq!(parser.len(), 2);
+        assert!(parser.entries.contains_key("1kB"));
+        assert!(parser.entries.contains_key("3kB"));
+
+        parser.clear();
+        assert!(parser.is_empty());
+        assert_eq!(parser.capacity(), 2);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut parser = CachedParser::new(crate::bps::parse, 0);
+        assert_eq!(parser.parse("1kbps").unwrap(), 1_000);
+        assert!(parser.is_empty());
+    }
+}
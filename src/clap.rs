@@ -0,0 +1,253 @@
+//! [`clap`](https://docs.rs/clap) value parsers.
+//!
+//! Each unit family has a function usable with `#[arg(value_parser = ...)]`,
+//! turning arguments like `--rate 5MB/s` into integers (or
+//! [`Duration`](std::time::Duration)s). Parsing errors are reported by clap,
+//! alongside the invalid argument.
+//!
+//! [`ValueParser`] does the same for a [`Unit`] type parameter, while
+//! [`Limit`] and [`RelativeOrAbsolute`] arguments are supported by
+//! `value_parser!` directly.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{limit::ByteLimit, relative::RelativeOrAbsolute, unit::Byte};
+//! use clap::Parser;
+//!
+//! #[derive(Parser)]
+//! struct Args {
+//!     #[arg(long, value_parser = bity::clap::byteps)]
+//!     rate: u64,
+//!     #[arg(long, value_parser = bity::clap::duration)]
+//!     timeout: std::time::Duration,
+//!     #[arg(long)]
+//!     quota: ByteLimit,
+//!     #[arg(long)]
+//!     reserved: RelativeOrAbsolute<Byte>,
+//! }
+//!
+//! let args = Args::try_parse_from([
+//!     "app",
+//!     "--rate",
+//!     "5MB/s",
+//!     "--timeout",
+//!     "1m30s",
+//!     "--quota",
+//!     "unlimited",
+//!     "--reserved",
+//!     "10%",
+//! ])
+//! .unwrap();
+//! assert_eq!(args.rate, 5_000_000);
+//! assert_eq!(args.timeout.as_secs(), 90);
+//! assert_eq!(args.quota.value(), None);
+//! assert_eq!(args.reserved.resolve(1_000), 100);
+//!
+//! let err = Args::try_parse_from(["app", "--rate", "5XB/s"]).err().unwrap();
+//! assert!(err.to_string().contains(r#"invalid unit "XB""#));
+//! ```
+
+use std::{ffi::OsStr, marker::PhantomData};
+
+use clap::{
+    builder::{TypedValueParser, ValueParserFactory},
+    Arg, Command,
+};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, unit::Unit, Error, OwnedError};
+
+macro_rules! parsers {
+    ($($(#[doc = $doc:literal])* $(#[cfg($cfg:meta)])? $name:ident => $parse:path: $value:ty,)*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            pub fn $name(input: &str) -> Result<$value, OwnedError> {
+                $parse(input).map_err(Error::into_owned)
+            }
+        )*
+    };
+}
+
+parsers! {
+    /// Parse an argument using [`si::parse`](crate::si::parse).
+    si => crate::si::parse: u64,
+    /// Parse an argument using [`bit::parse`](crate::bit::parse).
+    #[cfg(feature = "bit")]
+    bit => crate::bit::parse: u64,
+    /// Parse an argument using [`byte::parse`](crate::byte::parse).
+    #[cfg(feature = "byte")]
+    byte => crate::byte::parse: u64,
+    /// Parse an argument using [`packet::parse`](crate::packet::parse).
+    #[cfg(feature = "packet")]
+    packet => crate::packet::parse: u64,
+    /// Parse an argument using [`bps::parse`](crate::bps::parse).
+    #[cfg(feature = "bps")]
+    bps => crate::bps::parse: u64,
+    /// Parse an argument using [`byteps::parse`](crate::byteps::parse).
+    #[cfg(feature = "byteps")]
+    byteps => crate::byteps::parse: u64,
+    /// Parse an argument using [`pps::parse`](crate::pps::parse).
+    #[cfg(feature = "pps")]
+    pps => crate::pps::parse: u64,
+    /// Parse an argument using [`bpp::parse`](crate::bpp::parse).
+    #[cfg(feature = "bpp")]
+    bpp => crate::bpp::parse: u64,
+    /// Parse an argument using [`hz::parse`](crate::hz::parse).
+    #[cfg(feature = "hz")]
+    hz => crate::hz::parse: u64,
+    /// Parse an argument using [`watt::parse`](crate::watt::parse).
+    #[cfg(feature = "watt")]
+    watt => crate::watt::parse: u64,
+    /// Parse an argument using [`ops::parse`](crate::ops::parse).
+    #[cfg(feature = "ops")]
+    ops => crate::ops::parse: u64,
+    /// Parse an argument using [`percent::parse`](crate::percent::parse).
+    #[cfg(feature = "percent")]
+    percent => crate::percent::parse: u64,
+    /// Parse an argument using
+    /// [`compression::parse`](crate::compression::parse).
+    #[cfg(feature = "compression")]
+    compression => crate::compression::parse: u64,
+    /// Parse an argument using [`db::parse`](crate::db::parse).
+    #[cfg(feature = "db")]
+    db => crate::db::parse: i64,
+    /// Parse an argument using [`db::parse_dbm`](crate::db::parse_dbm).
+    #[cfg(feature = "db")]
+    dbm => crate::db::parse_dbm: i64,
+    /// Parse an argument using [`duration::parse`](crate::duration::parse).
+    #[cfg(feature = "duration")]
+    duration => crate::duration::parse: std::time::Duration,
+}
+
+/// Value parser of the arguments of a [`Unit`], for `#[arg(value_parser =
+/// ...)]`.
+///
+/// # Examples
+/// ```
+/// use bity::{clap::ValueParser, unit::Pps};
+/// use clap::Parser;
+///
+/// #[derive(Parser)]
+/// struct Args {
+///     #[arg(long, value_parser = ValueParser::<Pps>::new())]
+///     max_rate: u64,
+/// }
+///
+/// let args = Args::try_parse_from(["app", "--max-rate", "1.5Mpps"]).unwrap();
+/// assert_eq!(args.max_rate, 1_500_000);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueParser<U> {
+    unit: PhantomData<U>,
+}
+
+impl<U> ValueParser<U> {
+    /// Create a value parser for the unit `U`.
+    pub fn new() -> Self {
+        Self { unit: PhantomData }
+    }
+}
+
+impl<U: Unit + Clone + Send + Sync + 'static> TypedValueParser for ValueParser<U> {
+    type Value = u64;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        // Reuse the parser of functions, reporting non UTF-8 values and
+        // parsing errors like the builtin parsers.
+        let parse: fn(&str) -> Result<u64, OwnedError> =
+            |input| U::parse(input).map_err(Error::into_owned);
+        parse.parse_ref(cmd, arg, value)
+    }
+}
+
+impl<U: Unit + Clone + Send + Sync + 'static> ValueParserFactory for Limit<U> {
+    type Parser = fn(&str) -> Result<Self, OwnedError>;
+
+    fn value_parser() -> Self::Parser {
+        |input| Limit::parse(input).map_err(Error::into_owned)
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Unit + Clone + Send + Sync + 'static> ValueParserFactory for RelativeOrAbsolute<U> {
+    type Parser = fn(&str) -> Result<Self, OwnedError>;
+
+    fn value_parser() -> Self::Parser {
+        |input| RelativeOrAbsolute::parse(input).map_err(Error::into_owned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{error::ErrorKind, Parser};
+
+    use crate::{limit::Limit, unit::Bps};
+
+    #[derive(Parser, Debug)]
+    struct Args {
+        #[arg(long, value_parser = super::byte)]
+        size: Option<u64>,
+        #[arg(long, value_parser = super::ValueParser::<Bps>::new())]
+        rate: Option<u64>,
+        #[arg(long, value_parser = super::dbm)]
+        power: Option<i64>,
+        #[arg(long)]
+        limit: Option<Limit<Bps>>,
+    }
+
+    #[test]
+    fn parse() {
+        let args = Args::try_parse_from([
+            "app",
+            "--size",
+            "1.5kB",
+            "--rate",
+            "8kbps",
+            "--power=-20dBm",
+            "--limit",
+            "none",
+        ])
+        .unwrap();
+        assert_eq!(args.size, Some(1_500));
+        assert_eq!(args.rate, Some(8_000));
+        assert_eq!(args.power, Some(-20_000));
+        assert_eq!(args.limit, Some(Limit::unlimited()));
+
+        let args = Args::try_parse_from(["app", "--limit", "1Mb/s"]).unwrap();
+        assert_eq!(args.limit, Some(Limit::new(1_000_000)));
+    }
+
+    #[test]
+    fn error() {
+        let err = Args::try_parse_from(["app", "--size", "12kx"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        assert!(err
+            .to_string()
+            .contains(r#"invalid value '12kx' for '--size <SIZE>': invalid unit "kx""#));
+
+        let err = Args::try_parse_from(["app", "--rate=-1kbps"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        assert!(err.to_string().contains(r#"negative number "-1""#));
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+            let err = Args::try_parse_from([
+                OsString::from("app"),
+                OsString::from("--rate"),
+                OsString::from_vec(vec![0xff]),
+            ])
+            .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidUtf8);
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Compression (or deduplication) ratio parsing and formatting.
+//!
+//! Ratios are represented as fixed-point integers in thousandths: `3_200` is
+//! `3.2:1`. They can also be expressed as space savings, `68.75%` being the
+//! savings of a `3.2:1` ratio.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::compression::{format, format_savings, parse};
+//!
+//! assert_eq!(parse("3.2:1").unwrap(), 3_200);
+//! assert_eq!(parse("3.2x").unwrap(), 3_200);
+//! assert_eq!(parse("68.75%").unwrap(), 3_200);
+//!
+//! assert_eq!(format(3_200), "3.2:1");
+//! assert_eq!(format_savings(3_200), "68.75%");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::compression::serialize")]`, `#[serde(deserialize_with =
+//! "bity::compression::deserialize")]` and `#[serde(with =
+//! "bity::compression")]` attributes. Integers are deserialized as is, meaning
+//! that they are interpreted as thousandths.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Volume {
+//!     #[serde(with = "bity::compression")]
+//!     expected_ratio: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Volume>(r#"expected-ratio = "75%""#).unwrap(),
+//!     Volume { expected_ratio: 4_000 }
+//! );
+//! assert_eq!(
+//!     toml::to_string(&Volume { expected_ratio: 4_000 }).unwrap(),
+//!     indoc! {r#"
+//!         expected-ratio = "4:1"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{error::Error, percent, si::Digits};
+
+/// Fixed-point factor: the representation of a `1:1` ratio.
+const ONE: u64 = 1_000;
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:%|x)?|(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*:\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+))\s*$";
+
+/// Units accepted after the number, listed in error reports.
+pub const UNITS: &[&str] = &[":1", "x", "%"];
+
+/// Upper bound of the length of the strings returned by [`format`], reached
+/// by `format(u64::MAX)`.
+pub const MAX_FORMATTED_LEN: usize = 23;
+
+/// Parse a compression ratio (`3.2:1`, `3.2x`) or space savings (`68%`)
+/// string into thousandths.
+///
+/// Ratios are rounded to the nearest thousandth. Savings of `100%` or more
+/// give an infinite ratio, saturated to `u64::MAX`.
+///
+/// # Examples
+/// ```
+/// use bity::{compression::parse, Error};
+///
+/// assert_eq!(parse("3.2:1").unwrap(), 3_200);
+/// assert_eq!(parse("4:3").unwrap(), 1_333);
+/// assert_eq!(parse("2x").unwrap(), 2_000);
+/// assert_eq!(parse("1.5").unwrap(), 1_500);
+/// assert_eq!(parse("50%").unwrap(), 2_000);
+/// assert_eq!(parse("100%").unwrap(), u64::MAX);
+/// assert!(matches!(parse("3:1:1"), Err(Error::InvalidUnit(":1"))));
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    let input = input.trim();
+    if input.ends_with('%') {
+        return Ok(from_savings(percent::parse(input)?));
+    }
+
+    // Ratios are parsed into ppm, without unit. They aren't bounded to a `u64`
+    // so the ratios formatted by `format` (up to `u64::MAX` thousandths) can
+    // be parsed back.
+    fn parse_part(part: &str) -> Result<u128, Error<'_>> {
+        match part.find(|c: char| c.is_ascii_alphabetic() || c == '%' || c == ':') {
+            Some(position) => Err(Error::InvalidUnit(part[position..].trim())),
+            None => percent::parse_wide(part).map(|(_, ppm)| ppm),
+        }
+    }
+    let (numerator, denominator) = match input.split_once(':') {
+        Some((numerator, denominator)) => (parse_part(numerator)?, parse_part(denominator)?),
+        None => (parse_part(input.strip_suffix('x').unwrap_or(input))?, u128::from(percent::ONE)),
+    };
+    if denominator == 0 {
+        return Ok(if numerator == 0 { 0 } else { u64::MAX });
+    }
+    let ratio = (numerator * u128::from(ONE) + denominator / 2) / denominator;
+    Ok(u64::try_from(ratio).unwrap_or(u64::MAX))
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid.
+///
+/// # Examples
+/// ```
+/// use bity::compression::parse_const;
+///
+/// assert_eq!(parse_const("3.2:1"), Some(3_200));
+/// assert_eq!(parse_const("50%"), Some(2_000));
+/// assert_eq!(parse_const("3:1:1"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    if !input.is_ascii() {
+        return None;
+    }
+
+    let input = input.trim_ascii();
+    if let [.., b'%'] = input.as_bytes() {
+        return match percent::parse_const(input) {
+            Some(savings) => Some(from_savings(savings)),
+            None => None,
+        };
+    }
+
+    const fn parse_part(part: &str) -> Option<u128> {
+        let mut i = 0;
+        while i < part.len() {
+            if matches!(part.as_bytes()[i], b'a'..=b'z' | b'A'..=b'Z' | b'%' | b':') {
+                return None;
+            }
+            i += 1;
+        }
+        percent::parse_wide_const(part)
+    }
+    let mut colon = 0;
+    while colon < input.len() && input.as_bytes()[colon] != b':' {
+        colon += 1;
+    }
+    let (numerator, denominator) = if colon < input.len() {
+        let (numerator, denominator) = input.split_at(colon);
+        (parse_part(numerator), parse_part(denominator.split_at(1).1))
+    } else {
+        let numerator = match input.as_bytes() {
+            [.., b'x'] => input.split_at(input.len() - 1).0,
+            _ => input,
+        };
+        (parse_part(numerator), Some(percent::ONE as u128))
+    };
+    let (Some(numerator), Some(denominator)) = (numerator, denominator) else {
+        return None;
+    };
+    if denominator == 0 {
+        return Some(if numerator == 0 { 0 } else { u64::MAX });
+    }
+    let ratio = (numerator * ONE as u128 + denominator / 2) / denominator;
+    Some(if ratio > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        ratio as u64
+    })
+}
+
+/// Format thousandths into a compression ratio string.
+///
+/// # Examples
+/// ```
+/// use bity::compression::format;
+///
+/// assert_eq!(format(3_200), "3.2:1");
+/// assert_eq!(format(1_333), "1.333:1");
+/// assert_eq!(format(4_000), "4:1");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Like [`format`], but writing into an existing writer instead of
+/// allocating.
+///
+/// # Examples
+/// ```
+/// use bity::compression::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_250).unwrap();
+/// assert_eq!(output, "1.25:1");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    output.write_str(Digits::fixed(input, ONE).as_str())?;
+    output.write_str(":1")
+}
+
+/// Format thousandths into a space savings percent string.
+///
+/// This is equivalent to calling `percent::format(to_savings(input))`.
+///
+/// # Examples
+/// ```
+/// use bity::compression::format_savings;
+///
+/// assert_eq!(format_savings(3_200), "68.75%");
+/// assert_eq!(format_savings(1_000), "0%");
+/// ```
+pub fn format_savings(input: u64) -> String {
+    percent::format(to_savings(input))
+}
+
+/// Convert a ratio (in thousandths) into space savings (in ppm).
+///
+/// The result is rounded to the nearest integer. Ratios below `1:1` (which
+/// expand the data) give no savings.
+///
+/// # Examples
+/// ```
+/// use bity::{compression::to_savings, percent};
+///
+/// assert_eq!(percent::format(to_savings(4_000)), "75%");
+/// assert_eq!(to_savings(500), 0);
+/// ```
+pub fn to_savings(ratio: u64) -> u64 {
+    if ratio <= ONE {
+        return 0;
+    }
+    let kept =
+        (u128::from(ONE) * u128::from(percent::ONE) + u128::from(ratio) / 2) / u128::from(ratio);
+    percent::ONE - kept as u64
+}
+
+/// Convert space savings (in ppm) into a ratio (in thousandths).
+///
+/// The result is rounded to the nearest integer. Savings of `100%` or more
+/// give an infinite ratio, saturated to `u64::MAX`.
+///
+/// # Examples
+/// ```
+/// use bity::{compression::from_savings, percent};
+///
+/// assert_eq!(from_savings(percent::parse("75%").unwrap()), 4_000);
+/// assert_eq!(from_savings(percent::ONE), u64::MAX);
+/// ```
+pub const fn from_savings(savings: u64) -> u64 {
+    if savings >= percent::ONE {
+        return u64::MAX;
+    }
+    let kept = percent::ONE - savings;
+    ((percent::ONE as u128 * ONE as u128 + kept as u128 / 2) / kept as u128) as u64
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a compression ratio like "3.2:1", "3.2x" or "68%""#,
+    example: 3_200,
+    ser:
+    /// Serialize given thousandths into a compression ratio string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::compression::serialize")]` and `#[serde(with = "bity::compression")]` attributes.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Volume {
+    ///     #[serde(serialize_with = "bity::compression::serialize")]
+    ///     ratio: u64,
+    /// }
+    ///
+    /// assert_eq!(toml::to_string(&Volume { ratio: 3_200 }).unwrap(), "ratio = \"3.2:1\"\n");
+    /// ```
+    de:
+    /// Deserialize a given integer (thousandths), compression ratio or space savings string into thousandths.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::compression::deserialize")]` and `#[serde(with = "bity::compression")]` attributes.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// struct Volume {
+    ///     #[serde(deserialize_with = "bity::compression::deserialize")]
+    ///     ratio: u64,
+    /// }
+    ///
+    /// assert_eq!(toml::from_str::<Volume>(r#"ratio = "3.2:1""#).unwrap(), Volume { ratio: 3_200 });
+    /// assert_eq!(toml::from_str::<Volume>("ratio = 3_200").unwrap(), Volume { ratio: 3_200 });
+    /// ```
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("3.2:1").unwrap(), 3_200);
+        assert_eq!(super::parse(" 3.2 : 1 ").unwrap(), 3_200);
+        assert_eq!(super::parse("1:4").unwrap(), 250);
+        assert_eq!(super::parse("2:3").unwrap(), 667);
+        assert_eq!(super::parse("3.2x").unwrap(), 3_200);
+        assert_eq!(super::parse("3.2").unwrap(), 3_200);
+        assert_eq!(super::parse("68.75%").unwrap(), 3_200);
+        assert_eq!(super::parse("0%").unwrap(), 1_000);
+        assert_eq!(super::parse("0:0").unwrap(), 0);
+        assert_eq!(super::parse("1:0").unwrap(), u64::MAX);
+        assert_eq!(super::parse("18446744073709551.615:1").unwrap(), u64::MAX);
+        assert_eq!(super::parse("18446744073709551615x").unwrap(), u64::MAX);
+
+        assert!(matches!(super::parse("3%:1"), Err(Error::InvalidUnit("%"))));
+        assert!(matches!(super::parse("3y"), Err(Error::InvalidUnit("y"))));
+        assert!(matches!(super::parse(":1"), Err(Error::EmptyInput)));
+    }
+
+    #[test]
+    fn parse_const() {
+        for input in [
+            "3.2:1",
+            " 3.2 : 1 ",
+            "2:3",
+            "3.2x",
+            "3.2",
+            "68.75%",
+            "0%",
+            "100%",
+            "0:0",
+            "1:0",
+            "18446744073709551.615:1",
+            "18446744073709551615x",
+            "3%:1",
+            "3y",
+            ":1",
+            "3:1:1",
+            "3x:1",
+            "1:2x",
+            "-1:1",
+            "12€",
+            "",
+        ] {
+            assert_eq!(super::parse_const(input), super::parse(input).ok(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(0), "0:1");
+        assert_eq!(super::format(3_200), "3.2:1");
+        assert_eq!(super::format(250), "0.25:1");
+        assert_eq!(super::format_savings(2_000), "50%");
+        assert_eq!(super::format_savings(0), "0%");
+    }
+
+    #[test]
+    fn format_into() {
+        for input in [0, 1, 250, 3_200, 1_333, u64::MAX] {
+            let mut output = String::new();
+            super::format_into(&mut output, input).unwrap();
+            assert_eq!(output, super::format(input));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [0, 1, 250, 3_200, u64::MAX - 1, u64::MAX] {
+            assert_eq!(super::parse(&super::format(input)).unwrap(), input);
+        }
+        assert_eq!(super::parse(&super::format(super::parse("100%").unwrap())).unwrap(), u64::MAX);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Volume {
+            #[serde(with = "crate::compression")]
+            ratio: u64,
+        }
+
+        let volume = Volume { ratio: u64::MAX };
+        let json = serde_json::to_string(&volume).unwrap();
+        assert_eq!(json, r#"{"ratio":"18446744073709551.615:1"}"#);
+        assert_eq!(serde_json::from_str::<Volume>(&json).unwrap(), volume);
+    }
+
+    #[test]
+    fn savings() {
+        assert_eq!(super::to_savings(3_200), 687_500);
+        assert_eq!(super::to_savings(u64::MAX), 1_000_000);
+        assert_eq!(super::from_savings(687_500), 3_200);
+        assert_eq!(super::from_savings(0), 1_000);
+        assert_eq!(super::from_savings(999_999), 1_000_000_000);
+    }
+}
@@ -0,0 +1,94 @@
+//! SI prefixed parsing and formatting with a custom unit suffix.
+//!
+//! Useful for one-off units (messages, rows, sessions, ...) that don't have a
+//! dedicated module.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::custom::{format, parse};
+//!
+//! assert_eq!(parse("1.2kreq", "req").unwrap(), 1_200);
+//! assert_eq!(parse("3.4M rows", "rows").unwrap(), 3_400_000);
+//!
+//! assert_eq!(format(1_200, "req"), "1.2kreq");
+//! assert_eq!(format(3_400_000, " rows"), "3.4M rows");
+//! ```
+
+use alloc::string::String;
+
+use crate::{si, Error};
+
+/// Parse a SI prefixed string followed by a custom unit suffix into a number.
+///
+/// The suffix is matched case-sensitively and is optional. It's stripped
+/// before looking for the SI prefix, so suffixes starting with a prefix letter
+/// (`msg`, `events`) are supported.
+///
+/// Refer to [`si::parse`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::{custom::parse, Error};
+///
+/// assert_eq!(parse("12msg", "msg").unwrap(), 12);
+/// assert_eq!(parse("12kmsg", "msg").unwrap(), 12_000);
+/// assert_eq!(parse("12k", "msg").unwrap(), 12_000);
+/// assert!(matches!(parse("12kMSG", "msg"), Err(Error::InvalidUnit("kMSG"))));
+/// ```
+pub fn parse<'a>(input: &'a str, suffix: &str) -> Result<u64, Error<'a>> {
+    let input = input.trim();
+    si::parse(if suffix.is_empty() {
+        input
+    } else {
+        input.strip_suffix(suffix).unwrap_or(input)
+    })
+}
+
+/// Format an integer into a SI prefixed string followed by a custom unit
+/// suffix.
+///
+/// This is equivalent to calling `format!("{}{suffix}", si::format(input))`.
+///
+/// Refer to [`si::format`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::custom::format;
+///
+/// assert_eq!(format(12, "msg"), "12msg");
+/// assert_eq!(format(1_234, "req"), "1.23kreq");
+/// assert_eq!(format(1_234, ""), "1.23k");
+/// ```
+pub fn format(input: u64, suffix: &str) -> String {
+    let mut output = String::with_capacity(si::MAX_FORMATTED_LEN + suffix.len());
+    // Writing into a `String` can't fail.
+    let _ = si::format_into(&mut output, input);
+    output.push_str(suffix);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12req", "req").unwrap(), 12);
+        assert_eq!(super::parse(" 1.2 kreq ", "req").unwrap(), 1_200);
+        assert_eq!(super::parse("5 Gevents", "events").unwrap(), 5_000_000_000);
+        assert_eq!(super::parse("5M sessions", " sessions").unwrap(), 5_000_000);
+        assert_eq!(super::parse("5M", "").unwrap(), 5_000_000);
+        assert_eq!(super::parse("5", "rows").unwrap(), 5);
+
+        assert!(matches!(super::parse("5krow", "rows"), Err(Error::InvalidUnit("krow"))));
+        assert!(matches!(super::parse("rows", "rows"), Err(Error::EmptyInput)));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(0, "req"), "0req");
+        assert_eq!(super::format(1_234, "req"), "1.23kreq");
+        assert_eq!(super::format(12_000_000, " rows"), "12M rows");
+    }
+}
@@ -0,0 +1,531 @@
+//! Decibel (`dB`) and decibel-milliwatt (`dBm`) parsing, formatting and
+//! conversions.
+//!
+//! Values are represented as fixed-point signed integers in thousandths of a
+//! decibel: `3_010` is `3.01dB` and `-20_000` is `-20dBm`.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::db;
+//!
+//! assert_eq!(db::parse("3dB").unwrap(), 3_000);
+//! assert_eq!(db::parse_dbm("-20dBm").unwrap(), -20_000);
+//!
+//! assert_eq!(db::format(3_010), "3.01dB");
+//! assert_eq!(db::format_dbm(-20_000), "-20dBm");
+//!
+//! assert_eq!(db::from_ratio(2.0), 3_010);
+//! assert_eq!(db::to_milliwatts(-20_000), 0.01);
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::db::serialize")]`, `#[serde(deserialize_with =
+//! "bity::db::deserialize")]` and `#[serde(with = "bity::db")]` attributes for
+//! `dB` values. Use the [`dbm`] submodule for `dBm` values. Bare numbers
+//! (integers and floats) are read like strings without unit, meaning that they
+//! are interpreted as decibels: `-6.5` and `"-6.5"` are both `-6.5dB`.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Link {
+//!     #[serde(with = "bity::db")]
+//!     margin: i64,
+//!     #[serde(with = "bity::db::dbm")]
+//!     sensitivity: i64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Link>(indoc! {r#"
+//!         margin = "3.5dB"
+//!         sensitivity = -28
+//!     "#})
+//!     .unwrap(),
+//!     Link {
+//!         margin: 3_500,
+//!         sensitivity: -28_000,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Link {
+//!         margin: 3_500,
+//!         sensitivity: -28_000,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         margin = "3.5dB"
+//!         sensitivity = "-28dBm"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::{format, string::String};
+
+use crate::error::{check_number, is_multiple_units, Error};
+
+/// Fixed-point factor: the number of units in a decibel.
+const FACTOR: i64 = 1_000;
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*[+-]?(?:[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:dB)?\s*$";
+
+/// Regular expression matching the strings accepted by [`parse_dbm`].
+pub const PATTERN_DBM: &str = r"^\s*[+-]?(?:[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:dBm)?\s*$";
+
+/// Units accepted by [`parse`], listed in error reports.
+pub const UNITS: &[&str] = &["dB"];
+
+/// Units accepted by [`parse_dbm`], listed in error reports.
+pub const UNITS_DBM: &[&str] = &["dBm"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_dbm`], reached by `format_dbm(i64::MIN)`.
+pub const MAX_FORMATTED_LEN: usize = 24;
+
+/// Parse a decibel string (`3dB`, `-1.5dB`) into thousandths of a decibel.
+///
+/// The `dB` unit is optional. Fraction digits beyond the third are ignored.
+/// Whitespaces are trimmed around the number and the unit.
+///
+/// # Examples
+/// ```
+/// use bity::{db::parse, Error};
+///
+/// assert_eq!(parse("3dB").unwrap(), 3_000);
+/// assert_eq!(parse("-1.5 dB").unwrap(), -1_500);
+/// assert_eq!(parse("+0.25").unwrap(), 250);
+/// assert!(matches!(parse("3dBm"), Err(Error::InvalidUnit("dBm"))));
+/// ```
+pub fn parse(input: &str) -> Result<i64, Error<'_>> {
+    parse_with_unit(input, "dB")
+}
+
+/// Parse a decibel-milliwatt string (`-20dBm`) into thousandths of a
+/// decibel-milliwatt.
+///
+/// Same rules as [`parse`] apply, with the `dBm` unit.
+///
+/// # Examples
+/// ```
+/// use bity::{db::parse_dbm, Error};
+///
+/// assert_eq!(parse_dbm("-20dBm").unwrap(), -20_000);
+/// assert_eq!(parse_dbm("1.5").unwrap(), 1_500);
+/// assert!(matches!(parse_dbm("3dB"), Err(Error::InvalidUnit("dB"))));
+/// ```
+pub fn parse_dbm(input: &str) -> Result<i64, Error<'_>> {
+    parse_with_unit(input, "dBm")
+}
+
+fn parse_with_unit<'a>(input: &'a str, unit: &str) -> Result<i64, Error<'a>> {
+    if !input.is_ascii() {
+        return Err(Error::NotAscii);
+    }
+
+    let input = input.trim();
+    let (value, unit_str) = input.split_at(crate::scan::letter_position(input.as_bytes()));
+    if !unit_str.is_empty() && unit_str != unit {
+        if is_multiple_units(unit_str, &[unit]) {
+            return Err(Error::MultipleUnits(unit_str));
+        }
+        return Err(Error::InvalidUnit(unit_str));
+    }
+
+    let value = value.trim();
+    let (negative, magnitude) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    if magnitude.starts_with(['-', '+']) {
+        return Err(Error::ParseIntError(value, None));
+    }
+    check_number(magnitude)?;
+    let (integer_str, fraction_str) = magnitude.split_once('.').unwrap_or((magnitude, ""));
+    if integer_str.is_empty() && fraction_str.is_empty() {
+        return Err(Error::ParseIntError(value, None));
+    }
+    // Also check the digits that are dropped below.
+    if !fraction_str.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::ParseIntError(fraction_str, fraction_str.parse::<u64>().err()));
+    }
+    let fraction_str = fraction_str[..fraction_str.len().min(3)].trim_end_matches('0');
+
+    fn parse_part(part: &str) -> Result<u64, Error<'_>> {
+        if part.is_empty() {
+            return Ok(0);
+        }
+        part.parse::<u64>()
+            .map_err(|err| Error::ParseIntError(part, Some(err)))
+    }
+    let factor = FACTOR as u64;
+    let fraction = parse_part(fraction_str)? * factor / 10u64.pow(fraction_str.len() as u32);
+    // The magnitude is parsed unsigned so that `i64::MIN` can be parsed back.
+    let magnitude = parse_part(integer_str)?
+        .checked_mul(factor)
+        .and_then(|integer| integer.checked_add(fraction));
+    match magnitude {
+        Some(magnitude) if negative => 0i64.checked_sub_unsigned(magnitude),
+        Some(magnitude) => i64::try_from(magnitude).ok(),
+        None => None,
+    }
+    .ok_or(Error::ParseIntError(value, None))
+}
+
+/// Format thousandths of a decibel into a decibel string.
+///
+/// # Examples
+/// ```
+/// use bity::db::format;
+///
+/// assert_eq!(format(3_000), "3dB");
+/// assert_eq!(format(-1_500), "-1.5dB");
+/// assert_eq!(format(3_010), "3.01dB");
+/// ```
+pub fn format(input: i64) -> String {
+    format!("{}dB", format_value(input))
+}
+
+/// Format thousandths of a decibel-milliwatt into a decibel-milliwatt string.
+///
+/// # Examples
+/// ```
+/// use bity::db::format_dbm;
+///
+/// assert_eq!(format_dbm(-20_000), "-20dBm");
+/// assert_eq!(format_dbm(-500), "-0.5dBm");
+/// ```
+pub fn format_dbm(input: i64) -> String {
+    format!("{}dBm", format_value(input))
+}
+
+fn format_value(input: i64) -> String {
+    let sign = if input < 0 { "-" } else { "" };
+    let magnitude = input.unsigned_abs();
+    let fraction = format!("{:03}", magnitude % FACTOR as u64);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{sign}{}", magnitude / FACTOR as u64)
+    } else {
+        format!("{sign}{}.{fraction}", magnitude / FACTOR as u64)
+    }
+}
+
+/// Convert thousandths of a decibel into a linear power ratio.
+///
+/// # Examples
+/// ```
+/// use bity::db::to_ratio;
+///
+/// assert_eq!(to_ratio(10_000), 10.0);
+/// assert_eq!(to_ratio(-30_000), 0.001);
+/// ```
+#[cfg(feature = "std")]
+pub fn to_ratio(input: i64) -> f64 {
+    10f64.powf(input as f64 / (10 * FACTOR) as f64)
+}
+
+/// Convert a linear power ratio into thousandths of a decibel, rounded to the
+/// nearest integer.
+///
+/// Non-positive ratios saturate to `i64::MIN`, which [`format`] and [`parse`]
+/// handle like any other value.
+///
+/// # Examples
+/// ```
+/// use bity::db::from_ratio;
+///
+/// assert_eq!(from_ratio(2.0), 3_010);
+/// assert_eq!(from_ratio(0.5), -3_010);
+/// assert_eq!(from_ratio(0.0), i64::MIN);
+/// ```
+#[cfg(feature = "std")]
+pub fn from_ratio(ratio: f64) -> i64 {
+    if ratio.is_nan() || ratio <= 0.0 {
+        return i64::MIN;
+    }
+    (ratio.log10() * (10 * FACTOR) as f64).round() as i64
+}
+
+/// Convert thousandths of a decibel-milliwatt into milliwatts.
+///
+/// # Examples
+/// ```
+/// use bity::db::to_milliwatts;
+///
+/// assert_eq!(to_milliwatts(0), 1.0);
+/// assert_eq!(to_milliwatts(30_000), 1_000.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn to_milliwatts(input: i64) -> f64 {
+    to_ratio(input)
+}
+
+/// Convert milliwatts into thousandths of a decibel-milliwatt, rounded to the
+/// nearest integer.
+///
+/// Non-positive powers saturate to `i64::MIN`.
+///
+/// # Examples
+/// ```
+/// use bity::db::{format_dbm, from_milliwatts};
+///
+/// assert_eq!(format_dbm(from_milliwatts(0.01)), "-20dBm");
+/// assert_eq!(format_dbm(from_milliwatts(2.0)), "3.01dBm");
+/// ```
+#[cfg(feature = "std")]
+pub fn from_milliwatts(milliwatts: f64) -> i64 {
+    from_ratio(milliwatts)
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    parse: fn(&str) -> Result<i64, Error<'_>>,
+) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return serde::Deserialize::deserialize(deserializer);
+    }
+    crate::serde::deserialize(
+        deserializer,
+        expecting,
+        units,
+        // Numbers are parsed as decibels.
+        crate::serde::Numbers::Parsed,
+        |_| None,
+        parse,
+    )
+}
+
+/// Serialize thousandths of a decibel into a string using `format`, or as a
+/// plain integer (thousandths) if the serializer isn't human readable.
+#[cfg(feature = "serde")]
+fn serialize_with<S>(
+    value: i64,
+    serializer: S,
+    format: fn(i64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format(value))
+    } else {
+        serializer.serialize_i64(value)
+    }
+}
+
+/// Serialize thousandths of a decibel into a decibel string.
+///
+/// Enabling the `serde` allows the use of `#[serde(serialize_with =
+/// "bity::db::serialize")]` and `#[serde(with = "bity::db")]` attributes.
+///
+/// ```
+/// use indoc::indoc;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Link {
+///     #[serde(serialize_with = "bity::db::serialize")]
+///     margin: i64,
+/// }
+///
+/// assert_eq!(
+///     toml::to_string(&Link { margin: 3_500 }).unwrap(),
+///     indoc! {r#"
+///         margin = "3.5dB"
+///     "#}
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub fn serialize<S>(value: &i64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_with(*value, serializer, format)
+}
+
+/// Deserialize a given integer (whole decibels) or decibel string into
+/// thousandths of a decibel.
+///
+/// Enabling the `serde` allows the use of `#[serde(deserialize_with =
+/// "bity::db::deserialize")]` and `#[serde(with = "bity::db")]` attributes.
+///
+/// ```
+/// use indoc::indoc;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct Link {
+///     #[serde(deserialize_with = "bity::db::deserialize")]
+///     margin: i64,
+///     #[serde(deserialize_with = "bity::db::deserialize")]
+///     loss: i64,
+///     #[serde(deserialize_with = "bity::db::deserialize")]
+///     gain: i64,
+/// }
+///
+/// assert_eq!(
+///     toml::from_str::<Link>(indoc! {r#"
+///         margin = "3.5dB"
+///         loss = -6
+///         gain = 1.25
+///     "#})
+///     .unwrap(),
+///     Link {
+///         margin: 3_500,
+///         loss: -6_000,
+///         gain: 1_250,
+///     }
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_with(deserializer, r#"a decibel value like "3.5dB", "-6dB" or -6"#, UNITS, parse)
+}
+
+/// Serde helpers for decibel-milliwatt values.
+///
+/// Enabling the `serde` allows the use of `#[serde(serialize_with =
+/// "bity::db::dbm::serialize")]`, `#[serde(deserialize_with =
+/// "bity::db::dbm::deserialize")]` and `#[serde(with = "bity::db::dbm")]`
+/// attributes.
+#[cfg(feature = "serde")]
+pub mod dbm {
+    /// Serialize thousandths of a decibel-milliwatt into a decibel-milliwatt
+    /// string.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Link {
+    ///     #[serde(serialize_with = "bity::db::dbm::serialize")]
+    ///     sensitivity: i64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Link { sensitivity: -28_000 }).unwrap(),
+    ///     indoc! {r#"
+    ///         sensitivity = "-28dBm"
+    ///     "#}
+    /// );
+    /// ```
+    pub fn serialize<S>(value: &i64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        super::serialize_with(*value, serializer, super::format_dbm)
+    }
+
+    /// Deserialize a given integer (whole decibel-milliwatts) or
+    /// decibel-milliwatt string into thousandths of a decibel-milliwatt.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// struct Link {
+    ///     #[serde(deserialize_with = "bity::db::dbm::deserialize")]
+    ///     sensitivity: i64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Link>(r#"sensitivity = "-28.5dBm""#).unwrap(),
+    ///     Link { sensitivity: -28_500 }
+    /// );
+    /// ```
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        super::deserialize_with(
+            deserializer,
+            r#"a decibel-milliwatt value like "-28.5dBm", "10dBm" or -28"#,
+            super::UNITS_DBM,
+            super::parse_dbm,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("3dB").unwrap(), 3_000);
+        assert_eq!(super::parse(" 3 dB ").unwrap(), 3_000);
+        assert_eq!(super::parse("-0.5dB").unwrap(), -500);
+        assert_eq!(super::parse("-.5").unwrap(), -500);
+        assert_eq!(super::parse("1.23456dB").unwrap(), 1_234);
+        assert_eq!(super::parse("+3").unwrap(), 3_000);
+        assert_eq!(super::parse_dbm("-20dBm").unwrap(), -20_000);
+        assert_eq!(super::parse_dbm("0dBm").unwrap(), 0);
+
+        assert!(matches!(super::parse("3db"), Err(Error::InvalidUnit("db"))));
+        assert!(matches!(super::parse_dbm("3dB"), Err(Error::InvalidUnit("dB"))));
+        assert!(matches!(super::parse("-"), Err(Error::EmptyInput)));
+        assert!(matches!(super::parse("--3"), Err(Error::ParseIntError("--3", None))));
+        assert!(matches!(super::parse("1.1.1"), Err(Error::TrailingGarbage(".1"))));
+        assert!(matches!(
+            super::parse("9223372036854775807"),
+            Err(Error::ParseIntError(_, None))
+        ));
+        assert!(matches!(
+            super::parse("9223372036854775.808"),
+            Err(Error::ParseIntError(_, None))
+        ));
+        assert!(matches!(
+            super::parse("-9223372036854775.809"),
+            Err(Error::ParseIntError(_, None))
+        ));
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [i64::MIN, i64::MIN + 1, -1_500, 0, 3_010, i64::MAX] {
+            assert_eq!(super::parse(&super::format(input)).unwrap(), input);
+            assert_eq!(super::parse_dbm(&super::format_dbm(input)).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(0), "0dB");
+        assert_eq!(super::format(3_010), "3.01dB");
+        assert_eq!(super::format(-1), "-0.001dB");
+        assert_eq!(super::format(i64::MIN), "-9223372036854775.808dB");
+        assert_eq!(super::format_dbm(-20_000), "-20dBm");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn conversions() {
+        assert_eq!(super::from_ratio(1.0), 0);
+        assert_eq!(super::from_ratio(100.0), 20_000);
+        assert_eq!(super::from_ratio(-1.0), i64::MIN);
+        assert_eq!(super::to_ratio(0), 1.0);
+        assert_eq!(super::from_milliwatts(1_000.0), 30_000);
+        assert_eq!(super::to_milliwatts(-10_000), 0.1);
+    }
+}
@@ -0,0 +1,146 @@
+//! [`defmt`](https://docs.rs/defmt) support, to log human readable values
+//! from embedded targets.
+//!
+//! [`Limit`], [`RelativeOrAbsolute`], [`Prefix`], [`Period`], [`Error`] and
+//! [`ErrorKind`] implement [`defmt::Format`], and plain integers are logged
+//! using [`formatted`]. Values are written using the alloc-free `format_into`
+//! functions, without any `core::fmt` formatting.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bity::{defmt::formatted, unit::Byteps};
+//!
+//! let rate = 5_200_000;
+//! defmt::info!("uploading at {}", formatted::<Byteps>(rate)); // uploading at 5.2MB/s
+//! ```
+
+use core::{
+    fmt::{self, Write},
+    marker::PhantomData,
+};
+
+use defmt::{Format, Formatter};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, si::Prefix, unit::Unit, Error, ErrorKind, Period};
+
+/// Forward the strings written using [`fmt::Write`] to a defmt formatter.
+struct Writer<'a>(Formatter<'a>);
+
+impl Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        defmt::write!(self.0, "{=str}", s);
+        Ok(())
+    }
+}
+
+/// An integer logged using the formatting of the unit `U`, created by
+/// [`formatted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Formatted<U> {
+    value: u64,
+    unit: PhantomData<U>,
+}
+
+/// Wrap an integer, so it's logged like `U::format` would format it.
+///
+/// # Examples
+/// ```no_run
+/// use bity::{defmt::formatted, unit::Byte};
+///
+/// defmt::debug!("{} written", formatted::<Byte>(1_500)); // 1.5kB written
+/// ```
+pub fn formatted<U: Unit>(value: u64) -> Formatted<U> {
+    Formatted {
+        value,
+        unit: PhantomData,
+    }
+}
+
+impl<U: Unit> Format for Formatted<U> {
+    fn format(&self, f: Formatter<'_>) {
+        // `Writer` never fails.
+        let _ = U::format_into(&mut Writer(f), self.value);
+    }
+}
+
+impl<U: Unit> Format for Limit<U> {
+    fn format(&self, f: Formatter<'_>) {
+        match self.value() {
+            Some(value) => formatted::<U>(value).format(f),
+            None => defmt::write!(f, "unlimited"),
+        }
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Unit> Format for RelativeOrAbsolute<U> {
+    fn format(&self, f: Formatter<'_>) {
+        let _ = match (self.as_relative(), self.as_absolute()) {
+            (Some(ppm), _) => crate::percent::format_into(&mut Writer(f), ppm),
+            (None, value) => U::format_into(&mut Writer(f), value.unwrap_or_default()),
+        };
+    }
+}
+
+impl Format for Prefix {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.symbol());
+    }
+}
+
+impl Format for Period {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.symbol());
+    }
+}
+
+impl Format for ErrorKind {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.as_str());
+    }
+}
+
+impl Format for Error<'_> {
+    fn format(&self, f: Formatter<'_>) {
+        match self {
+            Error::NotAscii => defmt::write!(f, "input must be ascii"),
+            Error::EmptyInput => defmt::write!(f, "missing number"),
+            Error::InvalidUnit(input) => defmt::write!(f, "invalid unit \"{=str}\"", input),
+            Error::MultipleUnits(input) => {
+                defmt::write!(f, "multiple units in \"{=str}\"", input)
+            }
+            Error::NegativeValue(input) => defmt::write!(f, "negative number \"{=str}\"", input),
+            Error::FractionTooPrecise(input) => {
+                defmt::write!(f, "too many fraction digits \"{=str}\"", input)
+            }
+            Error::TrailingGarbage(input) => {
+                defmt::write!(f, "unexpected characters \"{=str}\" after the number", input)
+            }
+            Error::ParseIntError(input, _) => defmt::write!(f, "invalid number \"{=str}\"", input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use defmt::Format;
+
+    use crate::{limit::Limit, si::Prefix, unit::Si, Error, ErrorKind, Period};
+
+    fn assert_format<T: Format>() {}
+
+    #[test]
+    fn format() {
+        assert_format::<super::Formatted<Si>>();
+        assert_format::<Limit<Si>>();
+        #[cfg(feature = "percent")]
+        assert_format::<crate::relative::RelativeOrAbsolute<Si>>();
+        assert_format::<Prefix>();
+        assert_format::<Period>();
+        assert_format::<ErrorKind>();
+        assert_format::<Error<'static>>();
+    }
+}
@@ -0,0 +1,144 @@
+//! [`diesel`](https://docs.rs/diesel) support: [`Limit`]s are stored as
+//! `Nullable<BigInt>` columns, unlimited being `NULL`.
+//!
+//! Values above `i64::MAX` can't be written, and negative values can't be
+//! read.
+
+use core::num::TryFromIntError;
+
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql, Queryable},
+    expression::AsExpression,
+    serialize::{self, Output, ToSql},
+    sql_types::{BigInt, Nullable},
+};
+
+use crate::limit::Limit;
+
+/// A limited value converted into a `BIGINT`, failing to be written if it's
+/// too large.
+#[derive(Debug, Clone, Copy)]
+pub struct Encoded(Result<i64, TryFromIntError>);
+
+impl<DB: Backend> ToSql<BigInt, DB> for Encoded
+where
+    i64: ToSql<BigInt, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        match &self.0 {
+            Ok(value) => value.to_sql(out),
+            Err(err) => Err(Box::new(*err)),
+        }
+    }
+}
+
+fn encode(value: Option<u64>) -> Option<Encoded> {
+    value.map(|value| Encoded(i64::try_from(value)))
+}
+
+impl<U> AsExpression<Nullable<BigInt>> for Limit<U> {
+    type Expression = <Option<Encoded> as AsExpression<Nullable<BigInt>>>::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        encode(self.value()).as_expression()
+    }
+}
+
+impl<U> AsExpression<Nullable<BigInt>> for &Limit<U> {
+    type Expression = <Option<Encoded> as AsExpression<Nullable<BigInt>>>::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        encode(self.value()).as_expression()
+    }
+}
+
+impl<U, DB: Backend> FromSql<Nullable<BigInt>, DB> for Limit<U>
+where
+    i64: FromSql<BigInt, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(Limit::new(u64::try_from(i64::from_sql(bytes)?)?))
+    }
+
+    fn from_nullable_sql(bytes: Option<DB::RawValue<'_>>) -> deserialize::Result<Self> {
+        match bytes {
+            Some(bytes) => Self::from_sql(bytes),
+            None => Ok(Limit::unlimited()),
+        }
+    }
+}
+
+impl<U, DB: Backend> Queryable<Nullable<BigInt>, DB> for Limit<U>
+where
+    Self: FromSql<Nullable<BigInt>, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::{
+        dsl::sql,
+        prelude::*,
+        select, sql_query,
+        sql_types::{BigInt, Nullable},
+        Connection, SqliteConnection,
+    };
+
+    use crate::{limit::Limit, unit::Si};
+
+    diesel::table! {
+        quotas (id) {
+            id -> Integer,
+            quota -> Nullable<BigInt>,
+        }
+    }
+
+    #[derive(Debug, PartialEq, Queryable, Insertable)]
+    #[diesel(table_name = quotas)]
+    struct Quota {
+        id: i32,
+        quota: Limit<Si>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        sql_query("CREATE TABLE quotas (id INTEGER PRIMARY KEY, quota BIGINT)")
+            .execute(&mut connection)
+            .unwrap();
+        let quota = |id, quota| Quota { id, quota };
+        diesel::insert_into(quotas::table)
+            .values(&[quota(1, Limit::new(1_500)), quota(2, Limit::unlimited())])
+            .execute(&mut connection)
+            .unwrap();
+        assert_eq!(
+            quotas::table
+                .order(quotas::id)
+                .load::<Quota>(&mut connection)
+                .unwrap(),
+            [quota(1, Limit::new(1_500)), quota(2, Limit::unlimited()),]
+        );
+        assert!(diesel::insert_into(quotas::table)
+            .values(quota(3, Limit::new(u64::MAX)))
+            .execute(&mut connection)
+            .is_err());
+        assert_eq!(
+            quotas::table
+                .filter(quotas::quota.eq(Limit::<Si>::new(1_500)))
+                .select(quotas::id)
+                .first::<i32>(&mut connection)
+                .unwrap(),
+            1
+        );
+
+        assert!(select(sql::<Nullable<BigInt>>("-1"))
+            .get_result::<Limit<Si>>(&mut connection)
+            .is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Humantime-like duration parsing and formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use bity::duration::{format, parse};
+//!
+//! assert_eq!(parse("1h30m").unwrap(), Duration::from_secs(5_400));
+//! assert_eq!(parse("250ms").unwrap(), Duration::from_millis(250));
+//! assert_eq!(parse("2d").unwrap(), Duration::from_secs(172_800));
+//!
+//! assert_eq!(format(Duration::from_secs(5_400)), "1h30m");
+//! assert_eq!(format(Duration::from_millis(1_250)), "1s250ms");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::duration::serialize")]`, `#[serde(deserialize_with =
+//! "bity::duration::deserialize")]` and `#[serde(with = "bity::duration")]`
+//! attributes. Integers are deserialized as seconds.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use std::time::Duration;
+//!
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::duration")]
+//!     timeout: Duration,
+//!     #[serde(with = "bity::duration")]
+//!     keep_alive: Duration,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         timeout = "1m30s"
+//!         keep-alive = 75
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         timeout: Duration::from_secs(90),
+//!         keep_alive: Duration::from_secs(75),
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         timeout: Duration::from_secs(90),
+//!         keep_alive: Duration::from_secs(75),
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         timeout = "1m30s"
+//!         keep-alive = "1m15s"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::{collections::TryReserveError, string::String};
+use core::time::Duration;
+
+use crate::error::{check_number, is_multiple_units, Error};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str =
+    r"^\s*(?:(?:[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:ns|us|ms|min|s|m|h|d|w)\s*)+$";
+
+/// Units accepted after the number, listed in error reports.
+pub const UNITS: &[&str] = &["ns", "us", "ms", "s", "m", "min", "h", "d", "w"];
+
+/// Upper bound of the length of the strings returned by [`format`]: up to 15
+/// digits of days, and three digits for each of the other units.
+pub const MAX_FORMATTED_LEN: usize = 40;
+
+/// Parse a humantime-like duration string.
+///
+/// The input is a sequence of numbers, each followed by a unit: `ns`, `us`,
+/// `ms`, `s`, `m` (or `min`), `h`, `d` or `w`. Numbers can have a fraction
+/// (`1.5h`), fractions of nanoseconds are truncated. Whitespaces are allowed
+/// between the components.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{duration::parse, Error};
+///
+/// assert_eq!(parse("1h30m").unwrap(), Duration::from_secs(5_400));
+/// assert_eq!(parse("1h 30min").unwrap(), Duration::from_secs(5_400));
+/// assert_eq!(parse("1.5h").unwrap(), Duration::from_secs(5_400));
+/// assert_eq!(parse("1s250ms").unwrap(), Duration::from_millis(1_250));
+/// assert_eq!(parse("2w").unwrap(), Duration::from_secs(1_209_600));
+/// // Invalids.
+/// assert!(matches!(parse("12"), Err(Error::InvalidUnit(""))));
+/// assert!(matches!(parse("12y"), Err(Error::InvalidUnit("y"))));
+/// assert!(matches!(parse("h"), Err(Error::EmptyInput)));
+/// ```
+pub fn parse(input: &str) -> Result<Duration, Error<'_>> {
+    if !input.is_ascii() {
+        return Err(Error::NotAscii);
+    }
+
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    if rest.starts_with('-') {
+        return Err(Error::NegativeValue(rest));
+    }
+
+    fn parse_part(part: &str) -> Result<u128, Error<'_>> {
+        if part.is_empty() {
+            return Ok(0);
+        }
+        part.parse::<u64>()
+            .map(u128::from)
+            .map_err(|err| Error::ParseIntError(part, Some(err)))
+    }
+
+    let mut total = 0u128;
+    while !rest.is_empty() {
+        let (value, tail) = rest.split_at(
+            rest.bytes()
+                .position(|b| !b.is_ascii_digit() && b != b'.')
+                .unwrap_or(rest.len()),
+        );
+        let tail = tail.trim_start();
+        let (unit_str, tail) = tail.split_at(
+            tail.bytes()
+                .position(|b| !b.is_ascii_alphabetic())
+                .unwrap_or(tail.len()),
+        );
+        let unit: u128 = match unit_str {
+            "ns" => 1,
+            "us" => 1_000,
+            "ms" => 1_000_000,
+            "s" => NANOS_PER_SEC,
+            "m" | "min" => 60 * NANOS_PER_SEC,
+            "h" => 3_600 * NANOS_PER_SEC,
+            "d" => 86_400 * NANOS_PER_SEC,
+            "w" => 604_800 * NANOS_PER_SEC,
+            _ if is_multiple_units(unit_str, UNITS) => return Err(Error::MultipleUnits(unit_str)),
+            _ => return Err(Error::InvalidUnit(unit_str)),
+        };
+        check_number(value)?;
+
+        let (integer_str, fraction_str) = value.split_once('.').unwrap_or((value, ""));
+        if integer_str.is_empty() && fraction_str.is_empty() {
+            return Err(Error::ParseIntError(value, None));
+        }
+        // Also check the digits that are dropped below.
+        if !fraction_str.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::ParseIntError(fraction_str, fraction_str.parse::<u64>().err()));
+        }
+        // Digits after the 18th can't have an effect, even on weeks.
+        let fraction_str = fraction_str[..fraction_str.len().min(18)].trim_end_matches('0');
+        total = total
+            .saturating_add(parse_part(integer_str)? * unit)
+            .saturating_add(
+                parse_part(fraction_str)? * unit / 10u128.pow(fraction_str.len() as u32),
+            );
+
+        rest = tail.trim_start();
+    }
+
+    match u64::try_from(total / NANOS_PER_SEC) {
+        Ok(secs) => Ok(Duration::new(secs, (total % NANOS_PER_SEC) as u32)),
+        Err(_) => Err(Error::ParseIntError(input.trim(), None)),
+    }
+}
+
+/// Format a duration into a humantime-like string.
+///
+/// Every non-zero component is displayed, from days to nanoseconds, making
+/// the output lossless.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::duration::format;
+///
+/// assert_eq!(format(Duration::ZERO), "0s");
+/// assert_eq!(format(Duration::from_millis(250)), "250ms");
+/// assert_eq!(format(Duration::from_secs(93_784)), "1d2h3m4s");
+/// assert_eq!(format(Duration::new(1, 1_001)), "1s1us1ns");
+/// ```
+pub fn format(input: Duration) -> String {
+    let mut output = String::with_capacity(16);
+    push(&mut output, input);
+    output
+}
+
+/// Like [`format`], but returning an error instead of aborting if the string
+/// can't be allocated.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::duration::try_format;
+///
+/// assert_eq!(try_format(Duration::from_secs(93_784)).unwrap(), "1d2h3m4s");
+/// ```
+pub fn try_format(input: Duration) -> Result<String, TryReserveError> {
+    let mut output = String::new();
+    output.try_reserve_exact(MAX_FORMATTED_LEN)?;
+    push(&mut output, input);
+    Ok(output)
+}
+
+/// Append a duration formatted like [`format`] to `output`.
+fn push(output: &mut String, input: Duration) {
+    if input.is_zero() {
+        output.push_str("0s");
+        return;
+    }
+
+    let secs = input.as_secs();
+    let nanos = input.subsec_nanos();
+    for (value, unit) in [
+        (secs / 86_400, "d"),
+        (secs / 3_600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+        (u64::from(nanos / 1_000_000), "ms"),
+        (u64::from(nanos / 1_000 % 1_000), "us"),
+        (u64::from(nanos % 1_000), "ns"),
+    ] {
+        if value != 0 {
+            output.push_str(crate::si::Digits::integer(value).as_str());
+            output.push_str(unit);
+        }
+    }
+}
+
+/// Serialize a given [`Duration`] into a humantime-like string.
+///
+/// Non human readable serializers (like bincode) use the `Duration` serde
+/// representation instead.
+///
+/// Enabling the `serde` allows the use of `#[serde(serialize_with =
+/// "bity::duration::serialize")]` and `#[serde(with = "bity::duration")]`
+/// attributes.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use indoc::indoc;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "kebab-case")]
+/// struct Configuration {
+///     #[serde(serialize_with = "bity::duration::serialize")]
+///     timeout: Duration,
+/// }
+///
+/// assert_eq!(
+///     toml::to_string(&Configuration {
+///         timeout: Duration::from_millis(1_500),
+///     }).unwrap(),
+///     indoc! {r#"
+///         timeout = "1s500ms"
+///     "#}
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format(*value))
+    } else {
+        serde::Serialize::serialize(value, serializer)
+    }
+}
+
+/// Deserialize a given integer (seconds) or humantime-like string into a
+/// [`Duration`].
+///
+/// Enabling the `serde` allows the use of `#[serde(deserialize_with =
+/// "bity::duration::deserialize")]` and `#[serde(with = "bity::duration")]`
+/// attributes.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use indoc::indoc;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// #[serde(rename_all = "kebab-case")]
+/// struct Configuration {
+///     #[serde(deserialize_with = "bity::duration::deserialize")]
+///     timeout: Duration,
+///     #[serde(deserialize_with = "bity::duration::deserialize")]
+///     keep_alive: Duration,
+/// }
+///
+/// assert_eq!(
+///     toml::from_str::<Configuration>(
+///         indoc! {r#"
+///             timeout = "1.5s"
+///             keep-alive = 75
+///         "#}
+///     ).unwrap(),
+///     Configuration {
+///         timeout: Duration::from_millis(1_500),
+///         keep_alive: Duration::from_secs(75),
+///     }
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return serde::Deserialize::deserialize(deserializer);
+    }
+    crate::serde::deserialize(
+        deserializer,
+        r#"a duration like "1.5s", "1h30m" or 90 (seconds)"#,
+        UNITS,
+        crate::serde::Numbers::Exact,
+        |n| u64::try_from(n).ok().map(Duration::from_secs),
+        parse,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("1h30m").unwrap(), Duration::from_secs(5_400));
+        assert_eq!(super::parse(" 1h 30m ").unwrap(), Duration::from_secs(5_400));
+        assert_eq!(super::parse("1 h 30 min").unwrap(), Duration::from_secs(5_400));
+        assert_eq!(super::parse("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(super::parse("2d").unwrap(), Duration::from_secs(172_800));
+        assert_eq!(super::parse(".5s").unwrap(), Duration::from_millis(500));
+        assert_eq!(super::parse("5.s").unwrap(), Duration::from_secs(5));
+        assert_eq!(super::parse("1.0000000001s").unwrap(), Duration::from_secs(1));
+        assert_eq!(super::parse("1s1us1ns").unwrap(), Duration::new(1, 1_001));
+        assert_eq!(super::parse("1m1m").unwrap(), Duration::from_secs(120));
+        assert_eq!(super::parse("18446744073709551615s").unwrap(), Duration::from_secs(u64::MAX));
+
+        assert!(matches!(super::parse(""), Err(Error::EmptyInput)));
+        assert!(matches!(super::parse("1h30"), Err(Error::InvalidUnit(""))));
+        assert!(matches!(super::parse("1H"), Err(Error::InvalidUnit("H"))));
+        assert!(matches!(super::parse("-1s"), Err(Error::NegativeValue("-1s"))));
+        assert!(matches!(super::parse(".s"), Err(Error::ParseIntError(".", None))));
+        assert!(matches!(super::parse("1.1.1s"), Err(Error::TrailingGarbage(".1"))));
+        assert!(matches!(
+            super::parse("18446744073709551615w"),
+            Err(Error::ParseIntError("18446744073709551615w", None))
+        ));
+        assert!(matches!(super::parse("1µs"), Err(Error::NotAscii)));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(Duration::ZERO), "0s");
+        assert_eq!(super::format(Duration::from_nanos(1)), "1ns");
+        assert_eq!(super::format(Duration::from_millis(1_250)), "1s250ms");
+        assert_eq!(super::format(Duration::from_secs(3_600)), "1h");
+        assert_eq!(super::format(Duration::from_secs(93_784)), "1d2h3m4s");
+
+        // Round trip.
+        let duration = Duration::new(1_234_567, 890_123_456);
+        assert_eq!(super::parse(&super::format(duration)).unwrap(), duration);
+    }
+
+    #[test]
+    fn try_format() {
+        assert_eq!(super::try_format(Duration::ZERO).unwrap(), "0s");
+        assert_eq!(
+            super::try_format(Duration::MAX).unwrap(),
+            "213503982334601d7h15s999ms999us999ns"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Environment variables parsing.
+//!
+//! Each unit family has a function reading and parsing a variable, returning
+//! `None` if it isn't set. Errors name the variable and its invalid value.
+//!
+//! # Examples
+//!
+//! ```
+//! std::env::set_var("MAX_CACHE_SIZE", "512MB");
+//! std::env::set_var("UPLOAD_RATE", "5XB/s");
+//!
+//! assert_eq!(bity::env::byte("MAX_CACHE_SIZE").unwrap(), Some(512_000_000));
+//! assert_eq!(bity::env::byte("UNSET_CACHE_SIZE").unwrap(), None);
+//! assert_eq!(
+//!     bity::env::byteps("UPLOAD_RATE").unwrap_err().to_string(),
+//!     r#"invalid value "5XB/s" for environment variable UPLOAD_RATE: invalid unit "XB""#
+//! );
+//! ```
+
+use std::{
+    env::{self, VarError},
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{Error, OwnedError};
+
+/// Error returned when an environment variable can't be read or parsed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EnvError {
+    /// The value of the variable isn't valid unicode.
+    NotUnicode {
+        /// Name of the variable.
+        name: String,
+    },
+    /// The value of the variable couldn't be parsed.
+    Invalid {
+        /// Name of the variable.
+        name: String,
+        /// Value of the variable.
+        value: String,
+        /// Parsing error.
+        error: OwnedError,
+    },
+}
+
+impl EnvError {
+    /// Name of the variable.
+    pub fn name(&self) -> &str {
+        match self {
+            EnvError::NotUnicode { name } | EnvError::Invalid { name, .. } => name,
+        }
+    }
+}
+
+impl Display for EnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::NotUnicode { name } => {
+                write!(f, "environment variable {name} must be valid unicode")
+            }
+            EnvError::Invalid { name, value, error } => {
+                write!(f, r#"invalid value "{value}" for environment variable {name}: {error}"#)
+            }
+        }
+    }
+}
+
+impl StdError for EnvError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            EnvError::NotUnicode { .. } => None,
+            EnvError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Read the environment variable `name`, and parse it using `parse`.
+///
+/// Returns `None` if the variable isn't set.
+///
+/// # Examples
+/// ```
+/// use bity::{env::var, limit::ByteLimit};
+///
+/// std::env::set_var("DISK_QUOTA", "unlimited");
+/// assert_eq!(var("DISK_QUOTA", ByteLimit::parse).unwrap(), Some(ByteLimit::unlimited()));
+/// ```
+pub fn var<T>(
+    name: &str,
+    parse: impl for<'a> Fn(&'a str) -> Result<T, Error<'a>>,
+) -> Result<Option<T>, EnvError> {
+    let value = match env::var(name) {
+        Ok(value) => value,
+        Err(VarError::NotPresent) => return Ok(None),
+        Err(VarError::NotUnicode(_)) => {
+            return Err(EnvError::NotUnicode {
+                name: name.to_owned(),
+            })
+        }
+    };
+    match parse(&value) {
+        Ok(parsed) => Ok(Some(parsed)),
+        Err(err) => {
+            let error = err.into_owned();
+            Err(EnvError::Invalid {
+                name: name.to_owned(),
+                value,
+                error,
+            })
+        }
+    }
+}
+
+macro_rules! vars {
+    ($($(#[doc = $doc:literal])* $(#[cfg($cfg:meta)])? $name:ident => $parse:path: $value:ty,)*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            pub fn $name(name: &str) -> Result<Option<$value>, EnvError> {
+                var(name, $parse)
+            }
+        )*
+    };
+}
+
+vars! {
+    /// Read an environment variable using [`si::parse`](crate::si::parse).
+    si => crate::si::parse: u64,
+    /// Read an environment variable using [`bit::parse`](crate::bit::parse).
+    #[cfg(feature = "bit")]
+    bit => crate::bit::parse: u64,
+    /// Read an environment variable using [`byte::parse`](crate::byte::parse).
+    #[cfg(feature = "byte")]
+    byte => crate::byte::parse: u64,
+    /// Read an environment variable using
+    /// [`packet::parse`](crate::packet::parse).
+    #[cfg(feature = "packet")]
+    packet => crate::packet::parse: u64,
+    /// Read an environment variable using [`bps::parse`](crate::bps::parse).
+    #[cfg(feature = "bps")]
+    bps => crate::bps::parse: u64,
+    /// Read an environment variable using
+    /// [`byteps::parse`](crate::byteps::parse).
+    #[cfg(feature = "byteps")]
+    byteps => crate::byteps::parse: u64,
+    /// Read an environment variable using [`pps::parse`](crate::pps::parse).
+    #[cfg(feature = "pps")]
+    pps => crate::pps::parse: u64,
+    /// Read an environment variable using [`bpp::parse`](crate::bpp::parse).
+    #[cfg(feature = "bpp")]
+    bpp => crate::bpp::parse: u64,
+    /// Read an environment variable using [`hz::parse`](crate::hz::parse).
+    #[cfg(feature = "hz")]
+    hz => crate::hz::parse: u64,
+    /// Read an environment variable using [`watt::parse`](crate::watt::parse).
+    #[cfg(feature = "watt")]
+    watt => crate::watt::parse: u64,
+    /// Read an environment variable using [`ops::parse`](crate::ops::parse).
+    #[cfg(feature = "ops")]
+    ops => crate::ops::parse: u64,
+    /// Read an environment variable using
+    /// [`percent::parse`](crate::percent::parse).
+    #[cfg(feature = "percent")]
+    percent => crate::percent::parse: u64,
+    /// Read an environment variable using
+    /// [`compression::parse`](crate::compression::parse).
+    #[cfg(feature = "compression")]
+    compression => crate::compression::parse: u64,
+    /// Read an environment variable using [`db::parse`](crate::db::parse).
+    #[cfg(feature = "db")]
+    db => crate::db::parse: i64,
+    /// Read an environment variable using
+    /// [`db::parse_dbm`](crate::db::parse_dbm).
+    #[cfg(feature = "db")]
+    dbm => crate::db::parse_dbm: i64,
+    /// Read an environment variable using
+    /// [`duration::parse`](crate::duration::parse).
+    #[cfg(feature = "duration")]
+    duration => crate::duration::parse: std::time::Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvError;
+
+    #[cfg(all(feature = "byte", feature = "db", feature = "duration"))]
+    #[test]
+    fn read() {
+        use std::time::Duration;
+
+        std::env::set_var("BITY_TEST_ENV_SIZE", " 1.5kB ");
+        std::env::set_var("BITY_TEST_ENV_POWER", "-20dBm");
+        std::env::set_var("BITY_TEST_ENV_TIMEOUT", "1m30s");
+        assert_eq!(super::byte("BITY_TEST_ENV_SIZE").unwrap(), Some(1_500));
+        assert_eq!(super::dbm("BITY_TEST_ENV_POWER").unwrap(), Some(-20_000));
+        assert_eq!(
+            super::duration("BITY_TEST_ENV_TIMEOUT").unwrap(),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(super::byte("BITY_TEST_ENV_UNSET").unwrap(), None);
+    }
+
+    #[cfg(feature = "bps")]
+    #[test]
+    fn error() {
+        use std::error::Error as _;
+
+        use crate::ErrorKind;
+
+        std::env::set_var("BITY_TEST_ENV_RATE", "-1kbps");
+        let err = super::bps("BITY_TEST_ENV_RATE").unwrap_err();
+        assert_eq!(err.name(), "BITY_TEST_ENV_RATE");
+        assert!(matches!(
+            &err,
+            EnvError::Invalid { value, error, .. }
+                if value == "-1kbps" && error.kind() == ErrorKind::NegativeValue
+        ));
+        assert_eq!(
+            err.to_string(),
+            r#"invalid value "-1kbps" for environment variable BITY_TEST_ENV_RATE: negative number "-1""#
+        );
+        assert_eq!(err.source().unwrap().to_string(), r#"negative number "-1""#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn not_unicode() {
+        use std::{error::Error as _, ffi::OsString, os::unix::ffi::OsStringExt};
+
+        std::env::set_var("BITY_TEST_ENV_BINARY", OsString::from_vec(vec![0xff]));
+        let err = super::si("BITY_TEST_ENV_BINARY").unwrap_err();
+        assert!(matches!(err, EnvError::NotUnicode { .. }));
+        assert_eq!(
+            err.to_string(),
+            "environment variable BITY_TEST_ENV_BINARY must be valid unicode"
+        );
+        assert!(err.source().is_none());
+    }
+}
@@ -1,17 +1,39 @@
-use std::{
-    error::Error as StdError,
+use alloc::{borrow::ToOwned, format, string::String, vec};
+use core::{
     fmt,
     fmt::{Display, Formatter},
     num::ParseIntError,
+    ops::Range,
 };
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
 
 /// Errors that can happen when using parsing functions.
+///
+/// Errors borrow the invalid part of the input, use [`Error::into_owned`] to
+/// get an [`OwnedError`] instead.
+///
+/// New variants may be added, match on [`Error::kind`] and use
+/// [`Error::fragment`] to handle errors without depending on the variants.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error<'s> {
     /// The input string is not fully ASCII.
     NotAscii,
+    /// The input doesn't contain a number: it's empty, or only has a unit.
+    EmptyInput,
     /// The unit string is invalid.
     InvalidUnit(&'s str),
+    /// The unit string is made of multiple units (or prefixes), like `kk` or
+    /// `%%`.
+    MultipleUnits(&'s str),
+    /// The number is negative, while only positive numbers are allowed.
+    NegativeValue(&'s str),
+    /// The fraction has too many digits to be parsed.
+    FractionTooPrecise(&'s str),
+    /// The number is followed by characters that aren't part of a number nor
+    /// of a unit, like the `.3` of `1.2.3`.
+    TrailingGarbage(&'s str),
     /// The numeric part of the input could not be parsed.
     ParseIntError(&'s str, Option<ParseIntError>),
 }
@@ -20,20 +42,603 @@ impl Display for Error<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Error::NotAscii => write!(f, "input must be ascii"),
+            Error::EmptyInput => write!(f, "missing number"),
             Error::InvalidUnit(input) => write!(f, r#"invalid unit "{input}""#),
+            Error::MultipleUnits(input) => write!(f, r#"multiple units in "{input}""#),
+            Error::NegativeValue(input) => write!(f, r#"negative number "{input}""#),
+            Error::FractionTooPrecise(input) => write!(f, r#"too many fraction digits "{input}""#),
+            Error::TrailingGarbage(input) => {
+                write!(f, r#"unexpected characters "{input}" after the number"#)
+            }
             Error::ParseIntError(input, _) => write!(f, r#"invalid number "{input}""#),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl StdError for Error<'_> {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
-            Error::NotAscii => None,
             Error::ParseIntError(_, err) => {
                 err.as_ref().map(|err| err as &(dyn StdError + 'static))
             }
-            Error::InvalidUnit(_) => None,
+            _ => None,
+        }
+    }
+}
+
+/// Kind of an [`Error`], stable and cheap to compare, to map errors to
+/// application-specific codes.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, ErrorKind};
+///
+/// fn code(input: &str) -> u16 {
+///     match byte::parse(input).map_err(|err| err.kind()) {
+///         Ok(_) => 0,
+///         Err(ErrorKind::InvalidUnit | ErrorKind::MultipleUnits) => 1001,
+///         Err(_) => 1000,
+///     }
+/// }
+///
+/// assert_eq!(code("12kB"), 0);
+/// assert_eq!(code("12kx"), 1001);
+/// assert_eq!(code("-12kB"), 1000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [`Error::NotAscii`].
+    NotAscii,
+    /// See [`Error::EmptyInput`].
+    EmptyInput,
+    /// See [`Error::InvalidUnit`].
+    InvalidUnit,
+    /// See [`Error::MultipleUnits`].
+    MultipleUnits,
+    /// See [`Error::NegativeValue`].
+    NegativeValue,
+    /// See [`Error::FractionTooPrecise`].
+    FractionTooPrecise,
+    /// See [`Error::TrailingGarbage`].
+    TrailingGarbage,
+    /// See [`Error::ParseIntError`].
+    InvalidNumber,
+}
+
+impl ErrorKind {
+    /// Snake case name of the kind, like `invalid_unit`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::ErrorKind;
+    ///
+    /// assert_eq!(ErrorKind::InvalidUnit.as_str(), "invalid_unit");
+    /// ```
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::NotAscii => "not_ascii",
+            ErrorKind::EmptyInput => "empty_input",
+            ErrorKind::InvalidUnit => "invalid_unit",
+            ErrorKind::MultipleUnits => "multiple_units",
+            ErrorKind::NegativeValue => "negative_value",
+            ErrorKind::FractionTooPrecise => "fraction_too_precise",
+            ErrorKind::TrailingGarbage => "trailing_garbage",
+            ErrorKind::InvalidNumber => "invalid_number",
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'s> Error<'s> {
+    /// Kind of the error.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{byte, ErrorKind};
+    ///
+    /// assert_eq!(byte::parse("12kx").unwrap_err().kind(), ErrorKind::InvalidUnit);
+    /// assert_eq!(byte::parse("").unwrap_err().kind(), ErrorKind::EmptyInput);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::NotAscii => ErrorKind::NotAscii,
+            Error::EmptyInput => ErrorKind::EmptyInput,
+            Error::InvalidUnit(_) => ErrorKind::InvalidUnit,
+            Error::MultipleUnits(_) => ErrorKind::MultipleUnits,
+            Error::NegativeValue(_) => ErrorKind::NegativeValue,
+            Error::FractionTooPrecise(_) => ErrorKind::FractionTooPrecise,
+            Error::TrailingGarbage(_) => ErrorKind::TrailingGarbage,
+            Error::ParseIntError(..) => ErrorKind::InvalidNumber,
+        }
+    }
+
+    /// Part of the input that caused the error, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::byte;
+    ///
+    /// assert_eq!(byte::parse("12 kx").unwrap_err().fragment(), Some("kx"));
+    /// assert_eq!(byte::parse("1.2.3kB").unwrap_err().fragment(), Some(".3"));
+    /// assert_eq!(byte::parse("").unwrap_err().fragment(), None);
+    /// ```
+    pub fn fragment(&self) -> Option<&'s str> {
+        match self {
+            Error::NotAscii | Error::EmptyInput => None,
+            Error::InvalidUnit(part)
+            | Error::MultipleUnits(part)
+            | Error::NegativeValue(part)
+            | Error::FractionTooPrecise(part)
+            | Error::TrailingGarbage(part)
+            | Error::ParseIntError(part, _) => Some(part),
+        }
+    }
+
+    /// Byte range of the invalid part of `input`, which must be the string
+    /// given to the parsing function that returned the error.
+    ///
+    /// Returns `None` if the error doesn't come from `input`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::byte;
+    ///
+    /// let input = "quota: 12 kx";
+    /// let err = byte::parse(&input[7..]).unwrap_err();
+    /// assert_eq!(err.span(input), Some(10..12));
+    /// assert_eq!(byte::parse("1.5€").unwrap_err().span("1.5€"), Some(3..6));
+    /// ```
+    pub fn span(&self, input: &str) -> Option<Range<usize>> {
+        let part = match self {
+            Error::NotAscii => {
+                return input
+                    .char_indices()
+                    .find(|(_, c)| !c.is_ascii())
+                    .map(|(start, c)| start..start + c.len_utf8());
+            }
+            _ => self.fragment()?,
+        };
+        let start = (part.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+        let end = start + part.len();
+        (end <= input.len()).then_some(start..end)
+    }
+
+    /// Closest valid unit (possibly SI prefixed) to the invalid one, among
+    /// `units` (like [`bps::UNITS`](crate::bps::UNITS)).
+    ///
+    /// Returns `None` if the error isn't about an invalid unit, or if no unit
+    /// is close enough.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{bps, byte, duration};
+    ///
+    /// let err = bps::parse("12GBps/s").unwrap_err();
+    /// assert_eq!(err.suggestion(bps::UNITS).as_deref(), Some("GBps"));
+    /// let err = byte::parse("12kb").unwrap_err();
+    /// assert_eq!(err.suggestion(byte::UNITS).as_deref(), Some("kB"));
+    /// let err = duration::parse("1hr").unwrap_err();
+    /// assert_eq!(err.suggestion(duration::UNITS).as_deref(), Some("h"));
+    /// let err = byte::parse("12 bananas").unwrap_err();
+    /// assert_eq!(err.suggestion(byte::UNITS), None);
+    /// ```
+    pub fn suggestion(&self, units: &[&str]) -> Option<String> {
+        match self {
+            Error::InvalidUnit(unit) => closest_unit(unit, units),
+            _ => None,
+        }
+    }
+
+    /// Copy the borrowed parts of the input, to store the error or send it
+    /// across threads.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{byte, OwnedError};
+    ///
+    /// fn quota(input: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    ///     Ok(byte::parse(input).map_err(|err| err.into_owned())?)
+    /// }
+    ///
+    /// assert_eq!(quota("12kx").unwrap_err().to_string(), r#"invalid unit "kx""#);
+    /// assert!(matches!(
+    ///     byte::parse("12kx").unwrap_err().into_owned(),
+    ///     OwnedError::InvalidUnit(unit) if unit == "kx"
+    /// ));
+    /// ```
+    pub fn into_owned(self) -> OwnedError {
+        match self {
+            Error::NotAscii => OwnedError::NotAscii,
+            Error::EmptyInput => OwnedError::EmptyInput,
+            Error::InvalidUnit(input) => OwnedError::InvalidUnit(input.to_owned()),
+            Error::MultipleUnits(input) => OwnedError::MultipleUnits(input.to_owned()),
+            Error::NegativeValue(input) => OwnedError::NegativeValue(input.to_owned()),
+            Error::FractionTooPrecise(input) => OwnedError::FractionTooPrecise(input.to_owned()),
+            Error::TrailingGarbage(input) => OwnedError::TrailingGarbage(input.to_owned()),
+            Error::ParseIntError(input, err) => OwnedError::ParseIntError(input.to_owned(), err),
+        }
+    }
+}
+
+/// Owned version of [`Error`], without lifetime, created by
+/// [`Error::into_owned`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum OwnedError {
+    /// The input string is not fully ASCII.
+    NotAscii,
+    /// The input doesn't contain a number: it's empty, or only has a unit.
+    EmptyInput,
+    /// The unit string is invalid.
+    InvalidUnit(String),
+    /// The unit string is made of multiple units (or prefixes).
+    MultipleUnits(String),
+    /// The number is negative, while only positive numbers are allowed.
+    NegativeValue(String),
+    /// The fraction has too many digits to be parsed.
+    FractionTooPrecise(String),
+    /// The number is followed by characters that aren't part of a number nor
+    /// of a unit.
+    TrailingGarbage(String),
+    /// The numeric part of the input could not be parsed.
+    ParseIntError(String, Option<ParseIntError>),
+}
+
+impl OwnedError {
+    /// Owned version of [`Error::kind`].
+    pub fn kind(&self) -> ErrorKind {
+        self.as_error().kind()
+    }
+
+    /// Owned version of [`Error::fragment`].
+    pub fn fragment(&self) -> Option<&str> {
+        self.as_error().fragment()
+    }
+
+    /// Borrowed version of the error, without its source.
+    fn as_error(&self) -> Error<'_> {
+        match self {
+            OwnedError::NotAscii => Error::NotAscii,
+            OwnedError::EmptyInput => Error::EmptyInput,
+            OwnedError::InvalidUnit(input) => Error::InvalidUnit(input),
+            OwnedError::MultipleUnits(input) => Error::MultipleUnits(input),
+            OwnedError::NegativeValue(input) => Error::NegativeValue(input),
+            OwnedError::FractionTooPrecise(input) => Error::FractionTooPrecise(input),
+            OwnedError::TrailingGarbage(input) => Error::TrailingGarbage(input),
+            OwnedError::ParseIntError(input, _) => Error::ParseIntError(input, None),
+        }
+    }
+
+    /// Owned version of [`Error::span`], `input` being the string given to
+    /// the parsing function that returned the error.
+    ///
+    /// As the error doesn't borrow `input` anymore, its fragment is looked up
+    /// in `input`: from the end for units and trailing characters, from the
+    /// start for numbers. Returns `None` if `input` doesn't contain it.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::byte;
+    ///
+    /// let input = "quota: 12 kx";
+    /// let err = byte::parse(&input[7..]).unwrap_err().into_owned();
+    /// assert_eq!(err.span(input), Some(10..12));
+    /// assert_eq!(err.span("12kB"), None);
+    /// ```
+    pub fn span(&self, input: &str) -> Option<Range<usize>> {
+        let start = match self {
+            OwnedError::NotAscii | OwnedError::EmptyInput => return self.as_error().span(input),
+            OwnedError::InvalidUnit(part)
+            | OwnedError::MultipleUnits(part)
+            | OwnedError::TrailingGarbage(part) => input.rfind(part.as_str())?,
+            OwnedError::NegativeValue(part)
+            | OwnedError::FractionTooPrecise(part)
+            | OwnedError::ParseIntError(part, _) => input.find(part.as_str())?,
+        };
+        Some(start..start + self.fragment()?.len())
+    }
+
+    /// Owned version of [`Error::suggestion`].
+    pub fn suggestion(&self, units: &[&str]) -> Option<String> {
+        self.as_error().suggestion(units)
+    }
+}
+
+impl From<Error<'_>> for OwnedError {
+    fn from(err: Error<'_>) -> Self {
+        err.into_owned()
+    }
+}
+
+impl Display for OwnedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_error().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdError for OwnedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            OwnedError::ParseIntError(_, err) => {
+                err.as_ref().map(|err| err as &(dyn StdError + 'static))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Check the parts of a (trimmed) positive number that can't be checked by
+/// `u64::from_str`: its presence, its sign and its end.
+pub(crate) fn check_number(value: &str) -> Result<(), Error<'_>> {
+    if value.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    if value.starts_with('-') {
+        return Err(Error::NegativeValue(value));
+    }
+
+    let digits = |from: usize| from + crate::scan::digits_end(&value.as_bytes()[from..]);
+    let integer_end = digits(usize::from(value.starts_with('+')));
+    let end = if value[integer_end..].starts_with('.') {
+        digits(integer_end + 1)
+    } else {
+        integer_end
+    };
+    if end < value.len() && value[..end].bytes().any(|b| b.is_ascii_digit()) {
+        return Err(Error::TrailingGarbage(value[end..].trim_start()));
+    }
+    Ok(())
+}
+
+/// Whether `unit` is a sequence of at least two of the `units`.
+pub(crate) fn is_multiple_units(unit: &str, units: &[&str]) -> bool {
+    fn split(unit: &str, units: &[&str], count: usize) -> bool {
+        if unit.is_empty() {
+            return count >= 2;
+        }
+        units.iter().any(|candidate| {
+            !candidate.is_empty()
+                && unit
+                    .strip_prefix(candidate)
+                    .is_some_and(|rest| split(rest, units, count + 1))
+        })
+    }
+    split(unit, units, 0)
+}
+
+/// Closest unit of `units` to `unit`, optionally keeping its SI prefix.
+///
+/// Ties are won by the unprefixed units, then by the first unit in `units`.
+fn closest_unit(unit: &str, units: &[&str]) -> Option<String> {
+    let closest = |unit: &str| {
+        units
+            .iter()
+            .map(|candidate| (distance(unit, candidate), *candidate))
+            .min_by_key(|(distance, _)| *distance)
+            .filter(|(distance, _)| *distance <= unit.len().max(3) / 3)
+    };
+    let unprefixed = closest(unit).map(|(distance, candidate)| (distance, candidate.to_owned()));
+    let prefixed = match unit.as_bytes() {
+        [b'k' | b'K' | b'm' | b'M' | b'g' | b'G' | b't' | b'T' | b'p' | b'P' | b'e' | b'E', _, ..] => {
+            closest(&unit[1..])
+                .map(|(distance, candidate)| (distance, format!("{}{candidate}", &unit[..1])))
+        }
+        _ => None,
+    };
+    match (unprefixed, prefixed) {
+        (Some(unprefixed), Some(prefixed)) if prefixed.0 < unprefixed.0 => Some(prefixed.1),
+        (None, prefixed) => prefixed.map(|(_, candidate)| candidate),
+        (unprefixed, _) => unprefixed.map(|(_, candidate)| candidate),
+    }
+}
+
+/// Optimal string alignment distance between two ASCII strings: the number of
+/// insertions, deletions, substitutions and transpositions needed to turn `a`
+/// into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    rows[0] = (0..=b.len()).collect();
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            rows[i][j] = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                rows[i][j] = rows[i][j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use std::error::Error as _;
+
+    #[cfg(feature = "byte")]
+    use super::ErrorKind;
+    use super::{Error, OwnedError};
+
+    #[test]
+    fn into_owned() {
+        let err = crate::si::parse("12x").unwrap_err().into_owned();
+        assert!(matches!(&err, OwnedError::InvalidUnit(unit) if unit == "x"));
+        assert_eq!(err.to_string(), r#"invalid unit "x""#);
+
+        let err = OwnedError::from(crate::si::parse("99999999999999999999k").unwrap_err());
+        assert!(
+            matches!(&err, OwnedError::ParseIntError(input, Some(_)) if input == "99999999999999999999")
+        );
+        assert_eq!(err.to_string(), r#"invalid number "99999999999999999999""#);
+        #[cfg(feature = "std")]
+        assert!(err.source().is_some());
+
+        assert!(matches!(Error::NotAscii.into_owned(), OwnedError::NotAscii));
+
+        // Can be moved to another thread.
+        let err = std::thread::spawn(move || err).join().unwrap();
+        assert!(matches!(err, OwnedError::ParseIntError(..)));
+    }
+
+    #[cfg(all(
+        feature = "byte",
+        feature = "hz",
+        feature = "duration",
+        feature = "db",
+        feature = "percent"
+    ))]
+    #[test]
+    fn variants() {
+        assert!(matches!(crate::byte::parse(""), Err(Error::EmptyInput)));
+        assert!(matches!(crate::byte::parse(" kB "), Err(Error::EmptyInput)));
+        assert!(matches!(crate::byte::parse("12kBB"), Err(Error::MultipleUnits("kBB"))));
+        assert!(matches!(crate::hz::parse("12HzHz"), Err(Error::MultipleUnits("HzHz"))));
+        assert!(matches!(crate::duration::parse("1hm"), Err(Error::MultipleUnits("hm"))));
+        assert!(matches!(crate::db::parse("3dBdB"), Err(Error::MultipleUnits("dBdB"))));
+        assert!(matches!(crate::byte::parse("-12kB"), Err(Error::NegativeValue("-12"))));
+        assert!(matches!(crate::percent::parse("-5%"), Err(Error::NegativeValue("-5"))));
+        assert!(matches!(
+            crate::si::parse("1.00000000000000000001k"),
+            Err(Error::FractionTooPrecise("00000000000000000001"))
+        ));
+        assert_eq!(crate::si::parse("1.10000000000000000000k").unwrap(), 1_100);
+        assert!(matches!(crate::byte::parse("12 kB!"), Err(Error::InvalidUnit("kB!"))));
+        assert!(matches!(crate::byte::parse("1,5kB"), Err(Error::TrailingGarbage(",5"))));
+        assert!(matches!(crate::percent::parse("1 2%"), Err(Error::TrailingGarbage("2"))));
+
+        assert_eq!(Error::EmptyInput.to_string(), "missing number");
+        assert_eq!(Error::MultipleUnits("kk").to_string(), r#"multiple units in "kk""#);
+        assert_eq!(Error::NegativeValue("-1").to_string(), r#"negative number "-1""#);
+        assert_eq!(
+            Error::FractionTooPrecise("00000000000000000001").to_string(),
+            r#"too many fraction digits "00000000000000000001""#
+        );
+        assert_eq!(
+            Error::TrailingGarbage(",5").to_string(),
+            r#"unexpected characters ",5" after the number"#
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn kind() {
+        assert_eq!(crate::byte::parse("1.5€").unwrap_err().kind(), ErrorKind::NotAscii);
+        assert_eq!(crate::byte::parse("kB").unwrap_err().kind(), ErrorKind::EmptyInput);
+        assert_eq!(crate::byte::parse("12kx").unwrap_err().kind(), ErrorKind::InvalidUnit);
+        assert_eq!(crate::byte::parse("12kk").unwrap_err().kind(), ErrorKind::MultipleUnits);
+        assert_eq!(crate::byte::parse("-12").unwrap_err().kind(), ErrorKind::NegativeValue);
+        assert_eq!(
+            crate::byte::parse("1.00000000000000000001")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::FractionTooPrecise
+        );
+        assert_eq!(crate::byte::parse("1.2.3").unwrap_err().kind(), ErrorKind::TrailingGarbage);
+        assert_eq!(crate::byte::parse(".kB").unwrap_err().kind(), ErrorKind::InvalidNumber);
+        assert_eq!(ErrorKind::FractionTooPrecise.to_string(), "fraction_too_precise");
+
+        let err = crate::byte::parse("12kx").unwrap_err();
+        assert_eq!(err.fragment(), Some("kx"));
+        let err = err.into_owned();
+        assert_eq!(err.kind(), ErrorKind::InvalidUnit);
+        assert_eq!(err.fragment(), Some("kx"));
+        assert_eq!(Error::NotAscii.fragment(), None);
+        assert_eq!(OwnedError::EmptyInput.fragment(), None);
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(super::distance("", ""), 0);
+        assert_eq!(super::distance("Bps", "Bps"), 0);
+        assert_eq!(super::distance("bsp", "bps"), 1);
+        assert_eq!(super::distance("mbps", "Mbps"), 1);
+        assert_eq!(super::distance("Hzz", "Hz"), 1);
+        assert_eq!(super::distance("kbit", "b/s"), 3);
+        assert_eq!(super::distance("", "ppm"), 3);
+    }
+
+    #[cfg(all(
+        feature = "bps",
+        feature = "byte",
+        feature = "hz",
+        feature = "percent",
+        feature = "duration"
+    ))]
+    #[test]
+    fn suggestion() {
+        let suggest = |unit, units| Error::InvalidUnit(unit).suggestion(units);
+        assert_eq!(suggest("GBps", crate::bps::UNITS).as_deref(), Some("GBps"));
+        assert_eq!(suggest("Gbsp", crate::bps::UNITS).as_deref(), Some("Gbps"));
+        assert_eq!(suggest("kHzz", crate::hz::UNITS).as_deref(), Some("kHz"));
+        assert_eq!(suggest("pmm", crate::percent::UNITS).as_deref(), Some("ppm"));
+        assert_eq!(suggest("hr", crate::duration::UNITS).as_deref(), Some("h"));
+        assert_eq!(suggest("bytes", crate::byte::UNITS), None);
+        assert_eq!(Error::NotAscii.suggestion(crate::byte::UNITS), None);
+        assert_eq!(
+            crate::byte::parse("1kb")
+                .unwrap_err()
+                .into_owned()
+                .suggestion(crate::byte::UNITS)
+                .as_deref(),
+            Some("kB")
+        );
+    }
+
+    #[cfg(all(feature = "byte", feature = "duration", feature = "compression"))]
+    #[test]
+    fn span() {
+        let input = " 1.2.3kB";
+        assert_eq!(crate::byte::parse(input).unwrap_err().span(input), Some(4..6));
+        let input = "12 kx ";
+        assert_eq!(crate::byte::parse(input).unwrap_err().span(input), Some(3..5));
+        let input = "1h 30y";
+        assert_eq!(crate::duration::parse(input).unwrap_err().span(input), Some(5..6));
+        let input = "3%:1";
+        assert_eq!(crate::compression::parse(input).unwrap_err().span(input), Some(1..2));
+        let input = "12";
+        assert_eq!(crate::duration::parse(input).unwrap_err().span(input), Some(2..2));
+        let input = "µs";
+        assert_eq!(crate::duration::parse(input).unwrap_err().span(input), Some(0..2));
+        assert_eq!(crate::byte::parse("12kx").unwrap_err().span("other"), None);
+        assert_eq!(Error::NotAscii.span("ascii"), None);
+    }
+
+    #[cfg(all(feature = "byte", feature = "duration", feature = "compression"))]
+    #[test]
+    fn owned_span() {
+        for input in [
+            " 1.2.3kB",
+            "12 kx ",
+            "-12kB",
+            "1.00000000000000000001kB",
+            "99999999999999999999kB",
+            "12kBB",
+            "1.5€",
+            "kB",
+        ] {
+            let err = crate::byte::parse(input).unwrap_err();
+            assert_eq!(err.clone().into_owned().span(input), err.span(input), "{input:?}");
+        }
+        for input in ["1h 30y", "12", "µs"] {
+            let err = crate::duration::parse(input).unwrap_err();
+            assert_eq!(err.clone().into_owned().span(input), err.span(input), "{input:?}");
         }
+        let err = crate::compression::parse("3%:1").unwrap_err();
+        assert_eq!(err.into_owned().span("3%:1"), Some(1..2));
+        assert_eq!(OwnedError::InvalidUnit("kx".to_owned()).span("12kB"), None);
     }
 }
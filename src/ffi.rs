@@ -0,0 +1,402 @@
+//! C bindings, to link network tooling written in C or C++ against bity.
+//!
+//! Each unit family exports a `bity_<family>_parse` and a
+//! `bity_<family>_format` function, declared in `include/bity.h`. Parsing
+//! functions take a NUL-terminated string and write the parsed value to an
+//! output pointer. Formatting functions write a NUL-terminated string to a
+//! caller-provided buffer: a buffer of `MAX_FORMATTED_LEN + 1` bytes (like
+//! [`byte::MAX_FORMATTED_LEN`](crate::byte::MAX_FORMATTED_LEN)) is always
+//! large enough. All functions return a [`Status`], whose values are stable.
+//!
+//! The shared library is built with:
+//!
+//! ```sh
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! # Examples
+//!
+//! ```c
+//! #include <bity.h>
+//!
+//! uint64_t rate;
+//! bity_status status = bity_byteps_parse("5MB/s", &rate);
+//! if (status != BITY_OK) {
+//!     fprintf(stderr, "invalid rate: %s\n", bity_status_str(status));
+//! }
+//!
+//! char buffer[BITY_FORMAT_BUFFER_LEN];
+//! bity_byte_format(1500, buffer, sizeof(buffer), NULL); // "1.5kB"
+//! ```
+
+use std::{
+    ffi::{c_char, c_int, CStr},
+    ptr,
+};
+
+use crate::{Error, ErrorKind};
+
+/// Status returned by the C functions.
+///
+/// Parsing errors use the codes 1 to 99, one per [`ErrorKind`], and misuses of
+/// the functions use codes starting at 100.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Status {
+    /// The function succeeded.
+    Ok = 0,
+    /// See [`ErrorKind::NotAscii`]. Also returned for inputs that aren't
+    /// valid UTF-8.
+    NotAscii = 1,
+    /// See [`ErrorKind::EmptyInput`].
+    EmptyInput = 2,
+    /// See [`ErrorKind::InvalidUnit`].
+    InvalidUnit = 3,
+    /// See [`ErrorKind::MultipleUnits`].
+    MultipleUnits = 4,
+    /// See [`ErrorKind::NegativeValue`].
+    NegativeValue = 5,
+    /// See [`ErrorKind::FractionTooPrecise`].
+    FractionTooPrecise = 6,
+    /// See [`ErrorKind::TrailingGarbage`].
+    TrailingGarbage = 7,
+    /// See [`ErrorKind::InvalidNumber`].
+    InvalidNumber = 8,
+    /// A required pointer argument is null.
+    NullPointer = 100,
+    /// The buffer is too small to hold the formatted string and its NUL
+    /// terminator.
+    BufferTooSmall = 101,
+}
+
+impl Status {
+    /// Snake case name of the status, like `invalid_unit`.
+    pub fn as_c_str(self) -> &'static CStr {
+        match self {
+            Status::Ok => c"ok",
+            Status::NotAscii => c"not_ascii",
+            Status::EmptyInput => c"empty_input",
+            Status::InvalidUnit => c"invalid_unit",
+            Status::MultipleUnits => c"multiple_units",
+            Status::NegativeValue => c"negative_value",
+            Status::FractionTooPrecise => c"fraction_too_precise",
+            Status::TrailingGarbage => c"trailing_garbage",
+            Status::InvalidNumber => c"invalid_number",
+            Status::NullPointer => c"null_pointer",
+            Status::BufferTooSmall => c"buffer_too_small",
+        }
+    }
+}
+
+impl From<ErrorKind> for Status {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::NotAscii => Status::NotAscii,
+            ErrorKind::EmptyInput => Status::EmptyInput,
+            ErrorKind::InvalidUnit => Status::InvalidUnit,
+            ErrorKind::MultipleUnits => Status::MultipleUnits,
+            ErrorKind::NegativeValue => Status::NegativeValue,
+            ErrorKind::FractionTooPrecise => Status::FractionTooPrecise,
+            ErrorKind::TrailingGarbage => Status::TrailingGarbage,
+            ErrorKind::InvalidNumber => Status::InvalidNumber,
+        }
+    }
+}
+
+/// Name of a status code, as a static NUL-terminated string (like
+/// `invalid_unit`), or `unknown` for codes that aren't a [`Status`].
+///
+/// The code is taken as an integer, as C callers may pass any value (like a
+/// status from a newer header).
+#[no_mangle]
+pub extern "C" fn bity_status_str(status: c_int) -> *const c_char {
+    const STATUSES: [Status; 11] = [
+        Status::Ok,
+        Status::NotAscii,
+        Status::EmptyInput,
+        Status::InvalidUnit,
+        Status::MultipleUnits,
+        Status::NegativeValue,
+        Status::FractionTooPrecise,
+        Status::TrailingGarbage,
+        Status::InvalidNumber,
+        Status::NullPointer,
+        Status::BufferTooSmall,
+    ];
+    STATUSES
+        .into_iter()
+        .find(|&known| known as c_int == status)
+        .map_or(c"unknown", Status::as_c_str)
+        .as_ptr()
+}
+
+/// # Safety
+/// `input` must be null or point to a NUL-terminated string, and `output`
+/// must be null or valid for writes.
+unsafe fn parse<T>(
+    input: *const c_char,
+    output: *mut T,
+    parse: impl for<'a> FnOnce(&'a str) -> Result<T, Error<'a>>,
+) -> Status {
+    if input.is_null() || output.is_null() {
+        return Status::NullPointer;
+    }
+    // SAFETY: `input` is a NUL-terminated string, as required by the caller.
+    let Ok(input) = unsafe { CStr::from_ptr(input) }.to_str() else {
+        return Status::NotAscii;
+    };
+    match parse(input) {
+        Ok(value) => {
+            // SAFETY: `output` is valid for writes, as required by the caller.
+            unsafe { output.write(value) };
+            Status::Ok
+        }
+        Err(err) => err.kind().into(),
+    }
+}
+
+/// # Safety
+/// `buffer` must be null or valid for `capacity` bytes of writes, and `len`
+/// must be null or valid for writes.
+unsafe fn write(formatted: &str, buffer: *mut c_char, capacity: usize, len: *mut usize) -> Status {
+    if !len.is_null() {
+        // SAFETY: `len` is valid for writes, as required by the caller.
+        unsafe { len.write(formatted.len()) };
+    }
+    if formatted.len() >= capacity {
+        return Status::BufferTooSmall;
+    }
+    if buffer.is_null() {
+        return Status::NullPointer;
+    }
+    // SAFETY: `buffer` is valid for `capacity` bytes, which is more than the
+    // formatted string and its NUL terminator.
+    unsafe {
+        ptr::copy_nonoverlapping(formatted.as_ptr(), buffer.cast(), formatted.len());
+        buffer.add(formatted.len()).write(0);
+    }
+    Status::Ok
+}
+
+macro_rules! bindings {
+    ($(
+        $(#[cfg($cfg:meta)])?
+        $module:ident: $parse:ident, $format:ident => $value:ty,
+    )*) => {
+        $(
+            #[doc = concat!("Parse a NUL-terminated string using [`", stringify!($module), "::parse`](crate::", stringify!($module), "::parse), writing the value to `output`.")]
+            ///
+            /// # Safety
+            /// `input` must be null or point to a NUL-terminated string, and
+            /// `output` must be null or valid for writes.
+            $(#[cfg($cfg)])?
+            #[no_mangle]
+            pub unsafe extern "C" fn $parse(input: *const c_char, output: *mut $value) -> Status {
+                // SAFETY: forwarded to the caller.
+                unsafe { parse(input, output, crate::$module::parse) }
+            }
+
+            #[doc = concat!("Format a value using [`", stringify!($module), "::format`](crate::", stringify!($module), "::format), writing it to `buffer` followed by a NUL terminator.")]
+            ///
+            /// The length of the formatted string (without the NUL
+            /// terminator) is written to `len` if it isn't null, even if the
+            /// buffer is too small.
+            ///
+            /// # Safety
+            /// `buffer` must be null or valid for `capacity` bytes of writes,
+            /// and `len` must be null or valid for writes.
+            $(#[cfg($cfg)])?
+            #[no_mangle]
+            pub unsafe extern "C" fn $format(
+                input: $value,
+                buffer: *mut c_char,
+                capacity: usize,
+                len: *mut usize,
+            ) -> Status {
+                // SAFETY: forwarded to the caller.
+                unsafe { write(&crate::$module::format(input), buffer, capacity, len) }
+            }
+        )*
+    };
+}
+
+bindings! {
+    si: bity_si_parse, bity_si_format => u64,
+    #[cfg(feature = "bit")]
+    bit: bity_bit_parse, bity_bit_format => u64,
+    #[cfg(feature = "byte")]
+    byte: bity_byte_parse, bity_byte_format => u64,
+    #[cfg(feature = "packet")]
+    packet: bity_packet_parse, bity_packet_format => u64,
+    #[cfg(feature = "bps")]
+    bps: bity_bps_parse, bity_bps_format => u64,
+    #[cfg(feature = "byteps")]
+    byteps: bity_byteps_parse, bity_byteps_format => u64,
+    #[cfg(feature = "pps")]
+    pps: bity_pps_parse, bity_pps_format => u64,
+    #[cfg(feature = "bpp")]
+    bpp: bity_bpp_parse, bity_bpp_format => u64,
+    #[cfg(feature = "hz")]
+    hz: bity_hz_parse, bity_hz_format => u64,
+    #[cfg(feature = "watt")]
+    watt: bity_watt_parse, bity_watt_format => u64,
+    #[cfg(feature = "ops")]
+    ops: bity_ops_parse, bity_ops_format => u64,
+    #[cfg(feature = "percent")]
+    percent: bity_percent_parse, bity_percent_format => u64,
+    #[cfg(feature = "compression")]
+    compression: bity_compression_parse, bity_compression_format => u64,
+    #[cfg(feature = "db")]
+    db: bity_db_parse, bity_db_format => i64,
+}
+
+/// Parse a NUL-terminated string using
+/// [`db::parse_dbm`](crate::db::parse_dbm), writing the value to `output`.
+///
+/// # Safety
+/// `input` must be null or point to a NUL-terminated string, and `output`
+/// must be null or valid for writes.
+#[cfg(feature = "db")]
+#[no_mangle]
+pub unsafe extern "C" fn bity_dbm_parse(input: *const c_char, output: *mut i64) -> Status {
+    // SAFETY: forwarded to the caller.
+    unsafe { parse(input, output, crate::db::parse_dbm) }
+}
+
+/// Format a value using [`db::format_dbm`](crate::db::format_dbm), like
+/// `bity_db_format`.
+///
+/// # Safety
+/// `buffer` must be null or valid for `capacity` bytes of writes, and `len`
+/// must be null or valid for writes.
+#[cfg(feature = "db")]
+#[no_mangle]
+pub unsafe extern "C" fn bity_dbm_format(
+    input: i64,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> Status {
+    // SAFETY: forwarded to the caller.
+    unsafe { write(&crate::db::format_dbm(input), buffer, capacity, len) }
+}
+
+/// Parse a NUL-terminated string using
+/// [`duration::parse`](crate::duration::parse), writing the number of
+/// nanoseconds to `output`.
+///
+/// Durations above `u64::MAX` nanoseconds (~584 years) are saturated.
+///
+/// # Safety
+/// `input` must be null or point to a NUL-terminated string, and `output`
+/// must be null or valid for writes.
+#[cfg(feature = "duration")]
+#[no_mangle]
+pub unsafe extern "C" fn bity_duration_parse(input: *const c_char, output: *mut u64) -> Status {
+    // SAFETY: forwarded to the caller.
+    unsafe {
+        parse(input, output, |input| {
+            let duration = crate::duration::parse(input)?;
+            Ok(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX))
+        })
+    }
+}
+
+/// Format a number of nanoseconds using
+/// [`duration::format`](crate::duration::format), like the other formatting
+/// functions.
+///
+/// # Safety
+/// `buffer` must be null or valid for `capacity` bytes of writes, and `len`
+/// must be null or valid for writes.
+#[cfg(feature = "duration")]
+#[no_mangle]
+pub unsafe extern "C" fn bity_duration_format(
+    input: u64,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> Status {
+    let formatted = crate::duration::format(std::time::Duration::from_nanos(input));
+    // SAFETY: forwarded to the caller.
+    unsafe { write(&formatted, buffer, capacity, len) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::{c_int, CStr},
+        ptr,
+    };
+
+    use super::Status;
+
+    #[test]
+    fn parse() {
+        let mut value = 0;
+        assert_eq!(unsafe { super::bity_byteps_parse(c"5MB/s".as_ptr(), &mut value) }, Status::Ok);
+        assert_eq!(value, 5_000_000);
+        assert_eq!(
+            unsafe { super::bity_byteps_parse(c"5XB/s".as_ptr(), &mut value) },
+            Status::InvalidUnit
+        );
+        assert_eq!(unsafe { super::bity_bps_parse(c"".as_ptr(), &mut value) }, Status::EmptyInput);
+        assert_eq!(
+            unsafe { super::bity_byte_parse(c"\xff".as_ptr(), &mut value) },
+            Status::NotAscii
+        );
+        assert_eq!(unsafe { super::bity_byte_parse(ptr::null(), &mut value) }, Status::NullPointer);
+        assert_eq!(
+            unsafe { super::bity_byte_parse(c"1kB".as_ptr(), ptr::null_mut()) },
+            Status::NullPointer
+        );
+
+        let mut power = 0;
+        assert_eq!(unsafe { super::bity_dbm_parse(c"-20dBm".as_ptr(), &mut power) }, Status::Ok);
+        assert_eq!(power, -20_000);
+        assert_eq!(
+            unsafe { super::bity_duration_parse(c"1m30s".as_ptr(), &mut value) },
+            Status::Ok
+        );
+        assert_eq!(value, 90_000_000_000);
+    }
+
+    #[test]
+    fn format() {
+        let mut buffer = [0x7f; crate::byte::MAX_FORMATTED_LEN + 1];
+        let mut len = 0;
+        let status =
+            unsafe { super::bity_byte_format(1_500, buffer.as_mut_ptr(), buffer.len(), &mut len) };
+        assert_eq!(status, Status::Ok);
+        assert_eq!(len, 5);
+        let formatted = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        assert_eq!(formatted, c"1.5kB");
+
+        let status = unsafe {
+            super::bity_byte_format(u64::MAX, buffer.as_mut_ptr(), buffer.len(), ptr::null_mut())
+        };
+        assert_eq!(status, Status::Ok);
+
+        let status = unsafe { super::bity_db_format(-1_500, buffer.as_mut_ptr(), 6, &mut len) };
+        assert_eq!((status, len), (Status::BufferTooSmall, 6));
+        let status = unsafe { super::bity_db_format(-1_500, ptr::null_mut(), 0, &mut len) };
+        assert_eq!((status, len), (Status::BufferTooSmall, 6));
+        let status = unsafe { super::bity_db_format(-1_500, ptr::null_mut(), 7, &mut len) };
+        assert_eq!(status, Status::NullPointer);
+    }
+
+    #[test]
+    fn status() {
+        let name = |status| unsafe { CStr::from_ptr(super::bity_status_str(status)) };
+        assert_eq!(
+            name(Status::InvalidUnit as c_int).to_str(),
+            Ok(crate::ErrorKind::InvalidUnit.as_str())
+        );
+        assert_eq!(name(Status::BufferTooSmall as c_int), c"buffer_too_small");
+        assert_eq!(name(9), c"unknown");
+        assert_eq!(name(-1), c"unknown");
+        assert_eq!(Status::InvalidNumber as i32, 8);
+        assert_eq!(Status::BufferTooSmall as i32, 101);
+    }
+}
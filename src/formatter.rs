@@ -0,0 +1,265 @@
+//! Formatting with custom options, resolved once into a reusable
+//! [`Formatter`].
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{
+//!     formatter::{FormatOptions, Formatter},
+//!     si::Prefix,
+//! };
+//!
+//! let formatter = Formatter::new(&FormatOptions::new().with_precision(3).with_separator(" ").with_suffix("B"));
+//! assert_eq!(formatter.format(1_234_567), "1.234 MB");
+//! assert_eq!(formatter.format(12), "12 B");
+//!
+//! let megabits = Formatter::new(&FormatOptions::new().with_prefix(Prefix::Mega).with_suffix("b/s"));
+//! assert_eq!(megabits.format(1_234_567_890), "1234.56Mb/s");
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::si::{self, Digits, Prefix};
+
+/// Options of a [`Formatter`].
+///
+/// The default options format like [`si::format`]: automatic SI prefix, at
+/// most two fraction digits, no separator and no suffix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    prefix: Option<Prefix>,
+    precision: u32,
+    separator: String,
+    suffix: String,
+}
+
+impl FormatOptions {
+    /// Default options, formatting like [`si::format`].
+    pub fn new() -> Self {
+        Self {
+            prefix: None,
+            precision: 2,
+            separator: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// Use a given SI prefix, whatever the magnitude of the values (like
+    /// [`si::format_with_prefix`]).
+    pub fn with_prefix(mut self, prefix: Prefix) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// Display at most `precision` fraction digits (truncated, trailing
+    /// zeroes dropped). Precisions above `18` are treated as `18`.
+    pub fn with_precision(mut self, precision: u32) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Insert a separator (like a space) between the number and its prefix
+    /// or suffix.
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Append a unit suffix (like `B`) after the prefix.
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A formatter built once from [`FormatOptions`], to format many values
+/// without resolving the options on each call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formatter {
+    prefix: Option<Prefix>,
+    precision: u32,
+    separator: String,
+    suffix: String,
+}
+
+impl Formatter {
+    /// Resolve the options into a formatter.
+    pub fn new(options: &FormatOptions) -> Self {
+        Self {
+            prefix: options.prefix,
+            // `10^18` is the highest factor, there is nothing to display after
+            // its 18th fraction digit.
+            precision: options.precision.min(18),
+            separator: options.separator.clone(),
+            suffix: options.suffix.clone(),
+        }
+    }
+
+    /// Format an integer into a string.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::formatter::{FormatOptions, Formatter};
+    ///
+    /// let formatter = Formatter::new(&FormatOptions::new().with_precision(0));
+    /// assert_eq!(formatter.format(1_999), "1k");
+    /// ```
+    pub fn format(&self, input: u64) -> String {
+        let mut output = String::with_capacity(8 + self.separator.len() + self.suffix.len());
+        // Writing into a `String` can't fail.
+        let _ = self.format_into(&mut output, input);
+        output
+    }
+
+    /// Like [`Formatter::format`], but into any string type implementing
+    /// [`fmt::Write`] and [`Default`], like a small-string type.
+    ///
+    /// Refer to [`si::format_to`] for details.
+    pub fn format_to<S: fmt::Write + Default>(&self, input: u64) -> S {
+        let mut output = S::default();
+        let _ = self.format_into(&mut output, input);
+        output
+    }
+
+    /// Like [`Formatter::format`], but returning a
+    /// [`CompactString`](compact_str::CompactString) (`compact_str` feature),
+    /// stored inline if short enough.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::formatter::{FormatOptions, Formatter};
+    ///
+    /// let formatter = Formatter::new(&FormatOptions::new().with_separator(" ").with_suffix("B/s"));
+    /// let output = formatter.format_compact_str(1_234_567);
+    /// assert_eq!(output, "1.23 MB/s");
+    /// assert!(!output.is_heap_allocated());
+    /// ```
+    #[cfg(feature = "compact_str")]
+    pub fn format_compact_str(&self, input: u64) -> compact_str::CompactString {
+        self.format_to(input)
+    }
+
+    /// Like [`Formatter::format`], but writing into an existing writer (like
+    /// a reused `String` or a [`Formatter`](fmt::Formatter)) instead of
+    /// allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::formatter::{FormatOptions, Formatter};
+    ///
+    /// let formatter = Formatter::new(&FormatOptions::new().with_suffix("req"));
+    /// let mut output = String::new();
+    /// for input in [12, 1_500] {
+    ///     output.clear();
+    ///     formatter.format_into(&mut output, input).unwrap();
+    /// }
+    /// assert_eq!(output, "1.5kreq");
+    /// ```
+    pub fn format_into(&self, output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+        let prefix = self.prefix.unwrap_or_else(|| si::prefix_of(input));
+        let factor = prefix.factor();
+        output.write_str(Digits::integer(input / factor).as_str())?;
+
+        let precision = self.precision.min(factor.ilog10());
+        let fraction = input % factor / 10u64.pow(factor.ilog10() - precision);
+        if fraction != 0 {
+            // Pad the fraction with leading zeroes and drop the trailing ones.
+            let mut digits = [b'0'; 18];
+            let digits = &mut digits[..precision as usize];
+            let mut rest = fraction;
+            for digit in digits.iter_mut().rev() {
+                *digit = b'0' + (rest % 10) as u8;
+                rest /= 10;
+            }
+            let end = digits
+                .iter()
+                .rposition(|&digit| digit != b'0')
+                .map_or(0, |end| end + 1);
+            output.write_char('.')?;
+            output.write_str(core::str::from_utf8(&digits[..end]).unwrap_or_default())?;
+        }
+
+        if !prefix.symbol().is_empty() || !self.suffix.is_empty() {
+            output.write_str(&self.separator)?;
+        }
+        output.write_str(prefix.symbol())?;
+        output.write_str(&self.suffix)
+    }
+}
+
+impl From<&FormatOptions> for Formatter {
+    fn from(options: &FormatOptions) -> Self {
+        Self::new(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatOptions, Formatter};
+    use crate::si::Prefix;
+
+    #[test]
+    fn format() {
+        let formatter = Formatter::new(&FormatOptions::new());
+        for input in [0, 1, 1_050, 1_005, 12_345_678, 1_200_000_000, u64::MAX] {
+            assert_eq!(formatter.format(input), crate::si::format(input));
+        }
+        for prefix in [Prefix::None, Prefix::Kilo, Prefix::Exa] {
+            let formatter = Formatter::new(&FormatOptions::new().with_prefix(prefix));
+            for input in [0, 1_050, 12_345_678, u64::MAX] {
+                assert_eq!(formatter.format(input), crate::si::format_with_prefix(input, prefix));
+            }
+        }
+
+        let formatter = Formatter::new(&FormatOptions::new().with_precision(4));
+        assert_eq!(formatter.format(1_000_500), "1.0005M");
+        assert_eq!(formatter.format(1_234_567), "1.2345M");
+        assert_eq!(formatter.format(999), "999");
+        let formatter = Formatter::new(&FormatOptions::new().with_precision(u32::MAX));
+        assert_eq!(formatter.format(u64::MAX), "18.446744073709551615E");
+        assert_eq!(formatter.format(1_000_000_000_000_000_001), "1.000000000000000001E");
+        let formatter = Formatter::new(&FormatOptions::new().with_precision(0));
+        assert_eq!(formatter.format(1_999_999), "1M");
+
+        let formatter = Formatter::new(&FormatOptions::new().with_separator(" ").with_suffix("B"));
+        assert_eq!(formatter.format(1_500), "1.5 kB");
+        assert_eq!(formatter.format(12), "12 B");
+        let formatter = Formatter::new(&FormatOptions::new().with_separator(" "));
+        assert_eq!(formatter.format(12), "12");
+        assert_eq!(formatter.format(12_000), "12 k");
+    }
+
+    #[test]
+    fn format_into() {
+        struct Size(u64);
+
+        impl std::fmt::Display for Size {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                Formatter::new(&FormatOptions::new().with_suffix("B")).format_into(f, self.0)
+            }
+        }
+
+        assert_eq!(format!("[{}]", Size(1_234)), "[1.23kB]");
+    }
+
+    #[cfg(feature = "compact_str")]
+    #[test]
+    fn format_compact_str() {
+        let formatter = Formatter::new(&FormatOptions::new().with_suffix("B"));
+        for input in [0, 1_234, u64::MAX] {
+            let output = formatter.format_compact_str(input);
+            assert_eq!(output, formatter.format(input));
+            assert!(!output.is_heap_allocated());
+        }
+        // Longer than 24 bytes.
+        let formatter = Formatter::new(&FormatOptions::new().with_precision(18).with_suffix("B/s"));
+        assert!(formatter.format_compact_str(u64::MAX).is_heap_allocated());
+    }
+}
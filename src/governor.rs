@@ -0,0 +1,141 @@
+//! [`governor`](https://docs.rs/governor) interoperability.
+//!
+//! Build [`Quota`]s from parsed rates, where each cell of the quota is a unit of
+//! the rate (a bit for [`bps`](crate::bps), a byte for
+//! [`byteps`](crate::byteps), a packet for [`pps`](crate::pps), ...).
+//!
+//! As `governor` replenishes cells one at a time, every whole number of
+//! nanoseconds, rates are only converted if that interval matches them within
+//! 0.1%. Rates up to 1M cells per second always are, higher ones only if they
+//! (almost) divide 1G, like `2M` or `1G`.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::num::NonZeroU32;
+//!
+//! use bity::{byte, byteps, pps};
+//!
+//! let quota = bity::governor::quota(pps::parse("10kpps").unwrap()).unwrap();
+//! assert_eq!(quota.burst_size(), NonZeroU32::new(10_000).unwrap());
+//!
+//! let quota = bity::governor::quota_with_burst(
+//!     byteps::parse("5MB/s").unwrap(),
+//!     byte::parse("50MB").unwrap(),
+//! )
+//! .unwrap();
+//! assert_eq!(quota.burst_size(), NonZeroU32::new(50_000_000).unwrap());
+//! ```
+
+use std::num::NonZeroU32;
+
+use governor::Quota;
+
+fn cells(value: u64) -> Option<NonZeroU32> {
+    NonZeroU32::new(u32::try_from(value).ok()?)
+}
+
+/// Nanoseconds in a second.
+const NANOS: u64 = 1_000_000_000;
+
+/// Build a [`Quota`] replenishing `rate` cells per second, with a burst size of
+/// `rate` cells.
+///
+/// Returns `None` if the rate is zero, or if its replenish interval (one
+/// second divided by `rate`, truncated to the nanosecond) is off by more than
+/// 0.1%, which is always the case above 1G cells per second.
+///
+/// # Examples
+/// ```
+/// use std::{num::NonZeroU32, time::Duration};
+///
+/// use bity::{bps, governor::quota};
+///
+/// let quota = quota(bps::parse("1Mb/s").unwrap()).unwrap();
+/// assert_eq!(quota.replenish_interval(), Duration::from_micros(1));
+/// assert_eq!(quota.burst_size(), NonZeroU32::new(1_000_000).unwrap());
+///
+/// assert!(bity::governor::quota(0).is_none());
+/// // 66ns intervals would be 1% fast.
+/// assert!(bity::governor::quota(bps::parse("15Mb/s").unwrap()).is_none());
+/// assert!(bity::governor::quota(bps::parse("10Gb/s").unwrap()).is_none());
+/// ```
+pub fn quota(rate: u64) -> Option<Quota> {
+    let cells = cells(rate)?;
+    // `Quota::per_second` truncates the interval to the nanosecond.
+    let interval = NANOS / rate;
+    if NANOS % rate * 1_000 > rate * interval {
+        return None;
+    }
+    Some(Quota::per_second(cells))
+}
+
+/// Build a [`Quota`] replenishing `rate` cells per second, with a burst size of
+/// `burst` cells.
+///
+/// Returns `None` if the rate can't be converted (refer to [`quota`]), or if
+/// the burst size is zero or above `u32::MAX`.
+///
+/// # Examples
+/// ```
+/// use std::{num::NonZeroU32, time::Duration};
+///
+/// use bity::{byte, byteps, governor::quota_with_burst};
+///
+/// let quota = quota_with_burst(byteps::parse("1MB/s").unwrap(), byte::parse("5MB").unwrap());
+/// let quota = quota.unwrap();
+/// assert_eq!(quota.replenish_interval(), Duration::from_micros(1));
+/// assert_eq!(quota.burst_size(), NonZeroU32::new(5_000_000).unwrap());
+///
+/// assert!(quota_with_burst(1_000_000, 0).is_none());
+/// ```
+pub fn quota_with_burst(rate: u64, burst: u64) -> Option<Quota> {
+    Some(quota(rate)?.allow_burst(cells(burst)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    #[test]
+    fn quota() {
+        let quota = super::quota(1_000).unwrap();
+        assert_eq!(quota.replenish_interval(), Duration::from_millis(1));
+        assert_eq!(quota.burst_size(), NonZeroU32::new(1_000).unwrap());
+
+        assert_eq!(super::quota(3).unwrap().replenish_interval().as_nanos(), 333_333_333);
+        assert_eq!(
+            super::quota(1_000_000_000)
+                .unwrap()
+                .replenish_interval()
+                .as_nanos(),
+            1
+        );
+        assert_eq!(
+            super::quota(2_000_000)
+                .unwrap()
+                .replenish_interval()
+                .as_nanos(),
+            500
+        );
+        assert!(super::quota(999_999).is_some());
+        assert!(super::quota(15_000_000).is_none());
+        assert!(super::quota(300_000_000).is_none());
+        assert!(super::quota(700_000_000).is_none());
+        assert!(super::quota(1_000_000_001).is_none());
+        assert!(super::quota(u64::from(u32::MAX)).is_none());
+        assert!(super::quota(u64::from(u32::MAX) + 1).is_none());
+        assert!(super::quota(0).is_none());
+    }
+
+    #[test]
+    fn quota_with_burst() {
+        let quota = super::quota_with_burst(1_000, 5).unwrap();
+        assert_eq!(quota.replenish_interval(), Duration::from_millis(1));
+        assert_eq!(quota.burst_size(), NonZeroU32::new(5).unwrap());
+
+        assert!(super::quota_with_burst(0, 5).is_none());
+        assert!(super::quota_with_burst(1_000, 0).is_none());
+        assert!(super::quota_with_burst(1_000, u64::from(u32::MAX) + 1).is_none());
+    }
+}
@@ -0,0 +1,258 @@
+//! Size distribution analytics.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::histogram::SizeHistogram;
+//!
+//! let mut histogram = SizeHistogram::default();
+//! for size in [64, 512, 1_500, 1_500, 9_000] {
+//!     histogram.record(size);
+//! }
+//!
+//! assert_eq!(
+//!     histogram.summary().unwrap().to_string(),
+//!     "min 64B, p50 1.5kB, p95 9kB, p99 9kB, max 9kB"
+//! );
+//! ```
+
+use alloc::{collections::BTreeMap, format, string::String};
+use core::fmt::{self, Display, Formatter};
+
+use crate::byte;
+
+/// How recorded values are grouped into buckets.
+///
+/// Percentiles are reported as the upper bound of their bucket (clamped to the
+/// recorded minimum and maximum), so the scheme sets the precision of the
+/// reported values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketScheme {
+    /// Buckets of a fixed width: values are rounded up to the next multiple of
+    /// the width (at least `1`).
+    Linear(u64),
+    /// Buckets of a fixed relative precision: values are rounded up to the
+    /// given number of significant digits (at least `1`).
+    Significant(u32),
+}
+
+impl BucketScheme {
+    /// Upper bound of the bucket containing `value`.
+    fn bucket(self, value: u64) -> u64 {
+        let width = match self {
+            BucketScheme::Linear(width) => width.max(1),
+            BucketScheme::Significant(significant) => {
+                let digits = value.checked_ilog10().map_or(1, |log| log + 1);
+                10u64.pow(digits.saturating_sub(significant.max(1)))
+            }
+        };
+        value.div_ceil(width).saturating_mul(width)
+    }
+}
+
+impl Default for BucketScheme {
+    /// Three significant digits.
+    fn default() -> Self {
+        BucketScheme::Significant(3)
+    }
+}
+
+/// Histogram of sizes (or any other quantity), reporting percentiles.
+#[derive(Debug, Clone, Default)]
+pub struct SizeHistogram {
+    scheme: BucketScheme,
+    buckets: BTreeMap<u64, u64>,
+    count: u64,
+    min: u64,
+    max: u64,
+}
+
+impl SizeHistogram {
+    /// Create an empty histogram using the given bucket scheme.
+    pub fn new(scheme: BucketScheme) -> Self {
+        Self {
+            scheme,
+            ..Self::default()
+        }
+    }
+
+    /// Record a value.
+    pub fn record(&mut self, value: u64) {
+        *self.buckets.entry(self.scheme.bucket(value)).or_default() += 1;
+        if self.count == 0 {
+            (self.min, self.max) = (value, value);
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+    }
+
+    /// Number of recorded values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Lowest recorded value, if any.
+    pub fn min(&self) -> Option<u64> {
+        (self.count != 0).then_some(self.min)
+    }
+
+    /// Highest recorded value, if any.
+    pub fn max(&self) -> Option<u64> {
+        (self.count != 0).then_some(self.max)
+    }
+
+    /// Value below which `percentile` percents of the recorded values fall, if
+    /// any value was recorded.
+    ///
+    /// `percentile` is clamped between `0` and `100`.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::histogram::{BucketScheme, SizeHistogram};
+    ///
+    /// let mut histogram = SizeHistogram::new(BucketScheme::Linear(100));
+    /// (1..=1_000).for_each(|size| histogram.record(size));
+    ///
+    /// assert_eq!(histogram.percentile(50.0), Some(500));
+    /// assert_eq!(histogram.percentile(95.5), Some(1_000));
+    /// assert_eq!(histogram.percentile(0.0), Some(100));
+    /// ```
+    pub fn percentile(&self, percentile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        // Rounded up without `f64::ceil`, which isn't available without std.
+        let rank = percentile.clamp(0.0, 100.0) / 100.0 * self.count as f64;
+        let rank = (rank as u64 + u64::from((rank as u64 as f64) < rank)).clamp(1, self.count);
+        let mut seen = 0;
+        for (&bucket, &count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return Some(bucket.clamp(self.min, self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Summary of the distribution, if any value was recorded.
+    pub fn summary(&self) -> Option<Summary> {
+        Some(Summary {
+            min: self.min()?,
+            p50: self.percentile(50.0)?,
+            p95: self.percentile(95.0)?,
+            p99: self.percentile(99.0)?,
+            max: self.max()?,
+        })
+    }
+}
+
+/// Summary of a [`SizeHistogram`].
+///
+/// The [`Display`] implementation formats the values using [`byte::format`],
+/// use [`Summary::format_with`] for other units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// Lowest recorded value.
+    pub min: u64,
+    /// 50th percentile (median).
+    pub p50: u64,
+    /// 95th percentile.
+    pub p95: u64,
+    /// 99th percentile.
+    pub p99: u64,
+    /// Highest recorded value.
+    pub max: u64,
+}
+
+impl Summary {
+    /// Format the summary using a custom format function.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{histogram::SizeHistogram, packet};
+    ///
+    /// let mut histogram = SizeHistogram::default();
+    /// histogram.record(1_200);
+    ///
+    /// assert_eq!(
+    ///     histogram.summary().unwrap().format_with(packet::format),
+    ///     "min 1.2kp, p50 1.2kp, p95 1.2kp, p99 1.2kp, max 1.2kp"
+    /// );
+    /// ```
+    pub fn format_with(&self, format: impl Fn(u64) -> String) -> String {
+        format!(
+            "min {}, p50 {}, p95 {}, p99 {}, max {}",
+            format(self.min),
+            format(self.p50),
+            format(self.p95),
+            format(self.p99),
+            format(self.max)
+        )
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_with(byte::format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BucketScheme, SizeHistogram, Summary};
+
+    #[test]
+    fn bucket() {
+        assert_eq!(BucketScheme::Linear(100).bucket(0), 0);
+        assert_eq!(BucketScheme::Linear(100).bucket(1), 100);
+        assert_eq!(BucketScheme::Linear(100).bucket(100), 100);
+        assert_eq!(BucketScheme::Linear(100).bucket(101), 200);
+        assert_eq!(BucketScheme::Linear(0).bucket(12), 12);
+        assert_eq!(BucketScheme::Linear(100).bucket(u64::MAX), u64::MAX);
+
+        assert_eq!(BucketScheme::Significant(2).bucket(0), 0);
+        assert_eq!(BucketScheme::Significant(2).bucket(7), 7);
+        assert_eq!(BucketScheme::Significant(2).bucket(99), 99);
+        assert_eq!(BucketScheme::Significant(2).bucket(1_234), 1_300);
+        assert_eq!(BucketScheme::Significant(2).bucket(1_200), 1_200);
+        assert_eq!(BucketScheme::Significant(1).bucket(1_234), 2_000);
+        assert_eq!(BucketScheme::Significant(0).bucket(1_234), 2_000);
+        assert_eq!(BucketScheme::Significant(30).bucket(1_234), 1_234);
+        assert_eq!(BucketScheme::Significant(2).bucket(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn histogram() {
+        let mut histogram = SizeHistogram::new(BucketScheme::Significant(2));
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.min(), None);
+        assert_eq!(histogram.max(), None);
+        assert_eq!(histogram.percentile(50.0), None);
+        assert_eq!(histogram.summary(), None);
+
+        (1..=100).for_each(|size| histogram.record(size * 1_000));
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(
+            histogram.summary(),
+            Some(Summary {
+                min: 1_000,
+                p50: 50_000,
+                p95: 95_000,
+                p99: 99_000,
+                max: 100_000,
+            })
+        );
+        assert_eq!(histogram.percentile(-1.0), Some(1_000));
+        assert_eq!(histogram.percentile(200.0), Some(100_000));
+
+        // Bucket upper bound clamped to the maximum.
+        histogram.record(100_001);
+        assert_eq!(histogram.percentile(100.0), Some(100_001));
+        assert_eq!(
+            histogram.summary().unwrap().to_string(),
+            "min 1kB, p50 51kB, p95 96kB, p99 100kB, max 100kB"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! A compatibility layer mirroring the API of
+//! [`humansize`](https://docs.rs/humansize), to migrate with minimal call site
+//! changes.
+//!
+//! Sizes are truncated instead of rounded. [`DECIMAL`] sizes are formatted by
+//! a [`Formatter`], using SI prefixes, [`BINARY`] and [`WINDOWS`] ones use
+//! powers of 1024, with IEC (`KiB`) and SI (`kB`) symbols respectively.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::humansize::{format_size, make_format, SizeFormatter, BINARY, DECIMAL, WINDOWS};
+//!
+//! assert_eq!(format_size(1_500u32, DECIMAL), "1.5 kB");
+//! assert_eq!(format_size(1_500u32, BINARY), "1.46 KiB");
+//! assert_eq!(format_size(1_500u32, WINDOWS), "1.46 kB");
+//! assert_eq!(format_size(12u64, DECIMAL.space_after_value(false)), "12B");
+//!
+//! let format = make_format(DECIMAL.suffix("/s"));
+//! assert_eq!(format(42_900_000u64), "42.9 MB/s");
+//!
+//! assert_eq!(format!("{}", SizeFormatter::new(1_234_567usize, DECIMAL)), "1.23 MB");
+//! ```
+
+use alloc::{format, string::String};
+use core::fmt::{self, Display, Write};
+
+use crate::formatter::{FormatOptions, Formatter};
+
+/// Prefixes of the formatted sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefixes {
+    /// SI prefixes, powers of 1000 (`kB`).
+    Decimal,
+    /// IEC prefixes, powers of 1024 (`KiB`).
+    Binary,
+    /// SI symbols for powers of 1024 (`kB`).
+    Windows,
+}
+
+/// Options of the formatting functions, created from [`DECIMAL`], [`BINARY`]
+/// or [`WINDOWS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSizeOptions {
+    prefixes: Prefixes,
+    decimal_places: usize,
+    space_after_value: bool,
+    suffix: &'static str,
+}
+
+impl FormatSizeOptions {
+    /// Display at most `decimal_places` fraction digits.
+    pub const fn decimal_places(mut self, decimal_places: usize) -> Self {
+        self.decimal_places = decimal_places;
+        self
+    }
+
+    /// Insert a space between the number and the unit.
+    pub const fn space_after_value(mut self, space_after_value: bool) -> Self {
+        self.space_after_value = space_after_value;
+        self
+    }
+
+    /// Append a suffix (like `/s`) after the unit.
+    pub const fn suffix(mut self, suffix: &'static str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    fn formatter(&self) -> Formatter {
+        Formatter::new(
+            &FormatOptions::new()
+                .with_precision(u32::try_from(self.decimal_places).unwrap_or(u32::MAX))
+                .with_separator(if self.space_after_value { " " } else { "" })
+                .with_suffix(format!("B{}", self.suffix)),
+        )
+    }
+
+    /// Format using powers of 1024, for [`BINARY`] and [`WINDOWS`].
+    fn format_binary_into(&self, output: &mut impl Write, input: u64) -> fmt::Result {
+        const BINARY_SYMBOLS: [&str; 7] = ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei"];
+        const WINDOWS_SYMBOLS: [&str; 7] = ["", "k", "M", "G", "T", "P", "E"];
+
+        let exponent = input.checked_ilog2().unwrap_or(0) / 10;
+        let shift = exponent * 10;
+        write!(output, "{}", input >> shift)?;
+
+        // `10^18` times the remainder (below `2^60`) fits in a `u128`.
+        let precision = self.decimal_places.min(18);
+        let remainder = u128::from(input) & ((1 << shift) - 1);
+        let fraction = (remainder * 10u128.pow(precision as u32)) >> shift;
+        if fraction != 0 {
+            let fraction = format!("{fraction:0precision$}");
+            write!(output, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        let symbols = match self.prefixes {
+            Prefixes::Binary => BINARY_SYMBOLS,
+            _ => WINDOWS_SYMBOLS,
+        };
+        if self.space_after_value {
+            output.write_char(' ')?;
+        }
+        write!(output, "{}B{}", symbols[exponent as usize], self.suffix)
+    }
+}
+
+/// Options resolved once, to format many sizes.
+enum Resolved {
+    Decimal(Formatter),
+    Binary(FormatSizeOptions),
+}
+
+impl Resolved {
+    fn new(options: &FormatSizeOptions) -> Self {
+        match options.prefixes {
+            Prefixes::Decimal => Self::Decimal(options.formatter()),
+            Prefixes::Binary | Prefixes::Windows => Self::Binary(*options),
+        }
+    }
+
+    fn format(&self, input: u64) -> String {
+        let mut output = String::new();
+        // Writing into a `String` can't fail.
+        let _ = self.format_into(&mut output, input);
+        output
+    }
+
+    fn format_into(&self, output: &mut impl Write, input: u64) -> fmt::Result {
+        match self {
+            Self::Decimal(formatter) => formatter.format_into(output, input),
+            Self::Binary(options) => options.format_binary_into(output, input),
+        }
+    }
+}
+
+impl AsRef<FormatSizeOptions> for FormatSizeOptions {
+    fn as_ref(&self) -> &FormatSizeOptions {
+        self
+    }
+}
+
+/// SI prefixes, two decimal places and a space before the unit (like
+/// `1.5 kB`).
+pub const DECIMAL: FormatSizeOptions = FormatSizeOptions {
+    prefixes: Prefixes::Decimal,
+    decimal_places: 2,
+    space_after_value: true,
+    suffix: "",
+};
+
+/// IEC prefixes (powers of 1024), two decimal places and a space before the
+/// unit (like `1.46 KiB`).
+pub const BINARY: FormatSizeOptions = FormatSizeOptions {
+    prefixes: Prefixes::Binary,
+    ..DECIMAL
+};
+
+/// Powers of 1024 with SI symbols, two decimal places and a space before the
+/// unit (like `1.46 kB`), as displayed by Windows.
+pub const WINDOWS: FormatSizeOptions = FormatSizeOptions {
+    prefixes: Prefixes::Windows,
+    ..DECIMAL
+};
+
+/// Unsigned integers accepted as sizes.
+pub trait Unsigned: Copy {
+    /// Convert into a `u64`, saturating.
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Unsigned for $ty {
+                fn to_u64(self) -> u64 {
+                    u64::try_from(self).unwrap_or(u64::MAX)
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Format a size into a string.
+pub fn format_size(input: impl Unsigned, options: impl AsRef<FormatSizeOptions>) -> String {
+    Resolved::new(options.as_ref()).format(input.to_u64())
+}
+
+/// Create a function formatting sizes, resolving the options once.
+pub fn make_format<T: Unsigned>(options: impl AsRef<FormatSizeOptions>) -> impl Fn(T) -> String {
+    let resolved = Resolved::new(options.as_ref());
+    move |input| resolved.format(input.to_u64())
+}
+
+/// A size displayed using the given options.
+#[derive(Debug, Clone, Copy)]
+pub struct SizeFormatter<T, O> {
+    input: T,
+    options: O,
+}
+
+impl<T: Unsigned, O: AsRef<FormatSizeOptions>> SizeFormatter<T, O> {
+    /// Wrap a size, to be displayed using `options`.
+    pub fn new(input: T, options: O) -> Self {
+        Self { input, options }
+    }
+}
+
+impl<T: Unsigned, O: AsRef<FormatSizeOptions>> Display for SizeFormatter<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Resolved::new(self.options.as_ref()).format_into(f, self.input.to_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, BINARY, DECIMAL, WINDOWS};
+
+    #[test]
+    fn format() {
+        assert_eq!(format_size(0u8, DECIMAL), "0 B");
+        assert_eq!(format_size(999u16, DECIMAL), "999 B");
+        assert_eq!(format_size(1_000u64, DECIMAL), "1 kB");
+        assert_eq!(format_size(1_234_567u64, DECIMAL.decimal_places(0)), "1 MB");
+        assert_eq!(format_size(1_234_567u64, DECIMAL.decimal_places(4)), "1.2345 MB");
+        assert_eq!(format_size(u128::MAX, DECIMAL), "18.44 EB");
+        assert_eq!(format_size(1_500u64, DECIMAL), "1.5 kB");
+    }
+
+    #[test]
+    fn format_binary() {
+        assert_eq!(format_size(0u8, BINARY), "0 B");
+        assert_eq!(format_size(1_023u16, BINARY), "1023 B");
+        assert_eq!(format_size(1_024u16, BINARY), "1 KiB");
+        assert_eq!(format_size(1_500u32, BINARY), "1.46 KiB");
+        assert_eq!(format_size(1_536u32, BINARY.decimal_places(0)), "1 KiB");
+        assert_eq!(format_size(1_048_575u32, BINARY.decimal_places(4)), "1023.999 KiB");
+        assert_eq!(format_size(5u64 << 30, BINARY.space_after_value(false)), "5GiB");
+        assert_eq!(format_size(3u64 << 40, BINARY.suffix("/s")), "3 TiB/s");
+        assert_eq!(format_size(u64::MAX, BINARY), "15.99 EiB");
+        assert_eq!(format_size(u64::MAX, BINARY.decimal_places(30)), "15.999999999999999999 EiB");
+        assert_eq!(format_size(1_500u32, WINDOWS), "1.46 kB");
+        assert_eq!(format_size(1_572_864u32, WINDOWS), "1.5 MB");
+    }
+}
@@ -0,0 +1,281 @@
+//! SI prefixed frequency parsing and formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::hz::{format, parse};
+//!
+//! assert_eq!(parse("2.4GHz").unwrap(), 2_400_000_000);
+//! assert_eq!(parse("44.1kHz").unwrap(), 44_100);
+//!
+//! assert_eq!(format(1_234), "1.23kHz");
+//! assert_eq!(format(48_000), "48kHz");
+//! assert_eq!(format(3_200_000_000), "3.2GHz");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::hz::serialize")]`, `#[serde(deserialize_with =
+//! "bity::hz::deserialize")]` and `#[serde(with = "bity::hz")]` attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::hz")]
+//!     sample_rate: u64,
+//!     #[serde(with = "bity::hz")]
+//!     polling: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         sample-rate = "44.1kHz"
+//!         polling = 10
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         sample_rate: 44_100,
+//!         polling: 10,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         sample_rate: 44_100,
+//!         polling: 10,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         sample-rate = "44.1kHz"
+//!         polling = "10Hz"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    si::{self, Prefix},
+    Error,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?(?:Hz)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["Hz"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "Hz".len();
+
+/// Parse a frequency SI prefixed string into a number.
+///
+/// This is equivalent to colling `si::parse_with_additional_units(input,
+/// &[("Hz", 1)])`.
+///
+/// Refer to [`si::parse`] and [`si::parse_with_additional_units`] to learn the
+/// rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::hz::parse;
+///
+/// assert_eq!(parse("12Hz").unwrap(), 12);
+/// assert_eq!(parse("2.4GHz").unwrap(), 2_400_000_000);
+/// assert_eq!(parse("12").unwrap(), 12);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    si::parse_with_additional_units(input, &[("Hz", 1)])
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::hz::parse_const;
+///
+/// assert_eq!(parse_const("2.4GHz"), Some(2_400_000_000));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    si::parse_const_with_additional_units(input, &[("Hz", 1)])
+}
+
+/// Format an integer into a frequency SI prefixed string.
+///
+/// This is equivalent to colling `format!("{}Hz", si::format(input))`.
+///
+/// Refer to [`si::format`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::hz::format;
+///
+/// assert_eq!(format(12), "12Hz");
+/// assert_eq!(format(1_234), "1.23kHz");
+/// assert_eq!(format(2_400_000_000), "2.4GHz");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (frequency) using a given SI
+/// prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}Hz", si::format_with_prefix(input,
+/// prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{hz::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(2_400_000_000, Prefix::Mega), "2400MHz");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("Hz");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::hz::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 2_400_000_000).unwrap();
+/// assert_eq!(output, "2.4GHz");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("Hz")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::hz::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 2_400_000_000).unwrap();
+/// assert_eq!(&buffer[..len], b"2.4GHz");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a frequency like "2.4GHz", "50Hz" or 1500"#,
+    example: 2_400_000_000,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed frequency string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::hz::serialize")]` and `#[serde(with = "bity::hz")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::hz::serialize")]
+    ///     clock: u64,
+    ///     #[serde(with = "bity::hz")]
+    ///     polling: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         clock: 2_400_000_000,
+    ///         polling: 10,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         clock = "2.4GHz"
+    ///         polling = "10Hz"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed frequency string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::hz::deserialize")]` and `#[serde(with = "bity::hz")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::hz::deserialize")]
+    ///     clock: u64,
+    ///     #[serde(with = "bity::hz")]
+    ///     polling: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             clock = "2.4GHz"
+    ///             polling = 10
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         clock: 2_400_000_000,
+    ///         polling: 10,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12Hz").unwrap(), 12);
+        assert_eq!(super::parse("44.1kHz").unwrap(), 44_100);
+        assert_eq!(super::parse("2.4GHz").unwrap(), 2_400_000_000);
+        assert_eq!(super::parse("2.4 GHz").unwrap(), 2_400_000_000);
+        assert_eq!(super::parse("2.4G").unwrap(), 2_400_000_000);
+        assert_eq!(super::parse("12").unwrap(), 12);
+
+        // The unit is case-sensitive.
+        assert!(matches!(super::parse("12hz"), Err(Error::InvalidUnit("hz"))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123Hz");
+        assert_eq!(super::format(44_100), "44.1kHz");
+        assert_eq!(super::format(2_400_000_000), "2.4GHz");
+    }
+}
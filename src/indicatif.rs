@@ -0,0 +1,199 @@
+//! [`indicatif`](https://docs.rs/indicatif) support, to display progress bars
+//! sizes and data-rates like [`byte::format`] and [`byteps::format`].
+//!
+//! [`HumanBytes`], [`BinaryBytes`] and [`HumanThroughput`] replace
+//! indicatif's formatting types, and [`with_keys`] overrides the byte related
+//! template keys of a [`ProgressStyle`].
+//!
+//! IEC sizes are formatted like [`humansize::BINARY`](crate::humansize::BINARY),
+//! without space before the unit.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::indicatif::with_keys;
+//! use indicatif::{ProgressBar, ProgressStyle};
+//!
+//! let style = ProgressStyle::with_template("{bytes}/{total_bytes} ({bytes_per_sec})").unwrap();
+//! let bar = ProgressBar::hidden().with_style(with_keys(style));
+//! bar.set_length(4_500_000_000);
+//! bar.set_position(1_200_000_000);
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use indicatif::{ProgressState, ProgressStyle};
+
+use crate::{
+    byte, byteps,
+    humansize::{SizeFormatter, BINARY},
+};
+
+/// A size displayed using [`byte::format`], replacing indicatif's
+/// `HumanBytes` and `DecimalBytes`.
+///
+/// # Examples
+/// ```
+/// use bity::indicatif::HumanBytes;
+///
+/// assert_eq!(HumanBytes(1_234_567).to_string(), "1.23MB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HumanBytes(pub u64);
+
+impl Display for HumanBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        byte::format_into(f, self.0)
+    }
+}
+
+/// A size displayed using IEC prefixes (powers of 1024, truncated), replacing
+/// indicatif's `BinaryBytes`.
+///
+/// # Examples
+/// ```
+/// use bity::indicatif::BinaryBytes;
+///
+/// assert_eq!(BinaryBytes(1_234_567).to_string(), "1.17MiB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BinaryBytes(pub u64);
+
+impl Display for BinaryBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        SizeFormatter::new(self.0, BINARY.space_after_value(false)).fmt(f)
+    }
+}
+
+/// A data-rate (in bytes per second) displayed using [`byteps::format`].
+///
+/// # Examples
+/// ```
+/// use bity::indicatif::HumanThroughput;
+///
+/// assert_eq!(HumanThroughput(42_900_000).to_string(), "42.9MB/s");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HumanThroughput(pub u64);
+
+impl Display for HumanThroughput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        byteps::format_into(f, self.0)
+    }
+}
+
+fn bytes(state: &ProgressState, w: &mut dyn fmt::Write) {
+    let _ = write!(w, "{}", HumanBytes(state.pos()));
+}
+
+fn total_bytes(state: &ProgressState, w: &mut dyn fmt::Write) {
+    let _ = write!(w, "{}", HumanBytes(state.len().unwrap_or(state.pos())));
+}
+
+fn bytes_per_sec(state: &ProgressState, w: &mut dyn fmt::Write) {
+    // Saturating conversion, `NaN` giving zero.
+    let _ = write!(w, "{}", HumanThroughput(state.per_sec() as u64));
+}
+
+fn binary_bytes(state: &ProgressState, w: &mut dyn fmt::Write) {
+    let _ = write!(w, "{}", BinaryBytes(state.pos()));
+}
+
+fn binary_total_bytes(state: &ProgressState, w: &mut dyn fmt::Write) {
+    let _ = write!(w, "{}", BinaryBytes(state.len().unwrap_or(state.pos())));
+}
+
+fn binary_bytes_per_sec(state: &ProgressState, w: &mut dyn fmt::Write) {
+    let options = BINARY.space_after_value(false).suffix("/s");
+    let _ = write!(w, "{}", SizeFormatter::new(state.per_sec() as u64, options));
+}
+
+/// Override the `bytes`, `total_bytes` and `bytes_per_sec` template keys of
+/// `style` (and their `decimal_` and `binary_` variants), so they are
+/// displayed using [`HumanBytes`], [`HumanThroughput`] and [`BinaryBytes`].
+pub fn with_keys(style: ProgressStyle) -> ProgressStyle {
+    style
+        .with_key("bytes", bytes)
+        .with_key("decimal_bytes", bytes)
+        .with_key("binary_bytes", binary_bytes)
+        .with_key("total_bytes", total_bytes)
+        .with_key("decimal_total_bytes", total_bytes)
+        .with_key("binary_total_bytes", binary_total_bytes)
+        .with_key("bytes_per_sec", bytes_per_sec)
+        .with_key("decimal_bytes_per_sec", bytes_per_sec)
+        .with_key("binary_bytes_per_sec", binary_bytes_per_sec)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle, TermLike};
+
+    /// Terminal keeping the last drawn line.
+    #[derive(Debug, Default, Clone)]
+    struct Term(Arc<Mutex<String>>);
+
+    impl TermLike for Term {
+        fn width(&self) -> u16 {
+            80
+        }
+
+        fn move_cursor_up(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_down(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_right(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_left(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_line(&self, s: &str) -> io::Result<()> {
+            self.write_str(s)
+        }
+
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            // Lines are padded to clear the previous content.
+            let s = s.trim_end();
+            if !s.is_empty() {
+                *self.0.lock().unwrap() = s.to_owned();
+            }
+            Ok(())
+        }
+
+        fn clear_line(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn with_keys() {
+        let term = Term::default();
+        let bar = ProgressBar::with_draw_target(
+            Some(4_500_000_000),
+            ProgressDrawTarget::term_like(Box::new(term.clone())),
+        );
+        let style = ProgressStyle::with_template(
+            "{bytes}/{total_bytes} {decimal_bytes} {binary_bytes}/{binary_total_bytes}",
+        )
+        .unwrap();
+        bar.set_style(super::with_keys(style));
+        bar.set_position(1_234_567);
+        bar.force_draw();
+        assert_eq!(*term.0.lock().unwrap(), "1.23MB/4.5GB 1.23MB 1.17MiB/4.19GiB");
+    }
+}
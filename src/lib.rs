@@ -1,4 +1,4 @@
-//! [SI prefix](https://en.wikipedia.org/wiki/Metric_prefix), data, packets, data-rate, packet-rate string parser and formater.
+//! [SI prefix](https://en.wikipedia.org/wiki/Metric_prefix), data, packets, data-rate, packet-rate, frequency string parser and formater.
 //!
 //! This crate is mainly targeting network related projects, where configuration
 //! and logs are expressed as bits and packets count.
@@ -6,21 +6,30 @@
 //! # Examples
 //!
 //! ```
-//! use indoc::indoc;
-//! use serde::{Deserialize, Serialize};
-//!
 //! assert_eq!(bity::si::parse("5.1M").unwrap(), 5_100_000);
 //!
 //! assert_eq!(bity::bit::parse("12.34kb").unwrap(), 12_340);
+//! assert_eq!(bity::byte::parse("12.34kB").unwrap(), 12_340);
 //! assert_eq!(bity::packet::parse("3.4kp").unwrap(), 3_400);
 //! assert_eq!(bity::bps::parse("8.65kB/s").unwrap(), 69_200);
+//! assert_eq!(bity::byteps::parse("8.65kB/s").unwrap(), 8_650);
 //! assert_eq!(bity::pps::parse("2.44Mpps").unwrap(), 2_440_000);
+//! assert_eq!(bity::hz::parse("2.4GHz").unwrap(), 2_400_000_000);
+//! assert_eq!(bity::ops::parse("500req/s").unwrap(), 500);
 //!
 //! assert_eq!(bity::si::format(5_100_000), "5.1M");
 //! assert_eq!(bity::bit::format(12_340), "12.34kb");
+//! assert_eq!(bity::byte::format(12_340), "12.34kB");
 //! assert_eq!(bity::packet::format(3_400), "3.4kp");
 //! assert_eq!(bity::bps::format(69_200), "69.2kb/s");
+//! assert_eq!(bity::byteps::format(8_650), "8.65kB/s");
 //! assert_eq!(bity::pps::format(2_440_000), "2.44Mp/s");
+//! assert_eq!(bity::hz::format(2_400_000_000), "2.4GHz");
+//! assert_eq!(bity::ops::format(1_200), "1.2kops");
+//!
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
 //!
 //! #[derive(Serialize, Deserialize, PartialEq, Debug)]
 //! #[serde(rename_all = "kebab-case")]
@@ -71,25 +80,112 @@
 //!         record = "88.3Mp/s"
 //!     "#}
 //! );
+//! # }
 //! ```
 //!
 //! # Features
 //! - No precision loss
 //! - Differentiate bits and bytes
-//! - `serde` support
+//! - `serde` support (plain integers for non human readable formats, like
+//!   bincode), [`serde_with`](https://docs.rs/serde_with) adapters and
+//!   `#[bity(...)]` field attributes
+//! - Compile-time parsing of constants and defaults, using [`default!`]
+//! - Customizable formatting (fixed prefix, precision, separator and suffix)
+//!   using a reusable [`formatter::Formatter`], and inline small-string
+//!   results (`compact_str` feature)
+//! - Regular expressions matching the accepted inputs (like
+//!   [`byte::PATTERN`]), to validate them outside of Rust
+//! - [`schemars`](https://docs.rs/schemars) JSON schemas (`schemars` feature),
+//!   using the `json_schema` functions of the unit modules or the
+//!   [`unit`] marker types
+//! - [`utoipa`](https://docs.rs/utoipa) OpenAPI schemas (`utoipa` feature),
+//!   using the `openapi_schema` functions of the unit modules or the
+//!   [`unit`] marker types (`ToSchema` derives only accept generic types
+//!   like `RelativeOrAbsolute<Byte>` through a type alias, like
+//!   [`limit::ByteLimit`])
+//! - [`miette`](https://docs.rs/miette) diagnostics pointing at the invalid
+//!   part of the input (`miette` feature), using `Error::with_input`
+//! - Bulk parsing of many inputs (like log tokens) using [`bulk`], in parallel
+//!   with the `rayon` feature, and faster scanning of long inputs with the
+//!   `swar` feature
+//! - Bounded caching of parsed inputs, using [`cache::CachedParser`]
+//! - No panics, whatever the input: parsing errors are returned and values
+//!   that don't fit are rejected, while [`si::try_format`] and
+//!   [`duration::try_format`] also report allocation failures
+//! - Compile-time bounds of the formatted strings lengths (like
+//!   [`si::MAX_FORMATTED_LEN`]), to size stack buffers
+//! - [`clap`](https://docs.rs/clap) value parsers for command line arguments
+//!   (`clap` feature), and [`argh`](https://docs.rs/argh) options (`argh`
+//!   feature)
+//! - Environment variables parsing, with errors naming the variable, using
+//!   [`env`]
+//! - A `bity` command line tool (`cli` feature), to parse, format and convert
+//!   values from shell scripts, or fields of piped lines (like `numfmt`)
+//! - [`wasm-bindgen`](https://docs.rs/wasm-bindgen) bindings (`wasm`
+//!   feature), to parse and format values the same way in web frontends
+//! - C bindings (`ffi` feature), with stable status codes and caller-provided
+//!   buffers, declared in `include/bity.h`
+//! - A [PyO3](https://docs.rs/pyo3) Python module (`python` feature), so
+//!   Python scripts interpret values exactly like Rust services
+//! - One cargo feature per unit family (`byte`, `byteps`, `duration`, ...),
+//!   all enabled by the default `all-units` feature, to only compile the
+//!   needed modules (`default-features = false, features = ["std",
+//!   "byteps"]`)
+//! - `no_std` support (with `alloc`), by disabling the default `std` feature.
+//!   [`env`], [`cache`], [`meter`], [`throttle`], the `std::error::Error`
+//!   implementations, the float conversions of [`db`] and the integrations
+//!   requiring std are then unavailable
+//! - Alloc-free formatting into a [`fmt::Write`](core::fmt::Write) (like a
+//!   `heapless::String`) or a byte slice, using the `format_into` and
+//!   `format_to_slice` functions (like [`si::format_to_slice`])
+//! - [`defmt`](https://docs.rs/defmt) logging of values from embedded
+//!   targets (`defmt` feature), using `bity::defmt::formatted`
+//! - [`ufmt`](https://docs.rs/ufmt) `uDisplay` implementations and writers
+//!   support (`ufmt` feature), using `bity::ufmt::formatted` or
+//!   `bity::ufmt::uformat`
+//! - [`arbitrary`](https://docs.rs/arbitrary) implementations and valid
+//!   inputs generation for fuzz targets (`arbitrary` feature), using
+//!   `bity::arbitrary::valid_input`
+//! - [`proptest`](https://docs.rs/proptest) strategies generating values, and
+//!   valid or near-valid inputs of each unit family (`proptest` feature),
+//!   like `bity::proptest::byte_string`
+//! - [`quickcheck`](https://docs.rs/quickcheck) implementations, and
+//!   shrinking valid inputs (`quickcheck` feature), using
+//!   `bity::quickcheck::ValidInput`
+//! - [`sqlx`](https://docs.rs/sqlx) `BIGINT` columns of [`limit::Limit`]s,
+//!   unlimited being `NULL` (`sqlx` feature)
+//! - [`diesel`](https://docs.rs/diesel) `Nullable<BigInt>` columns of
+//!   [`limit::Limit`]s (`diesel` feature)
+//! - [`rkyv`](https://docs.rs/rkyv) zero-copy serialization of the value
+//!   types (`rkyv` feature)
+//! - [`borsh`](https://docs.rs/borsh) serialization of the value types
+//!   (`borsh` feature)
+//! - [`valuable`](https://docs.rs/valuable) structured values holding the
+//!   raw value and its human readable string, for `tracing` fields
+//!   (`valuable` feature), like `bity::valuable::formatted`
+//! - [`metrics`](https://docs.rs/metrics) counters, gauges and histograms
+//!   described with the matching `metrics::Unit` (`metrics` feature), using
+//!   `bity::metrics::MetricsUnit`
+//! - [`indicatif`](https://docs.rs/indicatif) progress bars sizes and
+//!   data-rates (`indicatif` feature), using `bity::indicatif::with_keys`
+//! - Conversions between [`limit::ByteLimit`]s and the sizes of the
+//!   [`bytesize`](https://docs.rs/bytesize) (`bytesize` feature) and
+//!   [`byte-unit`](https://docs.rs/byte-unit) (`byte-unit` feature) crates
+//! - A [`humansize`] compatible API, to migrate from the
+//!   [`humansize`](https://docs.rs/humansize) crate
+//! - Conversions between [`limit::Limit`]s and [`uom`](https://docs.rs/uom)
+//!   information and information rate quantities (`uom` feature)
 //!
 //! # Limitations
 //! - Only support [metric prefixes](https://en.wikipedia.org/wiki/Metric_prefix),
 //!   [IEC prefixes](https://en.wikipedia.org/wiki/Binary_prefix) are not
-//!   supported
-//! - Bit oriented (not byte)
-//! - No customizable formating
+//!   supported, except for block sizes and the [`humansize`] layer
 //! - `u64` limited (doesn't go above *exa*, aka. `10^18`)
 
 #![warn(
     clippy::all,
     clippy::todo,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::mem_forget,
     clippy::unused_self,
@@ -98,9 +194,7 @@
     clippy::needless_borrow,
     clippy::match_wildcard_for_single_variants,
     clippy::if_let_mutex,
-    clippy::mismatched_target_os,
     clippy::await_holding_lock,
-    clippy::match_on_vec_items,
     clippy::imprecise_flops,
     clippy::suboptimal_flops,
     clippy::lossy_float_literal,
@@ -121,19 +215,216 @@
     missing_docs
 )]
 #![deny(unreachable_pub)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![cfg_attr(not(test), warn(clippy::print_stdout, clippy::dbg_macro))]
 
+extern crate alloc;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "argh")]
+pub mod argh;
+#[cfg(feature = "bit")]
 pub mod bit;
+#[cfg(feature = "byte")]
+pub mod block;
+#[cfg(feature = "bpp")]
+pub mod bpp;
+#[cfg(feature = "bps")]
 pub mod bps;
+#[cfg(feature = "byte")]
+pub mod buckets;
+pub mod bulk;
+#[cfg(feature = "byte")]
+pub mod byte;
+#[cfg(feature = "byte-unit")]
+mod byte_unit;
+#[cfg(feature = "byteps")]
+pub mod byteps;
+#[cfg(feature = "bytesize")]
+mod bytesize;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod custom;
+#[cfg(feature = "db")]
+pub mod db;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "duration")]
+pub mod duration;
+#[cfg(feature = "std")]
+pub mod env;
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod formatter;
+#[cfg(feature = "governor")]
+pub mod governor;
+#[cfg(feature = "byte")]
+pub mod histogram;
+pub mod humansize;
+#[cfg(feature = "hz")]
+pub mod hz;
+#[cfg(feature = "indicatif")]
+pub mod indicatif;
+pub mod limit;
+mod macros;
+#[cfg(all(feature = "std", feature = "byteps"))]
+pub mod meter;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "miette")]
+mod miette;
+#[cfg(all(feature = "byteps", feature = "pps"))]
+pub mod net;
+#[cfg(feature = "ops")]
+pub mod ops;
+#[cfg(feature = "packet")]
 pub mod packet;
+#[cfg(feature = "percent")]
+pub mod percent;
+mod period;
+#[cfg(feature = "pps")]
 pub mod pps;
+#[cfg(feature = "byteps")]
+pub mod progress;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+mod rate;
+#[cfg(feature = "percent")]
+pub mod relative;
+mod scan;
+#[cfg(feature = "schemars")]
+mod schemars;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "serde_with")]
+pub mod serde_with;
 pub mod si;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(all(feature = "std", feature = "byteps"))]
+pub mod throttle;
+#[cfg(feature = "ufmt")]
+pub mod ufmt;
+pub mod unit;
+#[cfg(feature = "uom")]
+pub mod uom;
+#[cfg(feature = "utoipa")]
+mod utoipa;
+#[cfg(feature = "valuable")]
+pub mod valuable;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "watt")]
+pub mod watt;
 
-pub use error::Error;
+/// Replace the `#[bity(...)]` attributes of the fields of a struct or an enum
+/// with the matching `#[serde(...)]` attributes.
+///
+/// It must be placed before `#[derive(Serialize, Deserialize)]`. The first
+/// option of a field attribute is its unit module (`si`, `bit`, `byte`,
+/// `packet`, `bps`, `byteps`, `pps`, `bpp`, `hz`, `watt`, `ops`, `percent`,
+/// `compression`, `db`, `dbm` or `duration`), followed by:
+/// - `strict`, to reject bare integers
+/// - `as_int`, to always serialize plain integers
+/// - `min = ...` and `max = ...`, integers or strings parsed at compile time
+///   using the `parse_const` function of the unit module, to reject out of
+///   range values
+///
+/// `Option<u64>` (also accepting missing fields) and `Vec<u64>` fields use the
+/// matching submodules.
+///
+/// # Examples
+///
+/// ```
+/// use indoc::indoc;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[bity::bity]
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// #[serde(rename_all = "kebab-case")]
+/// struct Configuration {
+///     #[bity(byte, max = "10GB")]
+///     disk_quota: u64,
+///     #[bity(bps)]
+///     max_upload: Option<u64>,
+///     #[bity(pps)]
+///     tiers: Vec<u64>,
+/// }
+///
+/// assert_eq!(
+///     toml::from_str::<Configuration>(indoc! {r#"
+///         disk-quota = "1.5GB"
+///         tiers = ["10kp/s", "1Mp/s"]
+///     "#})
+///     .unwrap(),
+///     Configuration {
+///         disk_quota: 1_500_000_000,
+///         max_upload: None,
+///         tiers: vec![10_000, 1_000_000],
+///     }
+/// );
+/// assert_eq!(
+///     toml::from_str::<Configuration>(r#"disk-quota = "12GB""#)
+///         .unwrap_err()
+///         .message(),
+///     r#""12GB" is out of range, expected between 0B and 10GB"#
+/// );
+/// ```
+///
+/// Invalid bounds are compile errors:
+///
+/// ```compile_fail
+/// #[bity::bity]
+/// #[derive(serde::Deserialize)]
+/// struct Configuration {
+///     #[bity(byte, max = "10Gb")]
+///     disk_quota: u64,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[bity::bity]
+/// #[derive(serde::Deserialize)]
+/// struct Configuration {
+///     #[bity(byte, min = "1GB", max = "100MB")]
+///     disk_quota: u64,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use bity_derive::bity;
+pub use error::{Error, ErrorKind, OwnedError};
+#[cfg(feature = "miette")]
+pub use miette::InputError;
+pub use period::Period;
+pub use rate::{rate_between, rate_between_counter32, rate_between_counter64, IntoDuration};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+    pub use alloc::{format, string::String, vec::Vec};
+
+    #[cfg(feature = "serde")]
+    pub use crate::serde::{
+        deserialize_bounded_with, deserialize_map_keys_with, deserialize_map_values_with,
+        deserialize_option_with, deserialize_strict_with, deserialize_vec_with, deserialize_with,
+        serialize_map_keys_with, serialize_map_values_with, serialize_option_with,
+        serialize_structured_with, serialize_vec_with, serialize_with, Numbers,
+    };
+}
 
 /// Strip at most one per-second prefix such as `/s` or `ps` (per-second).
 ///
@@ -156,6 +447,39 @@ pub fn strip_per_second(mut input: &str) -> &str {
         .unwrap_or(input)
 }
 
+/// Const version of [`strip_per_second`], for ASCII inputs.
+#[cfg(any(feature = "bps", feature = "byteps", feature = "pps"))]
+pub(crate) const fn strip_per_second_const(input: &str) -> &str {
+    let input = input.trim_ascii();
+    match strip_suffix_const(input, "/s") {
+        Some(stripped) => stripped,
+        None => match strip_suffix_const(input, "ps") {
+            Some(stripped) => stripped,
+            None => input,
+        },
+    }
+}
+
+/// Const version of [`str::strip_suffix`].
+#[cfg(any(feature = "bps", feature = "byteps", feature = "pps", feature = "ops"))]
+pub(crate) const fn strip_suffix_const<'a>(input: &'a str, suffix: &str) -> Option<&'a str> {
+    if input.len() < suffix.len() {
+        return None;
+    }
+    // Compare the bytes before splitting, as the input may not have a char
+    // boundary where the suffix would start.
+    let start = input.len() - suffix.len();
+    let (bytes, suffix) = (input.as_bytes(), suffix.as_bytes());
+    let mut i = 0;
+    while i < suffix.len() {
+        if bytes[start + i] != suffix[i] {
+            return None;
+        }
+        i += 1;
+    }
+    Some(input.split_at(start).0)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -167,4 +491,237 @@ mod tests {
         assert_eq!(super::strip_per_second("whateverps/s"), "whateverps");
         assert_eq!(super::strip_per_second("whatever/sps"), "whatever/s");
     }
+
+    #[cfg(any(feature = "bps", feature = "byteps", feature = "pps"))]
+    #[test]
+    fn strip_per_second_const() {
+        assert_eq!(super::strip_per_second_const(" whatever/s "), "whatever");
+        assert_eq!(super::strip_per_second_const("whateverps"), "whatever");
+        assert_eq!(super::strip_per_second_const("whatever/s/s"), "whatever/s");
+        assert_eq!(super::strip_per_second_const("whateverps/s"), "whateverps");
+        assert_eq!(super::strip_per_second_const("s"), "s");
+        assert_eq!(super::strip_per_second_const("1é"), "1é");
+    }
+
+    #[test]
+    fn no_panic() {
+        let inputs = [
+            "",
+            "1é",
+            "éps",
+            "é/s",
+            "18446744073709551615E",
+            "99999999999999999999.9k",
+            "1.99999999999999999999E",
+            "18446744073709551615w",
+            "-9223372036854775808dB",
+            "+-1",
+            "1Ex",
+        ];
+        for input in inputs {
+            let _ = crate::si::parse(input);
+            let _ = crate::si::parse_const(input);
+            let _ = crate::si::parse_with_additional_units(input, &[("x", 100)]);
+            let _ = crate::si::parse_const_with_additional_units(input, &[("x", 100)]);
+            #[cfg(feature = "bps")]
+            let _ = crate::bps::parse(input);
+            #[cfg(feature = "bps")]
+            let _ = crate::bps::parse_const(input);
+            #[cfg(feature = "byteps")]
+            let _ = crate::byteps::parse_const(input);
+            #[cfg(feature = "pps")]
+            let _ = crate::pps::parse_const(input);
+            #[cfg(feature = "percent")]
+            let _ = crate::percent::parse(input);
+            #[cfg(feature = "percent")]
+            let _ = crate::percent::parse_const(input);
+            #[cfg(feature = "db")]
+            let _ = crate::db::parse(input);
+            #[cfg(feature = "duration")]
+            let _ = crate::duration::parse(input);
+            #[cfg(feature = "byte")]
+            let _ = crate::block::parse_size(input);
+            #[cfg(feature = "compression")]
+            let _ = crate::compression::parse(input);
+            #[cfg(feature = "compression")]
+            let _ = crate::compression::parse_const(input);
+        }
+    }
+
+    #[test]
+    fn max_formatted_len() {
+        use crate::si::Prefix;
+
+        type Formats = fn(u64, Prefix) -> String;
+        let modules: &[(usize, Formats)] = &[
+            (crate::si::MAX_FORMATTED_LEN, crate::si::format_with_prefix),
+            #[cfg(feature = "bit")]
+            (crate::bit::MAX_FORMATTED_LEN, crate::bit::format_with_prefix),
+            #[cfg(feature = "byte")]
+            (crate::byte::MAX_FORMATTED_LEN, crate::byte::format_with_prefix),
+            #[cfg(feature = "packet")]
+            (crate::packet::MAX_FORMATTED_LEN, crate::packet::format_with_prefix),
+            #[cfg(feature = "bps")]
+            (crate::bps::MAX_FORMATTED_LEN, crate::bps::format_with_prefix),
+            #[cfg(feature = "byteps")]
+            (crate::byteps::MAX_FORMATTED_LEN, crate::byteps::format_with_prefix),
+            #[cfg(feature = "pps")]
+            (crate::pps::MAX_FORMATTED_LEN, crate::pps::format_with_prefix),
+            #[cfg(feature = "bpp")]
+            (crate::bpp::MAX_FORMATTED_LEN, crate::bpp::format_with_prefix),
+            #[cfg(feature = "hz")]
+            (crate::hz::MAX_FORMATTED_LEN, crate::hz::format_with_prefix),
+            #[cfg(feature = "watt")]
+            (crate::watt::MAX_FORMATTED_LEN, crate::watt::format_with_prefix),
+            #[cfg(feature = "ops")]
+            (crate::ops::MAX_FORMATTED_LEN, crate::ops::format_with_prefix),
+        ];
+        let prefixes = [
+            Prefix::None,
+            Prefix::Kilo,
+            Prefix::Mega,
+            Prefix::Giga,
+            Prefix::Tera,
+            Prefix::Peta,
+            Prefix::Exa,
+        ];
+        for (max, format) in modules {
+            let longest = prefixes
+                .iter()
+                .flat_map(|&prefix| [0, 999, u64::MAX].map(|input| format(input, prefix).len()))
+                .max();
+            assert_eq!(longest, Some(*max));
+        }
+
+        #[cfg(feature = "percent")]
+        {
+            assert_eq!(
+                crate::percent::format_ppm(u64::MAX).len(),
+                crate::percent::MAX_FORMATTED_LEN
+            );
+            assert!(crate::percent::format(u64::MAX).len() <= crate::percent::MAX_FORMATTED_LEN);
+        }
+        #[cfg(feature = "compression")]
+        assert_eq!(
+            crate::compression::format(u64::MAX - 1).len(),
+            crate::compression::MAX_FORMATTED_LEN
+        );
+        #[cfg(feature = "db")]
+        assert_eq!(crate::db::format_dbm(i64::MIN + 1).len(), crate::db::MAX_FORMATTED_LEN);
+        #[cfg(feature = "duration")]
+        assert_eq!(
+            crate::duration::format(std::time::Duration::new(
+                100_000_000_000_000 * 86_400 + 86_399,
+                999_999_999
+            ))
+            .len(),
+            crate::duration::MAX_FORMATTED_LEN
+        );
+    }
+
+    #[test]
+    fn format_to_slice() {
+        type Formats = (fn(u64) -> String, fn(&mut [u8], u64) -> Option<usize>);
+        let modules: &[Formats] = &[
+            (crate::si::format, crate::si::format_to_slice),
+            #[cfg(feature = "bit")]
+            (crate::bit::format, crate::bit::format_to_slice),
+            #[cfg(feature = "byte")]
+            (crate::byte::format, crate::byte::format_to_slice),
+            #[cfg(feature = "packet")]
+            (crate::packet::format, crate::packet::format_to_slice),
+            #[cfg(feature = "bps")]
+            (crate::bps::format, crate::bps::format_to_slice),
+            #[cfg(feature = "byteps")]
+            (crate::byteps::format, crate::byteps::format_to_slice),
+            #[cfg(feature = "pps")]
+            (crate::pps::format, crate::pps::format_to_slice),
+            #[cfg(feature = "bpp")]
+            (crate::bpp::format, crate::bpp::format_to_slice),
+            #[cfg(feature = "hz")]
+            (crate::hz::format, crate::hz::format_to_slice),
+            #[cfg(feature = "watt")]
+            (crate::watt::format, crate::watt::format_to_slice),
+            #[cfg(feature = "ops")]
+            (crate::ops::format, crate::ops::format_to_slice),
+        ];
+        let mut buffer = [0; 32];
+        for (format, format_to_slice) in modules {
+            for input in [0, 999, 12_345, u64::MAX] {
+                let expected = format(input);
+                let len = format_to_slice(&mut buffer, input).unwrap();
+                assert_eq!(&buffer[..len], expected.as_bytes());
+                assert_eq!(format_to_slice(&mut buffer[..len - 1], input), None);
+            }
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        use regex::Regex;
+
+        type Parses = fn(&str) -> bool;
+        let modules: &[(&str, Parses)] = &[
+            (crate::si::PATTERN, |input| crate::si::parse(input).is_ok()),
+            #[cfg(feature = "bit")]
+            (crate::bit::PATTERN, |input| crate::bit::parse(input).is_ok()),
+            #[cfg(feature = "byte")]
+            (crate::byte::PATTERN, |input| crate::byte::parse(input).is_ok()),
+            #[cfg(feature = "packet")]
+            (crate::packet::PATTERN, |input| crate::packet::parse(input).is_ok()),
+            #[cfg(feature = "bps")]
+            (crate::bps::PATTERN, |input| crate::bps::parse(input).is_ok()),
+            #[cfg(feature = "byteps")]
+            (crate::byteps::PATTERN, |input| crate::byteps::parse(input).is_ok()),
+            #[cfg(feature = "pps")]
+            (crate::pps::PATTERN, |input| crate::pps::parse(input).is_ok()),
+            #[cfg(feature = "bpp")]
+            (crate::bpp::PATTERN, |input| crate::bpp::parse(input).is_ok()),
+            #[cfg(feature = "hz")]
+            (crate::hz::PATTERN, |input| crate::hz::parse(input).is_ok()),
+            #[cfg(feature = "watt")]
+            (crate::watt::PATTERN, |input| crate::watt::parse(input).is_ok()),
+            #[cfg(feature = "ops")]
+            (crate::ops::PATTERN, |input| crate::ops::parse(input).is_ok()),
+            #[cfg(feature = "percent")]
+            (crate::percent::PATTERN, |input| crate::percent::parse(input).is_ok()),
+            #[cfg(feature = "compression")]
+            (crate::compression::PATTERN, |input| crate::compression::parse(input).is_ok()),
+            #[cfg(feature = "db")]
+            (crate::db::PATTERN, |input| crate::db::parse(input).is_ok()),
+            #[cfg(feature = "db")]
+            (crate::db::PATTERN_DBM, |input| crate::db::parse_dbm(input).is_ok()),
+            #[cfg(feature = "duration")]
+            (crate::duration::PATTERN, |input| crate::duration::parse(input).is_ok()),
+        ];
+
+        // Small enough numbers to never overflow.
+        let numbers = [
+            "0", "1", "1.5", ".5", "1.", "+1", "+.5", "-1", ".", "", "1..5", "1 .5", "a",
+        ];
+        let prefixes = ["", "k", "K", "m", "M", "p", "P", "x", "H"];
+        let units = [
+            "", "b", "B", "p", "Hz", "W", "/s", "ps", "bps", "Bps", "b/s", "B/s", "pps", "p/s",
+            "/packet", "/pkt", "/p", "B/p", "ops", "op/s", "req/s", "reqps", "rps", "%", "ppm",
+            "x", ":1", ": .5", "%:1", "dB", "dBm", "s", "ms", "min", "m", "h", "w", "h30m", " 30m",
+            "pp", "Hzz", "/s/s", "€",
+        ];
+        for (pattern, parse) in modules {
+            let regex = Regex::new(pattern).unwrap();
+            for number in numbers {
+                for prefix in prefixes {
+                    for unit in units {
+                        for separator in ["", " "] {
+                            let input = format!(" {number}{separator}{prefix}{unit} ");
+                            assert_eq!(
+                                regex.is_match(&input),
+                                parse(&input),
+                                "{pattern} {input:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
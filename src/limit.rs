@@ -0,0 +1,357 @@
+//! Limits which can be unlimited, like quotas.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use bity::limit::{ByteLimit, Limit};
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Quotas {
+//!     disk: ByteLimit,
+//!     memory: ByteLimit,
+//! }
+//!
+//! let quotas = Quotas {
+//!     disk: Limit::new(5_000_000_000),
+//!     memory: Limit::unlimited(),
+//! };
+//! let serialized = indoc! {r#"
+//!     disk = "5GB"
+//!     memory = "unlimited"
+//! "#};
+//!
+//! assert_eq!(toml::from_str::<Quotas>(serialized).unwrap(), quotas);
+//! assert_eq!(toml::to_string(&quotas).unwrap(), serialized);
+//! assert_eq!(quotas.disk.value(), Some(5_000_000_000));
+//! assert!(quotas.memory.is_unlimited());
+//! # }
+//! ```
+
+use core::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
+
+#[cfg(feature = "byte")]
+use crate::unit::Byte;
+use crate::{unit::Unit, Error};
+
+/// A limit expressed in a given [`Unit`], or unlimited.
+///
+/// It's parsed from `unlimited`, `none` or a string of the unit (like `5GB` for
+/// [`ByteLimit`]), and formatted back as `unlimited` or a string of the unit.
+///
+/// If the `serde` feature is enabled, it implements `Serialize` and
+/// `Deserialize`, also accepting integers and `null` (unlimited). Non human
+/// readable formats use an `Option<u64>`. If the `rkyv` feature is enabled,
+/// it implements rkyv's `Archive`, `Serialize` and `Deserialize`, and if the
+/// `borsh` feature is enabled, `BorshSerialize` and `BorshDeserialize` (as an
+/// `Option<u64>`).
+///
+/// The default limit is unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct Limit<U> {
+    value: Option<u64>,
+    #[cfg_attr(feature = "borsh", borsh(skip))]
+    unit: PhantomData<U>,
+}
+
+/// A limit in bytes.
+#[cfg(feature = "byte")]
+pub type ByteLimit = Limit<Byte>;
+
+impl<U> Limit<U> {
+    /// Create a limit of the given value.
+    pub const fn new(value: u64) -> Self {
+        Self {
+            value: Some(value),
+            unit: PhantomData,
+        }
+    }
+
+    /// Create an unlimited limit.
+    pub const fn unlimited() -> Self {
+        Self {
+            value: None,
+            unit: PhantomData,
+        }
+    }
+
+    /// Whether the limit is unlimited.
+    pub const fn is_unlimited(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Value of the limit, `None` if unlimited.
+    pub const fn value(&self) -> Option<u64> {
+        self.value
+    }
+
+    /// Whether a given value is within the limit (inclusive).
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::limit::ByteLimit;
+    ///
+    /// assert!(ByteLimit::new(1_000).allows(1_000));
+    /// assert!(!ByteLimit::new(1_000).allows(1_001));
+    /// assert!(ByteLimit::unlimited().allows(u64::MAX));
+    /// ```
+    pub const fn allows(&self, value: u64) -> bool {
+        match self.value {
+            Some(limit) => value <= limit,
+            None => true,
+        }
+    }
+}
+
+impl<U: Unit> Limit<U> {
+    /// Parse `unlimited`, `none` or a string of the unit into a limit.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{limit::ByteLimit, Error};
+    ///
+    /// assert_eq!(ByteLimit::parse("1.5GB").unwrap(), ByteLimit::new(1_500_000_000));
+    /// assert_eq!(ByteLimit::parse(" unlimited ").unwrap(), ByteLimit::unlimited());
+    /// assert_eq!(ByteLimit::parse("none").unwrap(), ByteLimit::unlimited());
+    /// assert!(matches!(ByteLimit::parse("1.5Gx"), Err(Error::InvalidUnit("Gx"))));
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, Error<'_>> {
+        match input.trim() {
+            "unlimited" | "none" => Ok(Self::unlimited()),
+            _ => U::parse(input).map(Self::new),
+        }
+    }
+}
+
+impl<U> From<Option<u64>> for Limit<U> {
+    fn from(value: Option<u64>) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+}
+
+impl<U> From<Limit<U>> for Option<u64> {
+    fn from(limit: Limit<U>) -> Self {
+        limit.value
+    }
+}
+
+/// Error returned when converting an unlimited [`Limit`] into a type which
+/// can't represent it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnlimitedError;
+
+impl Display for UnlimitedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("unlimited value")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnlimitedError {}
+
+impl<U: Unit> Display for Limit<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(value) => f.write_str(&U::format(value)),
+            None => f.write_str("unlimited"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<U: Unit> serde::Serialize for Limit<U> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::serde::serialize_limit_with(self.value, serializer, U::format)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, U: Unit> serde::Deserialize<'de> for Limit<U> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde::deserialize_limit_with(
+            deserializer,
+            U::EXPECTING,
+            U::UNITS,
+            U::NUMBERS,
+            U::parse,
+        )
+        .map(Self::from)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<U: schemars::JsonSchema> schemars::JsonSchema for Limit<U> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        format!("{}Limit", U::schema_name()).into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        format!("bity::limit::Limit<{}>", U::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "anyOf": [
+                generator.subschema_for::<U>(),
+                { "enum": ["unlimited", "none"] },
+                { "type": "null" },
+            ],
+        })
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<U: utoipa::ToSchema> utoipa::PartialSchema for Limit<U> {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{AnyOfBuilder, ObjectBuilder, Ref, Type};
+
+        AnyOfBuilder::new()
+            .item(Ref::from_schema_name(U::name()))
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["unlimited", "none"])),
+            )
+            .item(ObjectBuilder::new().schema_type(Type::Null))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<U: utoipa::ToSchema> utoipa::ToSchema for Limit<U> {
+    fn name() -> std::borrow::Cow<'static, str> {
+        format!("{}Limit", U::name()).into()
+    }
+
+    fn schemas(
+        schemas: &mut Vec<(String, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>)>,
+    ) {
+        schemas.push((U::name().into_owned(), U::schema()));
+        U::schemas(schemas);
+    }
+}
+
+#[cfg(all(test, feature = "byte", feature = "bps"))]
+mod tests {
+    use super::{ByteLimit, Limit};
+    use crate::{error::Error, unit::Bps};
+
+    #[test]
+    fn parse() {
+        assert_eq!(ByteLimit::parse("12").unwrap(), ByteLimit::new(12));
+        assert_eq!(ByteLimit::parse("1.5kB").unwrap(), ByteLimit::new(1_500));
+        assert_eq!(ByteLimit::parse("unlimited").unwrap(), ByteLimit::unlimited());
+        assert_eq!(ByteLimit::parse("none").unwrap(), ByteLimit::unlimited());
+        assert_eq!(Limit::<Bps>::parse("1Mbps").unwrap().value(), Some(1_000_000));
+        assert!(matches!(ByteLimit::parse("Unlimited"), Err(Error::InvalidUnit(_))));
+        assert!(matches!(ByteLimit::parse(""), Err(Error::EmptyInput)));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(ByteLimit::new(1_500).to_string(), "1.5kB");
+        assert_eq!(ByteLimit::unlimited().to_string(), "unlimited");
+        assert_eq!(Limit::<Bps>::new(1_000_000).to_string(), "1Mb/s");
+        assert_eq!(ByteLimit::default(), ByteLimit::unlimited());
+        assert_eq!(Option::<u64>::from(ByteLimit::new(12)), Some(12));
+        assert_eq!(ByteLimit::from(None), ByteLimit::unlimited());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Quotas {
+            disk: ByteLimit,
+            #[serde(default)]
+            upload: Limit<Bps>,
+        }
+
+        assert_eq!(
+            toml::from_str::<Quotas>(indoc::indoc! {r#"
+                disk = "none"
+                upload = 1_000
+            "#})
+            .unwrap(),
+            Quotas {
+                disk: ByteLimit::unlimited(),
+                upload: Limit::new(1_000),
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Quotas>(r#"disk = "5GB""#).unwrap(),
+            Quotas {
+                disk: ByteLimit::new(5_000_000_000),
+                upload: Limit::unlimited(),
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Quotas>(r#"disk = "5Gb""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Gb" in "5Gb" (did you mean "GB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+        assert_eq!(
+            toml::from_str::<Quotas>("disk = true")
+                .unwrap_err()
+                .message(),
+            r#"invalid type: boolean `true`, expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+        let deserializer = serde::de::value::UnitDeserializer::<serde::de::value::Error>::new();
+        assert_eq!(serde::Deserialize::deserialize(deserializer), Ok(ByteLimit::unlimited()));
+
+        let quotas = Quotas {
+            disk: ByteLimit::new(5_000_000_000),
+            upload: Limit::unlimited(),
+        };
+        assert_eq!(toml::to_string(&quotas).unwrap(), "disk = \"5GB\"\nupload = \"unlimited\"\n");
+        let bytes = bincode::serialize(&quotas).unwrap();
+        assert_eq!(bytes, bincode::serialize(&(Some(5_000_000_000u64), None::<u64>)).unwrap());
+        assert_eq!(bincode::deserialize::<Quotas>(&bytes).unwrap(), quotas);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv() {
+        use rkyv::rancor::Error;
+
+        for limit in [ByteLimit::new(1_500), ByteLimit::unlimited()] {
+            let bytes = rkyv::to_bytes::<Error>(&limit).unwrap();
+            let archived = rkyv::access::<rkyv::Archived<ByteLimit>, Error>(&bytes).unwrap();
+            assert_eq!(rkyv::deserialize::<ByteLimit, Error>(archived).unwrap(), limit);
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn borsh() {
+        for limit in [ByteLimit::new(1_500), ByteLimit::unlimited()] {
+            let bytes = borsh::to_vec(&limit).unwrap();
+            assert_eq!(bytes, borsh::to_vec(&limit.value()).unwrap());
+            assert_eq!(borsh::from_slice::<ByteLimit>(&bytes).unwrap(), limit);
+        }
+    }
+}
@@ -0,0 +1,412 @@
+/// Generate a complete unit module, following the same pattern as the ones
+/// provided by this crate (like [`packet`](crate::packet)).
+///
+/// The generated module contains:
+/// - `parse`, calling [`si::parse_with_additional_units`](crate::si::parse_with_additional_units)
+///   with the given units table
+/// - `parse_const`, its const version (usable with [`default!`](crate::default))
+/// - `UNITS`, the given units, suggested in deserialization errors
+/// - `format`, appending the given suffix to [`si::format`](crate::si::format)
+/// - `format_with_prefix`, appending the given suffix to
+///   [`si::format_with_prefix`](crate::si::format_with_prefix)
+/// - `serialize` and `deserialize`, if the `serde` feature of this crate is
+///   enabled, usable with `#[serde(with = "...")]`, and the same functions for
+///   `Option<u64>`, `Vec<u64>` and map fields in the `option`, `vec`,
+///   `map_values` and `map_keys` submodules, an `as_int` submodule always
+///   serializing plain integers, a `strict` submodule rejecting bare integers,
+///   a `structured` submodule using `{ value, unit }` maps, a
+///   `Bounded<MIN, MAX>` type rejecting out of range values, a
+///   `Prefixed<PREFIX>` type always serializing using the given SI prefix and
+///   a `Quantized<DIGITS>` type rounding to the given significant digits
+/// - `EXPECTING`, if the `serde` feature of this crate is enabled, describing
+///   the accepted inputs in deserialization errors
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// bity::define_unit! {
+///     /// SI prefixed messages count.
+///     pub mod message {
+///         suffix: "msg",
+///         units: [("msg", 1), ("batch", 100)],
+///     }
+/// }
+///
+/// assert_eq!(message::parse("12msg").unwrap(), 12);
+/// assert_eq!(message::parse("1.2kmsg").unwrap(), 1_200);
+/// assert_eq!(message::parse("3batch").unwrap(), 300);
+/// assert_eq!(message::format(12), "12msg");
+/// assert_eq!(message::format(1_200), "1.2kmsg");
+///
+/// #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+/// struct Queue {
+///     #[serde(with = "message")]
+///     capacity: u64,
+/// }
+///
+/// assert_eq!(
+///     toml::from_str::<Queue>(r#"capacity = "10kmsg""#).unwrap(),
+///     Queue { capacity: 10_000 }
+/// );
+/// assert_eq!(
+///     toml::to_string(&Queue { capacity: 10_000 }).unwrap(),
+///     "capacity = \"10kmsg\"\n"
+/// );
+/// assert_eq!(
+///     toml::from_str::<Queue>(&toml::to_string(&Queue { capacity: 12 }).unwrap()).unwrap(),
+///     Queue { capacity: 12 }
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_unit {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $name:ident {
+            suffix: $suffix:literal,
+            units: [$(($unit:literal, $factor:expr)),* $(,)?] $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis mod $name {
+            /// Units accepted after the SI prefix, listed in error reports.
+            pub const UNITS: &[&str] = &[$($unit),*];
+
+            #[doc = concat!(
+                "Parse a SI prefixed string, optionally followed by one of the `",
+                $(stringify!($unit), " ",)*
+                "` units, into a number."
+            )]
+            pub fn parse(input: &str) -> ::core::result::Result<u64, $crate::Error<'_>> {
+                $crate::si::parse_with_additional_units(input, &[$(($unit, $factor)),*])
+            }
+
+            /// Const version of `parse`, returning `None` if the input is
+            /// invalid (or overflows).
+            pub const fn parse_const(input: &str) -> ::core::option::Option<u64> {
+                $crate::si::parse_const_with_additional_units(input, &[$(($unit, $factor)),*])
+            }
+
+            #[doc = concat!("Format an integer into a SI prefixed string followed by `", $suffix, "`.")]
+            pub fn format(input: u64) -> $crate::__private::String {
+                let mut output = $crate::__private::String::with_capacity(
+                    $crate::si::MAX_FORMATTED_LEN + $suffix.len(),
+                );
+                // Writing into a `String` can't fail.
+                let _ = $crate::si::format_into(&mut output, input);
+                output.push_str($suffix);
+                output
+            }
+
+            #[doc = concat!(
+                "Format an integer into a string using a given SI prefix, followed by `",
+                $suffix,
+                "`."
+            )]
+            pub fn format_with_prefix(input: u64, prefix: $crate::si::Prefix) -> $crate::__private::String {
+                let mut output = $crate::__private::String::with_capacity(
+                    $crate::si::MAX_FORMATTED_LEN + $suffix.len(),
+                );
+                // Writing into a `String` can't fail.
+                let _ = $crate::si::format_with_prefix_into(&mut output, input, prefix);
+                output.push_str($suffix);
+                output
+            }
+
+            $crate::__define_unit_serde!(::core::concat!(
+                "an integer or a SI prefixed string like \"1.5k",
+                $suffix,
+                "\""
+            ));
+        }
+    };
+}
+
+/// Parse a string literal at compile time using the `parse_const` function of
+/// a given unit module (like [`byte::parse_const`](crate::byte::parse_const)),
+/// failing to compile if it's invalid.
+///
+/// It allows expressing defaults (like `#[serde(default = "...")]` functions)
+/// or constants in human units rather than magic integers.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use bity::{bps, byte};
+/// use serde::Deserialize;
+///
+/// const MAX_UPLOAD: u64 = bity::default!(bps, "100Mb/s");
+///
+/// fn default_quota() -> u64 {
+///     bity::default!(byte, "1GB")
+/// }
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct Configuration {
+///     #[serde(default = "default_quota", with = "bity::byte")]
+///     quota: u64,
+/// }
+///
+/// assert_eq!(MAX_UPLOAD, 100_000_000);
+/// assert_eq!(
+///     toml::from_str::<Configuration>("").unwrap(),
+///     Configuration { quota: 1_000_000_000 }
+/// );
+/// # }
+/// ```
+///
+/// Invalid values are compile errors:
+///
+/// ```compile_fail
+/// use bity::byte;
+///
+/// const QUOTA: u64 = bity::default!(byte, "1Gb");
+/// ```
+#[macro_export]
+macro_rules! default {
+    ($($module:ident)::+, $input:literal) => {
+        const {
+            match $($module)::+::parse_const($input) {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => ::core::panic!(::core::concat!(
+                    "invalid ",
+                    ::core::stringify!($($module)::+),
+                    " value: ",
+                    $input
+                )),
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_unit_serde {
+    ($expecting:expr) => {
+        /// Short description of the accepted inputs, with a few examples,
+        /// quoted in deserialization errors.
+        pub const EXPECTING: &str = $expecting;
+
+        /// How bare numbers are deserialized.
+        #[doc(hidden)]
+        pub const NUMBERS: $crate::__private::Numbers = $crate::__private::Numbers::Exact;
+
+        /// Serialize a given `u64` into a SI prefixed string.
+        pub fn serialize<S>(value: &u64, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+        where
+            S: $crate::__private::serde::Serializer,
+        {
+            $crate::__private::serialize_with(*value, serializer, format)
+        }
+
+        /// Deserialize a given integer or SI prefixed string into an `u64`.
+        pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+        where
+            D: $crate::__private::serde::Deserializer<'de>,
+        {
+            $crate::__private::deserialize_with(deserializer, EXPECTING, UNITS, NUMBERS, parse)
+        }
+
+        $crate::__serde_submodules!();
+        $crate::__serde_prefixed!();
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_unit_serde {
+    ($expecting:expr) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    crate::define_unit! {
+        /// Test unit.
+        #[allow(unreachable_pub, dead_code)]
+        mod row {
+            suffix: "rows",
+            units: [("row", 1), ("rows", 1), ("page", 50)],
+        }
+    }
+
+    crate::define_unit! {
+        /// Test unit starting with a SI prefix letter.
+        #[allow(unreachable_pub, dead_code)]
+        mod message {
+            suffix: "msg",
+            units: [("msg", 1), ("events", 1)],
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(row::parse("12").unwrap(), 12);
+        assert_eq!(row::parse("12 rows").unwrap(), 12);
+        assert_eq!(row::parse("12row").unwrap(), 12);
+        assert_eq!(row::parse("1.2krows").unwrap(), 1_200);
+        assert_eq!(row::parse("2kpage").unwrap(), 100_000);
+        assert!(matches!(row::parse("12 lines"), Err(Error::InvalidUnit("lines"))));
+
+        assert_eq!(message::parse("12msg").unwrap(), 12);
+        assert_eq!(message::parse("5 events").unwrap(), 5);
+        assert_eq!(message::parse("1.2kmsg").unwrap(), 1_200);
+        assert_eq!(message::parse("2mmsg").unwrap(), 2_000_000);
+        assert_eq!(message::parse_const("12msg"), Some(12));
+        assert_eq!(message::parse_const("5Gevents"), Some(5_000_000_000));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(row::format(1_200), "1.2krows");
+        for value in [0, 12, 999, 1_200, 5_000_000] {
+            assert_eq!(message::parse(&message::format(value)).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn default() {
+        const LIMIT: u64 = crate::default!(row, "2kpage");
+        assert_eq!(LIMIT, 100_000);
+        #[cfg(feature = "byte")]
+        assert_eq!(crate::default!(crate::byte, "1.5GB"), 1_500_000_000);
+        assert_eq!(row::parse_const("12 lines"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Table {
+            #[serde(with = "row")]
+            size: u64,
+            #[serde(default, with = "row::option", skip_serializing_if = "Option::is_none")]
+            limit: Option<u64>,
+            #[serde(default, with = "row::vec")]
+            pages: Vec<u64>,
+            #[serde(default, with = "row::map_values")]
+            quotas: std::collections::BTreeMap<String, u64>,
+            #[serde(default, with = "row::map_keys")]
+            tiers: std::collections::BTreeMap<u64, String>,
+            #[serde(default, with = "row::as_int")]
+            offset: u64,
+            #[serde(default, with = "row::strict")]
+            batch: u64,
+            #[serde(default, with = "row::Bounded::<0, 1_000>")]
+            width: u64,
+            #[serde(default, with = "row::Prefixed::<'k'>")]
+            height: u64,
+            #[serde(default, with = "row::structured")]
+            depth: u64,
+            #[serde(default, with = "row::Quantized::<1>")]
+            area: u64,
+        }
+
+        assert_eq!(
+            toml::from_str::<Table>(r#"size = "2kpage""#).unwrap(),
+            Table {
+                size: 100_000,
+                limit: None,
+                pages: vec![],
+                quotas: Default::default(),
+                tiers: Default::default(),
+                offset: 0,
+                batch: 0,
+                width: 0,
+                height: 0,
+                depth: 0,
+                area: 0,
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Table>(indoc::indoc! {r#"
+                size = 12
+                limit = "unlimited"
+                pages = ["2kpage", 3]
+                tiers = { "50rows" = "small" }
+                offset = "1.5krows"
+                batch = "2kpage"
+                width = "1krows"
+                depth = { value = 2, unit = "kpage" }
+            "#})
+            .unwrap(),
+            Table {
+                size: 12,
+                limit: None,
+                pages: vec![100_000, 3],
+                quotas: Default::default(),
+                tiers: [(50, "small".to_owned())].into(),
+                offset: 1_500,
+                batch: 100_000,
+                width: 1_000,
+                height: 0,
+                depth: 100_000,
+                area: 0,
+            }
+        );
+        assert_eq!(
+            toml::to_string(&Table {
+                size: 1_200,
+                limit: Some(3_000),
+                pages: vec![1_000],
+                quotas: [("alice".to_owned(), 2_000)].into(),
+                tiers: [(50, "small".to_owned())].into(),
+                offset: 1_500,
+                batch: 100,
+                width: 20,
+                height: 20,
+                depth: 1_200,
+                area: 1_600,
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                size = "1.2krows"
+                limit = "3krows"
+                pages = ["1krows"]
+                offset = 1500
+                batch = "100rows"
+                width = "20rows"
+                height = "0.02krows"
+                area = "2krows"
+
+                [quotas]
+                alice = "2krows"
+
+                [tiers]
+                50rows = "small"
+
+                [depth]
+                value = 1.2
+                unit = "krows"
+            "#}
+        );
+        assert_eq!(
+            toml::from_str::<Table>("size = 12\nwidth = 1001")
+                .unwrap_err()
+                .message(),
+            "1001 is out of range, expected between 0rows and 1krows"
+        );
+        assert_eq!(
+            toml::from_str::<Table>(r#"size = "12 lines""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "lines" in "12 lines", expected an integer or a SI prefixed string like "1.5krows""#
+        );
+
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Queue {
+            #[serde(with = "message")]
+            capacity: u64,
+        }
+
+        for capacity in [12, 12_000] {
+            let queue = Queue { capacity };
+            assert_eq!(toml::from_str::<Queue>(&toml::to_string(&queue).unwrap()).unwrap(), queue);
+        }
+    }
+}
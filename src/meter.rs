@@ -0,0 +1,432 @@
+//! Throughput measurement.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//!
+//! use bity::meter::{RateMeter, Throughput};
+//!
+//! let start = Instant::now();
+//! let mut throughput = Throughput::new(Duration::from_secs(5));
+//! throughput.record(0, start);
+//! throughput.record(42_900_000, start + Duration::from_secs(1));
+//! throughput.record(42_900_000, start + Duration::from_secs(2));
+//!
+//! assert_eq!(throughput.rate(), 42_900_000);
+//! assert_eq!(throughput.to_string(), "42.9MB/s");
+//!
+//! let mut meter = RateMeter::new(Duration::from_secs(60));
+//! meter.record(12_000_000, start);
+//! meter.record(8_000_000, start + Duration::from_secs(1));
+//! meter.record(0, start + Duration::from_secs(2));
+//!
+//! assert_eq!(meter.current(), 8_000_000);
+//! assert_eq!(meter.sustained(), 10_000_000);
+//! assert_eq!(meter.peak(), 12_000_000);
+//! assert_eq!(meter.to_string(), "8MB/s (sustained 10MB/s, peak 12MB/s)");
+//! ```
+
+use std::{
+    collections::VecDeque,
+    f64::consts::LN_2,
+    fmt::{self, Display, Formatter},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{byte, byteps};
+
+/// Exponentially weighted moving average of a data-rate (in bytes per
+/// second).
+///
+/// Samples are the number of bytes transferred since the previous sample and
+/// the instant they were recorded at. The first sample only sets the reference
+/// instant, its byte count is ignored. Samples recorded at the same instant
+/// as the previous one are accumulated until time moves forward.
+///
+/// The weight of a past rate halves every `half_life`, whatever the number of
+/// samples recorded during that time.
+///
+/// The [`Display`] implementation formats the current rate using
+/// [`byteps::format`].
+#[derive(Debug, Clone)]
+pub struct Throughput {
+    half_life: Duration,
+    rate: Option<f64>,
+    last: Option<Instant>,
+    pending: u64,
+}
+
+impl Throughput {
+    /// Create a new tracker with no samples, using the given half-life.
+    pub fn new(half_life: Duration) -> Self {
+        Self {
+            half_life,
+            rate: None,
+            last: None,
+            pending: 0,
+        }
+    }
+
+    /// Record that `bytes` were transferred since the previous sample.
+    ///
+    /// Samples older than the previous one are ignored.
+    pub fn record(&mut self, bytes: u64, at: Instant) {
+        let Some(last) = self.last else {
+            self.last = Some(at);
+            return;
+        };
+        if at < last {
+            return;
+        }
+
+        self.pending = self.pending.saturating_add(bytes);
+        let elapsed = at - last;
+        if elapsed.is_zero() {
+            return;
+        }
+
+        let instant_rate = self.pending as f64 / elapsed.as_secs_f64();
+        self.rate = Some(match self.rate {
+            Some(rate) if !self.half_life.is_zero() => {
+                let weight =
+                    -(-elapsed.as_secs_f64() * LN_2 / self.half_life.as_secs_f64()).exp_m1();
+                weight.mul_add(instant_rate - rate, rate)
+            }
+            _ => instant_rate,
+        });
+        self.last = Some(at);
+        self.pending = 0;
+    }
+
+    /// Current data-rate, in bytes per second.
+    ///
+    /// Returns `0` until at least two samples at different instants were
+    /// recorded.
+    pub fn rate(&self) -> u64 {
+        self.rate.map_or(0, |rate| rate.round() as u64)
+    }
+}
+
+impl Display for Throughput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&byteps::format(self.rate()))
+    }
+}
+
+/// Sliding-window data-rate meter (in bytes per second).
+///
+/// Samples are aggregated into one second buckets, starting at the instant of
+/// the first sample. The meter keeps the buckets of the last `window` seconds,
+/// plus the bucket of the second in progress which is never used for
+/// reporting. Only the seconds during which data was transferred are stored,
+/// so memory doesn't grow with idle periods, even with a large window.
+///
+/// Reports are relative to the most recent sample, record an empty sample
+/// (`record(0, Instant::now())`) to move the window forward when no data is
+/// transferred.
+///
+/// The [`Display`] implementation formats the current, sustained and peak
+/// rates using [`byteps::format`].
+#[derive(Debug, Clone)]
+pub struct RateMeter {
+    window: u64,
+    origin: Option<Instant>,
+    head: u64,
+    /// Non-empty buckets as `(second, bytes)`, oldest first.
+    buckets: VecDeque<(u64, u64)>,
+}
+
+impl RateMeter {
+    /// Create a new meter over a given window, truncated to the second (at
+    /// least one second).
+    pub fn new(window: Duration) -> Self {
+        let window = window.as_secs().max(1);
+        Self {
+            window,
+            origin: None,
+            head: 0,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    /// Record that `bytes` were transferred at a given instant.
+    ///
+    /// Samples older than the window are ignored.
+    pub fn record(&mut self, bytes: u64, at: Instant) {
+        let origin = *self.origin.get_or_insert(at);
+        let Some(index) = at
+            .checked_duration_since(origin)
+            .map(|elapsed| elapsed.as_secs())
+        else {
+            return;
+        };
+
+        if index > self.head {
+            self.head = index;
+            let oldest = self.oldest();
+            while self
+                .buckets
+                .front()
+                .is_some_and(|&(second, _)| second < oldest)
+            {
+                self.buckets.pop_front();
+            }
+        }
+        if index < self.oldest() || bytes == 0 {
+            return;
+        }
+
+        match self
+            .buckets
+            .binary_search_by_key(&index, |&(second, _)| second)
+        {
+            Ok(position) => {
+                let bucket = &mut self.buckets[position].1;
+                *bucket = bucket.saturating_add(bytes);
+            }
+            Err(position) => self.buckets.insert(position, (index, bytes)),
+        }
+    }
+
+    /// Oldest second of the window.
+    fn oldest(&self) -> u64 {
+        self.head.saturating_sub(self.window)
+    }
+
+    /// Non-empty completed buckets as `(second, bytes)`, oldest first.
+    fn completed(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.buckets
+            .iter()
+            .copied()
+            .take_while(|&(second, _)| second < self.head)
+    }
+
+    /// Data-rate of the last completed second, in bytes per second.
+    pub fn current(&self) -> u64 {
+        match self.completed().last() {
+            Some((second, bytes)) if second + 1 == self.head => bytes,
+            _ => 0,
+        }
+    }
+
+    /// Average data-rate over the completed seconds of the window, in bytes
+    /// per second.
+    pub fn sustained(&self) -> u64 {
+        let count = self.head.min(self.window);
+        if count == 0 {
+            return 0;
+        }
+        let sum = self
+            .completed()
+            .map(|(_, bytes)| u128::from(bytes))
+            .sum::<u128>();
+        (sum / u128::from(count)) as u64
+    }
+
+    /// Highest data-rate of a completed second of the window, in bytes per
+    /// second.
+    pub fn peak(&self) -> u64 {
+        self.completed().map(|(_, bytes)| bytes).max().unwrap_or(0)
+    }
+}
+
+impl Display for RateMeter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (sustained {}, peak {})",
+            byteps::format(self.current()),
+            byteps::format(self.sustained()),
+            byteps::format(self.peak())
+        )
+    }
+}
+
+/// Thread-safe byte counter.
+///
+/// The counter wraps around on overflow, like [`AtomicU64::fetch_add`]. All
+/// operations use relaxed ordering, as the counter isn't meant to synchronize
+/// other memory accesses.
+///
+/// The [`Display`] implementation formats a snapshot of the counter using
+/// [`byte::format`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, thread};
+///
+/// use bity::meter::AtomicByteCounter;
+///
+/// let counter = Arc::new(AtomicByteCounter::new());
+/// let handles = (0..4)
+///     .map(|_| {
+///         let counter = Arc::clone(&counter);
+///         thread::spawn(move || counter.add(1_500_000))
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(counter.snapshot(), 6_000_000);
+/// assert_eq!(counter.to_string(), "6MB");
+/// ```
+#[derive(Debug, Default)]
+pub struct AtomicByteCounter(AtomicU64);
+
+impl AtomicByteCounter {
+    /// Create a new counter starting at zero.
+    pub const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    /// Add `bytes` to the counter.
+    pub fn add(&self, bytes: u64) {
+        self.0.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Current value of the counter.
+    pub fn snapshot(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Reset the counter to zero, returning its previous value.
+    pub fn take(&self) -> u64 {
+        self.0.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl Display for AtomicByteCounter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&byte::format(self.snapshot()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{AtomicByteCounter, RateMeter, Throughput};
+
+    #[test]
+    fn throughput() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut throughput = Throughput::new(Duration::from_secs(1));
+        assert_eq!(throughput.rate(), 0);
+
+        // Reference instant only.
+        throughput.record(1_000, at(0));
+        assert_eq!(throughput.rate(), 0);
+
+        // Same instant samples are accumulated.
+        throughput.record(1_000, at(0));
+        assert_eq!(throughput.rate(), 0);
+        throughput.record(1_000, at(1_000));
+        assert_eq!(throughput.rate(), 2_000);
+
+        // Stable rate.
+        throughput.record(2_000, at(2_000));
+        throughput.record(1_000, at(2_500));
+        assert_eq!(throughput.rate(), 2_000);
+
+        // Half-life.
+        throughput.record(0, at(3_500));
+        assert_eq!(throughput.rate(), 1_000);
+        throughput.record(0, at(4_500));
+        assert_eq!(throughput.rate(), 500);
+
+        // Out of order samples are ignored.
+        throughput.record(1_000_000, at(0));
+        assert_eq!(throughput.rate(), 500);
+        assert_eq!(throughput.to_string(), "500B/s");
+    }
+
+    #[test]
+    fn zero_half_life() {
+        let start = Instant::now();
+        let mut throughput = Throughput::new(Duration::ZERO);
+        throughput.record(0, start);
+        throughput.record(1_000, start + Duration::from_secs(1));
+        throughput.record(3_000, start + Duration::from_secs(2));
+        assert_eq!(throughput.rate(), 3_000);
+    }
+
+    #[test]
+    fn rate_meter() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut meter = RateMeter::new(Duration::from_secs(3));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (0, 0, 0));
+
+        // In progress second is not reported.
+        meter.record(1_000, at(0));
+        meter.record(1_000, at(500));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (0, 0, 0));
+
+        meter.record(4_000, at(1_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (2_000, 2_000, 2_000));
+        meter.record(0, at(2_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (4_000, 3_000, 4_000));
+
+        // Late sample within the window.
+        meter.record(3_000, at(1_999));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (7_000, 4_500, 7_000));
+
+        // Oldest bucket leaves the window.
+        meter.record(0, at(4_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (0, 2_333, 7_000));
+        meter.record(0, at(5_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (0, 0, 0));
+
+        // Gap larger than the window.
+        meter.record(1_000, at(60_000));
+        meter.record(0, at(61_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (1_000, 333, 1_000));
+
+        // Samples older than the window are ignored.
+        meter.record(1_000, at(0));
+        meter.record(1_000, at(55_000));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (1_000, 333, 1_000));
+        assert_eq!(meter.to_string(), "1kB/s (sustained 333B/s, peak 1kB/s)");
+    }
+
+    #[test]
+    fn unbounded_rate_meter() {
+        let start = Instant::now();
+        let mut meter = RateMeter::new(Duration::MAX);
+        meter.record(1_000, start);
+        meter.record(3_000, start + Duration::from_secs(1));
+        meter.record(0, start + Duration::from_secs(2));
+        assert_eq!((meter.current(), meter.sustained(), meter.peak()), (3_000, 2_000, 3_000));
+
+        // Idle seconds are not stored.
+        let year = Duration::from_secs(365 * 24 * 60 * 60);
+        meter.record(1_000, start + year);
+        meter.record(0, start + year + Duration::from_secs(1));
+        assert_eq!(meter.buckets.len(), 3);
+        assert_eq!((meter.current(), meter.peak()), (1_000, 3_000));
+    }
+
+    #[test]
+    fn atomic_byte_counter() {
+        let counter = AtomicByteCounter::new();
+        assert_eq!(counter.snapshot(), 0);
+        assert_eq!(counter.to_string(), "0B");
+
+        counter.add(1_000);
+        counter.add(234);
+        assert_eq!(counter.snapshot(), 1_234);
+        assert_eq!(counter.to_string(), "1.23kB");
+
+        assert_eq!(counter.take(), 1_234);
+        assert_eq!(counter.snapshot(), 0);
+
+        counter.add(u64::MAX);
+        counter.add(2);
+        assert_eq!(counter.snapshot(), 1);
+    }
+}
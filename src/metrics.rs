@@ -0,0 +1,223 @@
+//! [`metrics`](https://docs.rs/metrics) support, to emit values with the
+//! matching [`metrics::Unit`].
+//!
+//! Units implementing [`MetricsUnit`] describe metrics using
+//! [`describe_counter`], [`describe_gauge`] and [`describe_histogram`], and
+//! their values are recorded using [`increment_counter`], [`set_gauge`],
+//! [`set_limit`] and [`record_histogram`].
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{
+//!     limit::ByteLimit,
+//!     metrics::{describe_gauge, set_limit},
+//!     unit::Byte,
+//! };
+//!
+//! describe_gauge::<Byte>("quota", "Storage quota of the tenant");
+//! let quota = ByteLimit::parse("5GB").unwrap();
+//! set_limit(&metrics::gauge!("quota", "tenant" => "acme"), quota);
+//! ```
+
+use metrics::{Counter, Gauge, Histogram, KeyName, SharedString};
+
+use crate::{limit::Limit, unit::Unit};
+
+/// A [`Unit`] matching a [`metrics::Unit`].
+///
+/// # Examples
+/// ```
+/// use bity::{metrics::MetricsUnit, unit::Byteps};
+///
+/// assert_eq!(Byteps::UNIT, metrics::Unit::BitsPerSecond);
+/// assert_eq!(Byteps::to_metric(1_000), 8_000);
+/// ```
+pub trait MetricsUnit: Unit {
+    /// The unit of the emitted values.
+    const UNIT: metrics::Unit;
+
+    /// Convert a value of the unit into an emitted value.
+    fn to_metric(value: u64) -> u64 {
+        value
+    }
+}
+
+impl MetricsUnit for crate::unit::Si {
+    const UNIT: metrics::Unit = metrics::Unit::Count;
+}
+
+#[cfg(feature = "byte")]
+impl MetricsUnit for crate::unit::Byte {
+    const UNIT: metrics::Unit = metrics::Unit::Bytes;
+}
+
+#[cfg(feature = "packet")]
+impl MetricsUnit for crate::unit::Packet {
+    const UNIT: metrics::Unit = metrics::Unit::Count;
+}
+
+#[cfg(feature = "bps")]
+impl MetricsUnit for crate::unit::Bps {
+    const UNIT: metrics::Unit = metrics::Unit::BitsPerSecond;
+}
+
+/// There is no bytes per second [`metrics::Unit`], values are emitted in bits
+/// per second.
+#[cfg(feature = "byteps")]
+impl MetricsUnit for crate::unit::Byteps {
+    const UNIT: metrics::Unit = metrics::Unit::BitsPerSecond;
+
+    fn to_metric(value: u64) -> u64 {
+        value.saturating_mul(8)
+    }
+}
+
+#[cfg(feature = "pps")]
+impl MetricsUnit for crate::unit::Pps {
+    const UNIT: metrics::Unit = metrics::Unit::CountPerSecond;
+}
+
+#[cfg(feature = "hz")]
+impl MetricsUnit for crate::unit::Hz {
+    const UNIT: metrics::Unit = metrics::Unit::CountPerSecond;
+}
+
+#[cfg(feature = "ops")]
+impl MetricsUnit for crate::unit::Ops {
+    const UNIT: metrics::Unit = metrics::Unit::CountPerSecond;
+}
+
+/// Describe a counter of values of the unit `U`.
+pub fn describe_counter<U: MetricsUnit>(
+    name: impl Into<KeyName>,
+    description: impl Into<SharedString>,
+) {
+    metrics::describe_counter!(name, U::UNIT, description);
+}
+
+/// Describe a gauge of values of the unit `U`.
+pub fn describe_gauge<U: MetricsUnit>(
+    name: impl Into<KeyName>,
+    description: impl Into<SharedString>,
+) {
+    metrics::describe_gauge!(name, U::UNIT, description);
+}
+
+/// Describe a histogram of values of the unit `U`.
+pub fn describe_histogram<U: MetricsUnit>(
+    name: impl Into<KeyName>,
+    description: impl Into<SharedString>,
+) {
+    metrics::describe_histogram!(name, U::UNIT, description);
+}
+
+/// Increment a counter by a value of the unit `U`.
+///
+/// # Examples
+/// ```
+/// use bity::{metrics::increment_counter, unit::Byte};
+///
+/// increment_counter::<Byte>(&metrics::counter!("uploaded"), 1_500);
+/// ```
+pub fn increment_counter<U: MetricsUnit>(counter: &Counter, value: u64) {
+    counter.increment(U::to_metric(value));
+}
+
+/// Set a gauge to a value of the unit `U`.
+pub fn set_gauge<U: MetricsUnit>(gauge: &Gauge, value: u64) {
+    gauge.set(U::to_metric(value) as f64);
+}
+
+/// Set a gauge to a limit, unlimited being infinite.
+pub fn set_limit<U: MetricsUnit>(gauge: &Gauge, limit: Limit<U>) {
+    match limit.value() {
+        Some(value) => set_gauge::<U>(gauge, value),
+        None => gauge.set(f64::INFINITY),
+    }
+}
+
+/// Record a value of the unit `U` into a histogram.
+pub fn record_histogram<U: MetricsUnit>(histogram: &Histogram, value: u64) {
+    histogram.record(U::to_metric(value) as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    };
+
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
+    use crate::{limit::Limit, unit::Si};
+
+    #[derive(Default)]
+    struct TestRecorder {
+        descriptions: Mutex<Vec<(String, Option<Unit>)>>,
+        value: Arc<AtomicU64>,
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, key: KeyName, unit: Option<Unit>, _: SharedString) {
+            let key = key.as_str().to_owned();
+            self.descriptions.lock().unwrap().push((key, unit));
+        }
+
+        fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, _: SharedString) {
+            self.describe_counter(key, unit, SharedString::const_str(""));
+        }
+
+        fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, _: SharedString) {
+            self.describe_counter(key, unit, SharedString::const_str(""));
+        }
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.value.clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.value.clone())
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn record() {
+        let recorder = TestRecorder::default();
+        let gauge = |f: &dyn Fn()| {
+            metrics::with_local_recorder(&recorder, f);
+            f64::from_bits(recorder.value.load(Ordering::Relaxed))
+        };
+
+        assert_eq!(
+            gauge(&|| super::set_limit(&metrics::gauge!("quota"), Limit::<Si>::new(1_500))),
+            1_500.0
+        );
+        assert_eq!(
+            gauge(&|| super::set_limit(&metrics::gauge!("quota"), Limit::<Si>::unlimited())),
+            f64::INFINITY
+        );
+        #[cfg(feature = "byteps")]
+        assert_eq!(
+            gauge(&|| super::set_gauge::<crate::unit::Byteps>(&metrics::gauge!("rate"), 1_000)),
+            8_000.0
+        );
+
+        metrics::with_local_recorder(&recorder, || {
+            super::describe_gauge::<Si>("quota", "Quota");
+            #[cfg(feature = "byte")]
+            super::describe_counter::<crate::unit::Byte>("uploaded", "Uploaded bytes");
+        });
+        let descriptions = recorder.descriptions.lock().unwrap();
+        assert_eq!(descriptions[0], ("quota".to_owned(), Some(Unit::Count)));
+        #[cfg(feature = "byte")]
+        assert_eq!(descriptions[1], ("uploaded".to_owned(), Some(Unit::Bytes)));
+    }
+}
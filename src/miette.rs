@@ -0,0 +1,208 @@
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+use crate::{Error, OwnedError};
+
+impl Error<'_> {
+    /// Attach the input that failed to parse, to render the error as a
+    /// [`miette`] diagnostic pointing at the invalid part of the input.
+    ///
+    /// `input` must be the string given to the parsing function that returned
+    /// the error.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::byte;
+    ///
+    /// fn quota(input: &str) -> miette::Result<u64> {
+    ///     byte::parse(input).map_err(|err| err.with_input(input).with_units(byte::UNITS).into())
+    /// }
+    ///
+    /// let report = quota("12 kx").unwrap_err();
+    /// assert_eq!(report.to_string(), r#"invalid unit "kx""#);
+    /// assert_eq!(
+    ///     report.help().unwrap().to_string(),
+    ///     r#"did you mean "kB"? valid units are B"#
+    /// );
+    /// ```
+    pub fn with_input(self, input: &str) -> InputError {
+        InputError {
+            span: self.span(input),
+            error: self.into_owned(),
+            input: input.to_owned(),
+            units: &[],
+        }
+    }
+}
+
+/// An error with the input it comes from, implementing
+/// [`miette::Diagnostic`], created by [`Error::with_input`].
+#[derive(Debug, Clone)]
+pub struct InputError {
+    error: OwnedError,
+    input: String,
+    span: Option<Range<usize>>,
+    units: &'static [&'static str],
+}
+
+impl InputError {
+    /// List the valid units (like [`byte::UNITS`](crate::byte::UNITS)) in the
+    /// help of invalid unit errors, along with the closest one (see
+    /// [`Error::suggestion`]).
+    pub fn with_units(mut self, units: &'static [&'static str]) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Underlying error.
+    pub fn error(&self) -> &OwnedError {
+        &self.error
+    }
+
+    /// Input that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Byte range of the invalid part of the input.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl StdError for InputError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+impl Diagnostic for InputError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.code()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        match self.error {
+            OwnedError::InvalidUnit(_) if !self.units.is_empty() => {
+                let units = self.units.join(", ");
+                Some(Box::new(match self.error.suggestion(self.units) {
+                    Some(suggestion) => {
+                        format!(r#"did you mean "{suggestion}"? valid units are {units}"#)
+                    }
+                    None => format!("valid units are {units}"),
+                }))
+            }
+            _ => self.error.help(),
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.input)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let label = match self.error {
+            OwnedError::NotAscii => "not ASCII",
+            OwnedError::InvalidUnit(_) => "invalid unit",
+            OwnedError::MultipleUnits(_) => "multiple units",
+            OwnedError::EmptyInput => "missing number",
+            OwnedError::NegativeValue(_) => "negative number",
+            OwnedError::FractionTooPrecise(_) => "too many digits",
+            OwnedError::TrailingGarbage(_) => "unexpected characters",
+            OwnedError::ParseIntError(..) => "invalid number",
+        };
+        let span = self.span.clone()?;
+        Some(Box::new(std::iter::once(LabeledSpan::new_primary_with_span(
+            Some(label.to_owned()),
+            span,
+        ))))
+    }
+}
+
+impl Diagnostic for OwnedError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(format!("bity::{}", self.kind())))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        match self {
+            OwnedError::NotAscii => Some(Box::new("only ASCII characters are allowed")),
+            OwnedError::InvalidUnit(_) => None,
+            OwnedError::MultipleUnits(_) => Some(Box::new("only one unit is allowed, like 12kB")),
+            OwnedError::EmptyInput => Some(Box::new("a number is required, like 12 or 1.5")),
+            OwnedError::NegativeValue(_) => Some(Box::new("only positive numbers are allowed")),
+            OwnedError::FractionTooPrecise(_) => {
+                Some(Box::new("the fraction can have at most 19 digits"))
+            }
+            OwnedError::ParseIntError(..) | OwnedError::TrailingGarbage(_) => {
+                Some(Box::new("expected a positive number, like 12 or 1.5"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miette::{Diagnostic, NarratableReportHandler};
+
+    #[test]
+    fn diagnostic() {
+        let input = "1.5 kbit/s";
+        let err = crate::bps::parse(input)
+            .unwrap_err()
+            .with_input(input)
+            .with_units(crate::bps::UNITS);
+        assert_eq!(err.input(), input);
+        assert_eq!(err.span(), Some(4..8));
+        assert_eq!(err.code().unwrap().to_string(), "bity::invalid_unit");
+        assert_eq!(err.help().unwrap().to_string(), "valid units are b/s, bps, B/s, Bps");
+        let labels = err.labels().unwrap().collect::<Vec<_>>();
+        assert_eq!(labels.len(), 1);
+        assert_eq!((labels[0].offset(), labels[0].len()), (4, 4));
+        assert_eq!(labels[0].label(), Some("invalid unit"));
+
+        let mut report = String::new();
+        NarratableReportHandler::new()
+            .render_report(&mut report, &err)
+            .unwrap();
+        assert!(report.contains(r#"invalid unit "kbit""#));
+        assert!(report.contains("valid units are b/s, bps, B/s, Bps"));
+
+        let err = crate::byte::parse("1.2.3kB")
+            .unwrap_err()
+            .with_input("1.2.3kB");
+        assert_eq!(err.code().unwrap().to_string(), "bity::trailing_garbage");
+        assert_eq!(err.labels().unwrap().next().unwrap().label(), Some("unexpected characters"));
+        assert_eq!(err.span(), Some(3..5));
+        assert!(err.help().is_some());
+
+        let err = crate::byte::parse("").unwrap_err().with_input("");
+        assert_eq!(err.code().unwrap().to_string(), "bity::empty_input");
+        assert!(err.labels().is_none());
+        assert_eq!(err.help().unwrap().to_string(), "a number is required, like 12 or 1.5");
+
+        let err = crate::bps::parse("12GBps/s")
+            .unwrap_err()
+            .with_input("12GBps/s")
+            .with_units(crate::bps::UNITS);
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            r#"did you mean "GBps"? valid units are b/s, bps, B/s, Bps"#
+        );
+
+        // Without units, invalid unit errors have no help.
+        let err = crate::byte::parse("12x").unwrap_err().with_input("12x");
+        assert!(err.help().is_none());
+    }
+}
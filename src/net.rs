@@ -0,0 +1,252 @@
+//! Networking capacity helpers.
+//!
+//! Link rates are expressed in bits per second (see [`bps`](crate::bps)), sizes
+//! in bytes (see [`byte`](crate::byte)) and packet-rates in packets per second
+//! (see [`pps`]).
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{bps, byte, net, net::Header, pps};
+//!
+//! let link = bps::parse("1Gb/s").unwrap();
+//! let frame = byte::parse("64B").unwrap();
+//! assert_eq!(pps::format(net::max_pps(link, frame)), "1.48Mp/s");
+//! assert_eq!(net::format_line_rate(link, frame), "1.48Mp/s (95.23MB/s)");
+//!
+//! let stack = &[Header::Ethernet, Header::Ipv4, Header::Tcp];
+//! let goodput = net::goodput(link, byte::parse("1460B").unwrap(), stack);
+//! assert_eq!(bps::format(goodput), "949.28Mb/s");
+//! ```
+
+use alloc::{format, string::String};
+
+use crate::{byteps, pps};
+
+/// Ethernet preamble size, in bytes.
+pub const PREAMBLE: u64 = 7;
+/// Ethernet start frame delimiter size, in bytes.
+pub const SFD: u64 = 1;
+/// Ethernet minimum inter-frame gap, in bytes.
+pub const IFG: u64 = 12;
+/// Ethernet frame check sequence size, in bytes.
+pub const FCS: u64 = 4;
+/// Ethernet layer 1 overhead added to each frame on the wire, in bytes.
+pub const L1_OVERHEAD: u64 = PREAMBLE + SFD + IFG;
+/// Ethernet minimum frame size (including FCS), in bytes.
+pub const MIN_FRAME_SIZE: u64 = 64;
+
+/// Compute the maximum Ethernet frame-rate of a link for a given frame size.
+///
+/// The frame size must include the FCS (`64B` for the smallest frames).
+/// Smaller frames are padded to [`MIN_FRAME_SIZE`]. The [`L1_OVERHEAD`]
+/// (preamble, SFD and inter-frame gap) is added to each frame.
+///
+/// # Examples
+/// ```
+/// use bity::net::max_pps;
+///
+/// assert_eq!(max_pps(1_000_000_000, 64), 1_488_095);
+/// assert_eq!(max_pps(10_000_000_000, 1_518), 812_743);
+/// assert_eq!(max_pps(1_000_000_000, 12), 1_488_095); // Padded.
+/// ```
+pub fn max_pps(link_rate: u64, frame_size: u64) -> u64 {
+    link_rate
+        / frame_size
+            .max(MIN_FRAME_SIZE)
+            .saturating_add(L1_OVERHEAD)
+            .saturating_mul(8)
+}
+
+/// Compute the maximum Ethernet throughput (in bytes per second, layer 2
+/// frames including FCS) of a link for a given frame size.
+///
+/// Refer to [`max_pps`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::net::max_throughput;
+///
+/// assert_eq!(max_throughput(1_000_000_000, 64), 95_238_080);
+/// assert_eq!(max_throughput(1_000_000_000, 1_518), 123_373_932);
+/// ```
+pub fn max_throughput(link_rate: u64, frame_size: u64) -> u64 {
+    max_pps(link_rate, frame_size) * frame_size.max(MIN_FRAME_SIZE)
+}
+
+/// Format the maximum Ethernet frame-rate and throughput of a link for a given
+/// frame size.
+///
+/// Refer to [`max_pps`] and [`max_throughput`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::net::format_line_rate;
+///
+/// assert_eq!(format_line_rate(1_000_000_000, 64), "1.48Mp/s (95.23MB/s)");
+/// assert_eq!(format_line_rate(10_000_000_000, 1_518), "812.74kp/s (1.23GB/s)");
+/// ```
+pub fn format_line_rate(link_rate: u64, frame_size: u64) -> String {
+    format!(
+        "{} ({})",
+        pps::format(max_pps(link_rate, frame_size)),
+        byteps::format(max_throughput(link_rate, frame_size))
+    )
+}
+
+/// Protocol header that can be part of an encapsulation stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Header {
+    /// Ethernet II frame: `14` bytes header, plus the [`FCS`] and the
+    /// [`L1_OVERHEAD`] on the wire. Frames are also padded to
+    /// [`MIN_FRAME_SIZE`].
+    Ethernet,
+    /// 802.1Q VLAN tag: `4` bytes.
+    Vlan,
+    /// IPv4 header without options: `20` bytes.
+    Ipv4,
+    /// IPv6 header without extensions: `40` bytes.
+    Ipv6,
+    /// TCP header without options: `20` bytes.
+    Tcp,
+    /// UDP header: `8` bytes.
+    Udp,
+}
+
+impl Header {
+    /// Size of the header, in bytes.
+    ///
+    /// For [`Header::Ethernet`], the [`FCS`] and [`L1_OVERHEAD`] are not
+    /// included.
+    pub const fn size(self) -> u64 {
+        match self {
+            Header::Ethernet => 14,
+            Header::Vlan => 4,
+            Header::Ipv4 => 20,
+            Header::Ipv6 => 40,
+            Header::Tcp => 20,
+            Header::Udp => 8,
+        }
+    }
+}
+
+/// Size of a packet carrying `payload_size` bytes once encapsulated by `stack`.
+fn wire_size(payload_size: u64, stack: &[Header]) -> u64 {
+    let size = stack
+        .iter()
+        .fold(payload_size, |size, header| size.saturating_add(header.size()));
+    if stack.contains(&Header::Ethernet) {
+        size.saturating_add(FCS)
+            .max(MIN_FRAME_SIZE)
+            .saturating_add(L1_OVERHEAD)
+    } else {
+        size
+    }
+}
+
+/// Compute the application-layer goodput (in bits per second) of a wire rate
+/// (in bits per second), given the payload size of each packet (in bytes) and
+/// its encapsulation stack.
+///
+/// # Examples
+/// ```
+/// use bity::net::{goodput, Header};
+///
+/// let stack = &[Header::Ethernet, Header::Ipv4, Header::Tcp];
+/// assert_eq!(goodput(1_000_000_000, 1_460, stack), 949_284_785);
+/// assert_eq!(goodput(1_000_000_000, 18, &[Header::Ethernet, Header::Ipv4, Header::Udp]), 214_285_714);
+/// ```
+pub fn goodput(wire_rate: u64, payload_size: u64, stack: &[Header]) -> u64 {
+    (u128::from(wire_rate) * u128::from(payload_size)
+        / u128::from(wire_size(payload_size, stack).max(1))) as u64
+}
+
+/// Compute the wire rate (in bits per second) needed to reach an
+/// application-layer goodput (in bits per second), given the payload size of
+/// each packet (in bytes) and its encapsulation stack.
+///
+/// The result is rounded up and saturates to `u64::MAX` instead of
+/// overflowing. A zero payload size gives `u64::MAX`, except for a zero
+/// goodput which always gives a zero wire rate.
+///
+/// # Examples
+/// ```
+/// use bity::{bps, net::{wire_rate, Header}};
+///
+/// let stack = &[Header::Ethernet, Header::Vlan, Header::Ipv6, Header::Udp];
+/// assert_eq!(bps::format(wire_rate(bps::parse("1Gb/s").unwrap(), 1_200, stack)), "1.07Gb/s");
+/// ```
+pub fn wire_rate(goodput: u64, payload_size: u64, stack: &[Header]) -> u64 {
+    if payload_size == 0 {
+        return if goodput == 0 { 0 } else { u64::MAX };
+    }
+    u64::try_from(
+        (u128::from(goodput) * u128::from(wire_size(payload_size, stack)))
+            .div_ceil(u128::from(payload_size)),
+    )
+    .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn max_pps() {
+        assert_eq!(super::max_pps(10_000_000, 64), 14_880);
+        assert_eq!(super::max_pps(100_000_000, 64), 148_809);
+        assert_eq!(super::max_pps(1_000_000_000, 64), 1_488_095);
+        assert_eq!(super::max_pps(10_000_000_000, 64), 14_880_952);
+        assert_eq!(super::max_pps(100_000_000_000, 64), 148_809_523);
+        assert_eq!(super::max_pps(1_000_000_000, 1_518), 81_274);
+        assert_eq!(super::max_pps(1_000_000_000, 0), 1_488_095);
+        assert_eq!(super::max_pps(0, 64), 0);
+        assert_eq!(super::max_pps(1_000_000_000, u64::MAX), 0);
+    }
+
+    #[test]
+    fn max_throughput() {
+        assert_eq!(super::max_throughput(1_000_000_000, 64), 95_238_080);
+        assert_eq!(super::max_throughput(1_000_000_000, 0), 95_238_080);
+        assert_eq!(super::max_throughput(1_000_000_000, 1_518), 123_373_932);
+    }
+
+    #[test]
+    fn wire_size() {
+        use super::Header;
+
+        assert_eq!(super::wire_size(1_460, &[]), 1_460);
+        assert_eq!(super::wire_size(1_460, &[Header::Ipv4, Header::Tcp]), 1_500);
+        assert_eq!(super::wire_size(1_460, &[Header::Ethernet, Header::Ipv4, Header::Tcp]), 1_538);
+        assert_eq!(super::wire_size(1_440, &[Header::Ethernet, Header::Ipv6, Header::Tcp]), 1_538);
+        assert_eq!(
+            super::wire_size(1_472, &[Header::Ethernet, Header::Vlan, Header::Ipv4, Header::Udp]),
+            1_542
+        );
+        // Padded.
+        assert_eq!(super::wire_size(0, &[Header::Ethernet, Header::Ipv4, Header::Udp]), 84);
+    }
+
+    #[test]
+    fn goodput() {
+        use super::Header;
+
+        let stack = &[Header::Ethernet, Header::Ipv4, Header::Tcp];
+        assert_eq!(super::goodput(1_000_000_000, 1_460, stack), 949_284_785);
+        assert_eq!(super::goodput(1_000_000_000, 0, stack), 0);
+        assert_eq!(super::goodput(1_000_000_000, 1_000, &[]), 1_000_000_000);
+        assert_eq!(super::goodput(u64::MAX, 1_000, &[]), u64::MAX);
+        assert_eq!(super::goodput(1_000_000_000, 0, &[]), 0);
+    }
+
+    #[test]
+    fn wire_rate() {
+        use super::Header;
+
+        let stack = &[Header::Ethernet, Header::Ipv4, Header::Tcp];
+        assert_eq!(super::wire_rate(949_284_785, 1_460, stack), 1_000_000_000); // Round up.
+        assert_eq!(super::wire_rate(949_284_786, 1_460, stack), 1_000_000_001);
+        assert_eq!(super::wire_rate(1_000, 1_000, &[]), 1_000);
+        assert_eq!(super::wire_rate(u64::MAX, 1_460, stack), u64::MAX);
+        assert_eq!(super::wire_rate(0, 0, stack), 0);
+        assert_eq!(super::wire_rate(1, 0, stack), u64::MAX);
+    }
+}
@@ -0,0 +1,409 @@
+//! SI prefixed operation-rate (operations or requests per second) parsing and
+//! formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::ops::{format, parse};
+//!
+//! assert_eq!(parse("1.2kops").unwrap(), 1_200);
+//! assert_eq!(parse("500req/s").unwrap(), 500);
+//! assert_eq!(parse("12").unwrap(), 12);
+//!
+//! assert_eq!(format(1_234), "1.23kops");
+//! assert_eq!(format(123_456), "123.45kops");
+//! assert_eq!(format(12_345_678), "12.34Mops");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::ops::serialize")]`, `#[serde(deserialize_with =
+//! "bity::ops::deserialize")]` and `#[serde(with = "bity::ops")]` attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::ops")]
+//!     rate_limit: u64,
+//!     #[serde(with = "bity::ops")]
+//!     burst: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         rate-limit = "500req/s"
+//!         burst = 1_000
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         rate_limit: 500,
+//!         burst: 1_000,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         rate_limit: 500,
+//!         burst: 1_000,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         rate-limit = "500ops"
+//!         burst = "1kops"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::{format, string::String};
+use core::fmt;
+
+use crate::{
+    error::Error,
+    si::{self, Prefix},
+    IntoDuration, Period,
+};
+
+/// Suffixes accepted after the SI prefix, tried in order.
+const SUFFIXES: &[&str] = &["ops", "op/s", "req/s", "reqps", "rps", "/s"];
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?\s*(?:ops|op/s|req/s|reqps|rps|/s)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["ops", "op/s", "req/s", "reqps", "rps"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "ops".len();
+
+/// Parse an operation-rate SI prefixed string into a number.
+///
+/// The `ops`, `op/s`, `req/s`, `reqps` and `rps` suffixes (or a bare `/s`) are
+/// accepted and stripped before calling [`si::parse`], refer to it to learn the
+/// rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::ops::parse;
+///
+/// assert_eq!(parse("12ops").unwrap(), 12);
+/// assert_eq!(parse("12.345kop/s").unwrap(), 12_345);
+/// assert_eq!(parse("500req/s").unwrap(), 500);
+/// assert_eq!(parse("1.5krps").unwrap(), 1_500);
+/// assert_eq!(parse("12k/s").unwrap(), 12_000);
+/// assert_eq!(parse("12").unwrap(), 12);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    let input = input.trim();
+    si::parse(
+        SUFFIXES
+            .iter()
+            .find_map(|suffix| input.strip_suffix(suffix))
+            .unwrap_or(input),
+    )
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::ops::parse_const;
+///
+/// assert_eq!(parse_const("1.2kreq/s"), Some(1_200));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    let input = input.trim_ascii();
+    let mut i = 0;
+    while i < SUFFIXES.len() {
+        if let Some(stripped) = crate::strip_suffix_const(input, SUFFIXES[i]) {
+            return si::parse_const(stripped);
+        }
+        i += 1;
+    }
+    si::parse_const(input)
+}
+
+/// Format an integer into an operation-rate SI prefixed string.
+///
+/// This is equivalent to colling `format!("{}ops", si::format(input))`.
+///
+/// Refer to [`si::format`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::ops::format;
+///
+/// assert_eq!(format(12), "12ops");
+/// assert_eq!(format(1_234), "1.23kops");
+/// assert_eq!(format(12_000), "12kops");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (operation rate) using a given
+/// SI prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}ops",
+/// si::format_with_prefix(input, prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{ops::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000, Prefix::Kilo), "12300kops");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("ops");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::ops::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 12_300).unwrap();
+/// assert_eq!(output, "12.3kops");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("ops")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::ops::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 12_300).unwrap();
+/// assert_eq!(&buffer[..len], b"12.3kops");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Compute the operation-rate (per-second) of a count of operations performed
+/// over a given duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. A zero duration is treated as an infinite rate,
+/// except for a zero count which always gives a zero rate.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::ops;
+///
+/// let rate = ops::from_count(1_000_000, Duration::from_secs(80));
+/// assert_eq!(ops::format(rate), "12.5kops");
+/// ```
+pub fn from_count(count: u64, duration: impl IntoDuration) -> u64 {
+    crate::rate::from_volume(count, duration.into_duration())
+}
+
+/// Compute the count of operations performed at a given operation-rate over a
+/// given duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. Use [`checked_count_over`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{ops, si};
+///
+/// let count = ops::count_over(ops::parse("500req/s").unwrap(), Duration::from_secs(3_600));
+/// assert_eq!(si::format(count), "1.8M");
+/// assert_eq!(ops::count_over(u64::MAX, Duration::from_secs(2)), u64::MAX);
+/// ```
+pub fn count_over(rate: u64, duration: impl IntoDuration) -> u64 {
+    checked_count_over(rate, duration).unwrap_or(u64::MAX)
+}
+
+/// Like [`count_over`] but returns `None` if the count doesn't fit in an `u64`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::ops::checked_count_over;
+///
+/// assert_eq!(checked_count_over(1_000, Duration::from_millis(1_500)), Some(1_500));
+/// assert_eq!(checked_count_over(u64::MAX, Duration::from_secs(2)), None);
+/// ```
+pub fn checked_count_over(rate: u64, duration: impl IntoDuration) -> Option<u64> {
+    crate::rate::checked_volume_over(rate, duration.into_duration())
+}
+
+/// Compute the count of operations performed over a whole [`Period`] at a
+/// given sustained operation-rate.
+///
+/// This is equivalent to calling `count_over(rate, period.duration())`, refer
+/// to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{ops, si, Period};
+///
+/// assert_eq!(si::format(ops::count_per(500, Period::Day)), "43.2M");
+/// ```
+pub fn count_per(rate: u64, period: Period) -> u64 {
+    count_over(rate, period.duration())
+}
+
+/// Compute the sustained operation-rate (per-second) needed to perform a count
+/// of operations over a whole [`Period`].
+///
+/// This is equivalent to calling `from_count(count, period.duration())`, refer
+/// to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{ops, Period};
+///
+/// assert_eq!(ops::from_count_per(36_000, Period::Hour), 10);
+/// ```
+pub fn from_count_per(count: u64, period: Period) -> u64 {
+    from_count(count, period.duration())
+}
+
+/// Format a per-second operation-rate into a SI prefixed string using a
+/// different time base.
+///
+/// This is equivalent to calling `format!("{}op/{}", si::format(count_per(input,
+/// period)), period.symbol())`.
+///
+/// # Examples
+/// ```
+/// use bity::{ops::format_per, Period};
+///
+/// assert_eq!(format_per(500, Period::Second), "500op/s");
+/// assert_eq!(format_per(500, Period::Hour), "1.8Mop/h");
+/// ```
+pub fn format_per(input: u64, period: Period) -> String {
+    format!("{}op/{}", si::format(count_per(input, period)), period.symbol())
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"an operation rate like "1.2kops", "500req/s" or 1500"#,
+    example: 1_200,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed operation-rate string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::ops::serialize")]` and `#[serde(with = "bity::ops")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::ops::serialize")]
+    ///     rate_limit: u64,
+    ///     #[serde(with = "bity::ops")]
+    ///     burst: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         rate_limit: 500,
+    ///         burst: 1_000,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         rate-limit = "500ops"
+    ///         burst = "1kops"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed operation-rate string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::ops::deserialize")]` and `#[serde(with = "bity::ops")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::ops::deserialize")]
+    ///     rate_limit: u64,
+    ///     #[serde(with = "bity::ops")]
+    ///     burst: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             rate-limit = "500req/s"
+    ///             burst = 1_000
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         rate_limit: 500,
+    ///         burst: 1_000,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12ops").unwrap(), 12);
+        assert_eq!(super::parse("1.2kops").unwrap(), 1_200);
+        assert_eq!(super::parse("1.2 kop/s").unwrap(), 1_200);
+        assert_eq!(super::parse("500req/s").unwrap(), 500);
+        assert_eq!(super::parse("500reqps").unwrap(), 500);
+        assert_eq!(super::parse(" 2Mrps ").unwrap(), 2_000_000);
+        assert_eq!(super::parse("12k/s").unwrap(), 12_000);
+        assert_eq!(super::parse("12k").unwrap(), 12_000);
+        assert_eq!(super::parse("12").unwrap(), 12);
+
+        assert!(matches!(super::parse("12op"), Err(Error::InvalidUnit("op"))));
+        assert!(matches!(super::parse("12kreq"), Err(Error::InvalidUnit("kreq"))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123ops");
+        assert_eq!(super::format(1_234), "1.23kops");
+        assert_eq!(super::format(12_000), "12kops");
+    }
+}
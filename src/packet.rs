@@ -21,6 +21,7 @@
 //! attributes.
 //!
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use indoc::indoc;
 //! use serde::{Deserialize, Serialize};
 //!
@@ -56,9 +57,28 @@
 //!         remaining = "180p"
 //!     "#}
 //! );
+//! # }
 //! ```
 
-use crate::{si, Error};
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    si::{self, Prefix},
+    Error,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTPeE]?p?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["p"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "p".len();
 
 /// Parse a packet count SI prefixed string into a number.
 ///
@@ -80,6 +100,20 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
     si::parse_with_additional_units(input, &[("p", 1)])
 }
 
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::packet::parse_const;
+///
+/// assert_eq!(parse_const("1.5kp"), Some(1_500));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    si::parse_const_with_additional_units(input, &[("p", 1)])
+}
+
 /// Format an integer into a packet count SI prefixed string.
 ///
 /// This is equivalent to colling `format!("{}p", si::format(input))`.
@@ -95,11 +129,68 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// assert_eq!(format(12_000), "12kp");
 /// ```
 pub fn format(input: u64) -> String {
-    format!("{}p", si::format(input))
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (packet oriented) using a given
+/// SI prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}p", si::format_with_prefix(input,
+/// prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{packet::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300Mp");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push('p');
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::packet::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_234).unwrap();
+/// assert_eq!(output, "1.23kp");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("p")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::packet::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_234).unwrap();
+/// assert_eq!(&buffer[..len], b"1.23kp");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
 }
 
 #[cfg(feature = "serde")]
 crate::impl_serde!(
+    expecting: r#"a packet count like "1.5kp", "12Mp" or 1500"#,
+    example: 1_500,
     ser:
     /// Serialize a given `u64` into a SI prefixed packet count string.
     ///
@@ -162,6 +253,9 @@ crate::impl_serde!(
     /// ```
 );
 
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,573 @@
+//! Percent and ppm (parts-per-million) parsing and formatting.
+//!
+//! Values are represented as fixed-point integers in parts-per-million:
+//! `1_000_000` is `100%`, `125_000` is `12.5%` and `300` is `300ppm`. Values
+//! above `100%` are allowed.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::percent::{format, format_ppm, parse};
+//!
+//! assert_eq!(parse("12.5%").unwrap(), 125_000);
+//! assert_eq!(parse("300ppm").unwrap(), 300);
+//! assert_eq!(parse("0.5").unwrap(), 500_000);
+//!
+//! assert_eq!(format(125_000), "12.5%");
+//! assert_eq!(format(300), "0.03%");
+//! assert_eq!(format_ppm(300), "300ppm");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::percent::serialize")]`, `#[serde(deserialize_with =
+//! "bity::percent::deserialize")]` and `#[serde(with = "bity::percent")]`
+//! attributes.
+//!
+//! Bare numbers (integers and floats) are read like strings without unit,
+//! meaning that they are interpreted as ratios: `0.5` and `"0.5"` are both
+//! `50%`, and `1` is `100%`. This applies to all the submodules but `as_int`,
+//! which serializes ppm integers and reads integers back as ppm: `1` is
+//! `1ppm` there.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::percent")]
+//!     max_loss: u64,
+//!     #[serde(with = "bity::percent")]
+//!     target_utilization: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         max-loss = "300ppm"
+//!         target-utilization = "0.8"
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         max_loss: 300,
+//!         target_utilization: 800_000,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         max_loss: 300,
+//!         target_utilization: 800_000,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         max-loss = "0.03%"
+//!         target-utilization = "80%"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::{format, string::String};
+use core::fmt;
+
+use crate::{
+    error::{check_number, is_multiple_units, Error},
+    si::Digits,
+};
+
+/// `100%`, in ppm.
+pub const ONE: u64 = 1_000_000;
+/// `1%`, in ppm.
+pub const PERCENT: u64 = 10_000;
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:%|ppm)?\s*$";
+
+/// Units accepted after the number, listed in error reports.
+pub const UNITS: &[&str] = &["%", "ppm"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_ppm`], reached by `format_ppm(u64::MAX)`.
+pub const MAX_FORMATTED_LEN: usize = 23;
+
+/// Parse a percent, ppm or ratio string into ppm.
+///
+/// The number can be followed by `%` or `ppm`. A number without unit is
+/// treated as a ratio (`0.5` is `50%`). Fraction digits that are too precise
+/// to be represented are ignored. Whitespaces are trimmed around the number
+/// and the unit.
+///
+/// # Examples
+/// ```
+/// use bity::{percent::parse, Error};
+///
+/// assert_eq!(parse("12.5%").unwrap(), 125_000);
+/// assert_eq!(parse("150 %").unwrap(), 1_500_000);
+/// assert_eq!(parse("300ppm").unwrap(), 300);
+/// assert_eq!(parse("0.5").unwrap(), 500_000);
+/// assert_eq!(parse("1").unwrap(), 1_000_000);
+/// assert_eq!(parse("0.00001%").unwrap(), 0); // Less than a ppm.
+/// assert!(matches!(parse("12k%"), Err(Error::InvalidUnit("k%"))));
+/// assert!(matches!(parse("%"), Err(Error::EmptyInput)));
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    let (value, ppm) = parse_wide(input)?;
+    u64::try_from(ppm).map_err(|_| Error::ParseIntError(value, None))
+}
+
+/// Like [`parse`], but without overflowing a `u64`, also returning the number
+/// part of the input.
+pub(crate) fn parse_wide(input: &str) -> Result<(&str, u128), Error<'_>> {
+    if !input.is_ascii() {
+        return Err(Error::NotAscii);
+    }
+
+    let input = input.trim();
+    let (value, unit_str) = input.split_at(
+        input
+            .bytes()
+            .position(|b| b.is_ascii_alphabetic() || b == b'%')
+            .unwrap_or(input.len()),
+    );
+    let unit = match unit_str {
+        "%" => PERCENT,
+        "ppm" => 1,
+        "" => ONE,
+        _ if is_multiple_units(unit_str, UNITS) => return Err(Error::MultipleUnits(unit_str)),
+        _ => return Err(Error::InvalidUnit(unit_str)),
+    };
+
+    let value = value.trim();
+    check_number(value)?;
+    let (integer_str, fraction_str) = value.split_once('.').unwrap_or((value, ""));
+    if integer_str.is_empty() && fraction_str.is_empty() {
+        return Err(Error::ParseIntError(value, None));
+    }
+    // Also check the digits that are dropped below.
+    if !fraction_str.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::ParseIntError(fraction_str, fraction_str.parse::<u64>().err()));
+    }
+    // Drop the digits that are more precise than a ppm.
+    let fraction_str = &fraction_str[..fraction_str.len().min(unit.ilog10() as usize)];
+    let fraction_str = fraction_str.trim_end_matches('0');
+
+    fn parse_part(part: &str) -> Result<u64, Error<'_>> {
+        if part.is_empty() {
+            return Ok(0);
+        }
+        part.parse::<u64>()
+            .map_err(|err| Error::ParseIntError(part, Some(err)))
+    }
+    // A `u64` times the unit fits in a `u128`.
+    let integer = u128::from(parse_part(integer_str)?) * u128::from(unit);
+    let fraction = parse_part(fraction_str)? * unit / 10u64.pow(fraction_str.len() as u32);
+    Ok((value, integer + u128::from(fraction)))
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows).
+///
+/// # Examples
+/// ```
+/// use bity::percent::parse_const;
+///
+/// assert_eq!(parse_const("12.5%"), Some(125_000));
+/// assert_eq!(parse_const("0.5"), Some(500_000));
+/// assert_eq!(parse_const("12k%"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    match parse_wide_const(input) {
+        Some(ppm) if ppm <= u64::MAX as u128 => Some(ppm as u64),
+        _ => None,
+    }
+}
+
+/// Const version of [`parse_wide`], without the number part.
+pub(crate) const fn parse_wide_const(input: &str) -> Option<u128> {
+    if !input.is_ascii() {
+        return None;
+    }
+
+    let input = input.trim_ascii();
+    let mut split = 0;
+    while split < input.len()
+        && !input.as_bytes()[split].is_ascii_alphabetic()
+        && input.as_bytes()[split] != b'%'
+    {
+        split += 1;
+    }
+    let (value, unit_str) = input.split_at(split);
+    let unit = match unit_str.as_bytes() {
+        b"%" => PERCENT,
+        b"ppm" => 1,
+        b"" => ONE,
+        _ => return None,
+    };
+
+    let value = value.trim_ascii();
+    let mut dot = 0;
+    while dot < value.len() && value.as_bytes()[dot] != b'.' {
+        dot += 1;
+    }
+    let (integer_str, mut fraction_str) = value.split_at(dot);
+    if !fraction_str.is_empty() {
+        fraction_str = fraction_str.split_at(1).1;
+    }
+    if integer_str.is_empty() && fraction_str.is_empty() {
+        return None;
+    }
+    // Also check the digits that are dropped below.
+    let mut i = 0;
+    while i < fraction_str.len() {
+        if !fraction_str.as_bytes()[i].is_ascii_digit() {
+            return None;
+        }
+        i += 1;
+    }
+    // Drop the digits that are more precise than a ppm.
+    let precision = unit.ilog10() as usize;
+    if fraction_str.len() > precision {
+        fraction_str = fraction_str.split_at(precision).0;
+    }
+    while let [.., b'0'] = fraction_str.as_bytes() {
+        fraction_str = fraction_str.split_at(fraction_str.len() - 1).0;
+    }
+
+    const fn parse_part(part: &str) -> Option<u64> {
+        if part.is_empty() {
+            return Some(0);
+        }
+        match u64::from_str_radix(part, 10) {
+            Ok(part) => Some(part),
+            Err(_) => None,
+        }
+    }
+    let (Some(integer), Some(fraction)) = (parse_part(integer_str), parse_part(fraction_str))
+    else {
+        return None;
+    };
+    // A `u64` times the unit fits in a `u128`.
+    let fraction = fraction * unit / 10u64.pow(fraction_str.len() as u32);
+    Some(integer as u128 * unit as u128 + fraction as u128)
+}
+
+/// Format a ppm value into a percent string.
+///
+/// At most four fraction digits will be displayed, which is the precision of
+/// a ppm.
+///
+/// # Examples
+/// ```
+/// use bity::percent::format;
+///
+/// assert_eq!(format(0), "0%");
+/// assert_eq!(format(125_000), "12.5%");
+/// assert_eq!(format(1_500_000), "150%");
+/// assert_eq!(format(1), "0.0001%");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Like [`format`], but writing into an existing writer instead of
+/// allocating.
+///
+/// # Examples
+/// ```
+/// use bity::percent::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 125_000).unwrap();
+/// assert_eq!(output, "12.5%");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    output.write_str(Digits::fixed(input, PERCENT).as_str())?;
+    output.write_str("%")
+}
+
+/// Format a ppm value into a ppm string.
+///
+/// # Examples
+/// ```
+/// use bity::percent::format_ppm;
+///
+/// assert_eq!(format_ppm(300), "300ppm");
+/// assert_eq!(format_ppm(125_000), "125000ppm");
+/// ```
+pub fn format_ppm(input: u64) -> String {
+    format!("{input}ppm")
+}
+
+/// Apply a ppm ratio to a value.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, percent};
+///
+/// let reserved = percent::of(percent::parse("12.5%").unwrap(), byte::parse("2GB").unwrap());
+/// assert_eq!(byte::format(reserved), "250MB");
+/// ```
+pub fn of(ratio: u64, value: u64) -> u64 {
+    let result = (u128::from(ratio) * u128::from(value) + u128::from(ONE / 2)) / u128::from(ONE);
+    u64::try_from(result).unwrap_or(u64::MAX)
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a percentage like "12.5%", "300ppm" or "0.125""#,
+    example: 125_000,
+    numbers: Parsed,
+    ser:
+    /// Serialize a given ppm `u64` into a percent string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::percent::serialize")]` and `#[serde(with = "bity::percent")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::percent::serialize")]
+    ///     max_loss: u64,
+    ///     #[serde(with = "bity::percent")]
+    ///     target_utilization: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         max_loss: 300,
+    ///         target_utilization: 800_000,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         max-loss = "0.03%"
+    ///         target-utilization = "80%"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given ratio number or percent, ppm or ratio string into a ppm `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::percent::deserialize")]` and `#[serde(with = "bity::percent")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::percent::deserialize")]
+    ///     max_loss: u64,
+    ///     #[serde(with = "bity::percent")]
+    ///     target_utilization: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             max-loss = "300ppm"
+    ///             target-utilization = 0.8
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         max_loss: 300,
+    ///         target_utilization: 800_000,
+    ///     }
+    /// );
+    /// ```
+    as_int:
+    ///
+    /// **Unlike the other submodules, bare integers are read as ppm, not as
+    /// ratios**: `1` is `1ppm` here, but `100%` with `bity::percent`. Strings
+    /// without unit are still read as ratios.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// struct Configuration {
+    ///     #[serde(with = "bity::percent::as_int")]
+    ///     max_loss: u64,
+    ///     #[serde(with = "bity::percent")]
+    ///     target_utilization: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>("max_loss = 1\ntarget_utilization = 1").unwrap(),
+    ///     Configuration {
+    ///         max_loss: 1,
+    ///         target_utilization: 1_000_000,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("12.5%").unwrap(), 125_000);
+        assert_eq!(super::parse(" 12.5 % ").unwrap(), 125_000);
+        assert_eq!(super::parse("0.0001%").unwrap(), 1);
+        assert_eq!(super::parse("0.00019%").unwrap(), 1);
+        assert_eq!(super::parse(".5%").unwrap(), 5_000);
+        assert_eq!(super::parse("300ppm").unwrap(), 300);
+        assert_eq!(super::parse("300.9ppm").unwrap(), 300);
+        assert_eq!(super::parse(".9ppm").unwrap(), 0);
+        assert!(matches!(super::parse("1..5ppm"), Err(Error::TrailingGarbage(".5"))));
+        assert_eq!(super::parse("0.5").unwrap(), 500_000);
+        assert_eq!(super::parse("0.1234567").unwrap(), 123_456);
+        assert_eq!(super::parse("2").unwrap(), 2_000_000);
+
+        assert!(matches!(super::parse("12PPM"), Err(Error::InvalidUnit("PPM"))));
+        assert!(matches!(super::parse("12%%"), Err(Error::MultipleUnits("%%"))));
+        assert!(matches!(super::parse("."), Err(Error::ParseIntError(".", None))));
+        assert!(matches!(super::parse("-1%"), Err(Error::NegativeValue("-1"))));
+        assert!(matches!(
+            super::parse("18446744073709551615"),
+            Err(Error::ParseIntError(_, None))
+        ));
+        assert!(matches!(super::parse("12€"), Err(Error::NotAscii)));
+    }
+
+    #[test]
+    fn parse_const() {
+        for input in [
+            "12.5%",
+            " 12.5 % ",
+            "0.00019%",
+            ".5%",
+            "300.9ppm",
+            ".9ppm",
+            "0.1234567",
+            "2",
+            "1.",
+            "+1%",
+            "12PPM",
+            "12%%",
+            ".",
+            "",
+            "%",
+            "-1%",
+            "1..5ppm",
+            "1.+5",
+            "1 2%",
+            "12€",
+            "18446744073709551615",
+            "18446744073709.551615",
+        ] {
+            assert_eq!(super::parse_const(input), super::parse(input).ok(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(0), "0%");
+        assert_eq!(super::format(300), "0.03%");
+        assert_eq!(super::format(125_000), "12.5%");
+        assert_eq!(super::format(1_000_000), "100%");
+        assert_eq!(super::format(1_000_001), "100.0001%");
+        assert_eq!(super::format_ppm(12), "12ppm");
+    }
+
+    #[test]
+    fn format_into() {
+        for input in [0, 1, 300, 125_000, 1_000_000, 1_000_001, u64::MAX] {
+            let mut output = String::new();
+            super::format_into(&mut output, input).unwrap();
+            assert_eq!(output, super::format(input));
+        }
+    }
+
+    #[test]
+    fn of() {
+        assert_eq!(super::of(500_000, 3), 2);
+        assert_eq!(super::of(0, u64::MAX), 0);
+        assert_eq!(super::of(super::ONE, u64::MAX), u64::MAX);
+        assert_eq!(super::of(2 * super::ONE, u64::MAX), u64::MAX);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::percent")]
+            ratio: u64,
+        }
+
+        let from_toml = |ratio| {
+            toml::from_str::<Configuration>(&format!("ratio = {ratio}"))
+                .map(|configuration| configuration.ratio)
+                .map_err(|err| err.message().to_owned())
+        };
+        assert_eq!(from_toml("0.125"), Ok(125_000));
+        assert_eq!(from_toml(r#""0.125""#), Ok(125_000));
+        assert_eq!(from_toml("50"), Ok(50_000_000));
+        assert_eq!(from_toml(r#""50""#), Ok(50_000_000));
+        assert_eq!(from_toml(r#""50%""#), Ok(500_000));
+        assert_eq!(from_toml("1e-4"), Ok(100));
+        assert_eq!(
+            from_toml("-0.5").unwrap_err(),
+            r#"invalid value: floating point `-0.5`, expected a percentage like "12.5%", "300ppm" or "0.125""#
+        );
+        assert_eq!(
+            from_toml("100000000000000").unwrap_err(),
+            r#"invalid value: integer `100000000000000`, expected a percentage like "12.5%", "300ppm" or "0.125""#
+        );
+
+        let configuration = Configuration { ratio: u64::MAX };
+        let json = serde_json::to_string(&configuration).unwrap();
+        assert_eq!(serde_json::from_str::<Configuration>(&json).unwrap(), configuration);
+        assert_eq!(
+            serde_json::from_str::<Configuration>(r#"{"ratio":0.5}"#).unwrap(),
+            Configuration { ratio: 500_000 }
+        );
+
+        #[derive(serde::Deserialize, PartialEq, Debug)]
+        struct Ratios {
+            #[serde(with = "crate::percent::vec")]
+            ratios: Vec<u64>,
+            #[serde(with = "crate::percent::as_int")]
+            ppm: u64,
+        }
+        assert_eq!(
+            toml::from_str::<Ratios>(
+                r#"ratios = [0.5, 1, "10%"]
+ppm = 300"#
+            )
+            .unwrap(),
+            Ratios {
+                ratios: vec![500_000, 1_000_000, 100_000],
+                ppm: 300,
+            }
+        );
+        // Only `as_int` reads bare integers as ppm.
+        assert_eq!(
+            toml::from_str::<Ratios>(
+                r#"ratios = [1]
+ppm = "1""#
+            )
+            .unwrap(),
+            Ratios {
+                ratios: vec![1_000_000],
+                ppm: 1_000_000,
+            }
+        );
+    }
+}
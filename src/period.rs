@@ -0,0 +1,97 @@
+use core::time::Duration;
+
+/// Fixed-length time period used to express rates in a different time base
+/// than the second.
+///
+/// Periods have a fixed length and ignore calendars (leap days, leap seconds,
+/// DST, ...):
+/// - a [`Day`](Period::Day) is always `24` hours
+/// - a [`Week`](Period::Week) is always `7` days
+/// - a [`Month`](Period::Month) is always `30` days (billing month)
+/// - a [`Year`](Period::Year) is always `365` days
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::Period;
+///
+/// assert_eq!(Period::Hour.duration(), Duration::from_secs(3_600));
+/// assert_eq!(Period::Hour.symbol(), "h");
+/// assert_eq!(Period::Month.duration(), Duration::from_secs(30 * 86_400));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub enum Period {
+    /// One second.
+    Second,
+    /// Sixty seconds.
+    Minute,
+    /// Sixty minutes.
+    Hour,
+    /// Twenty-four hours.
+    Day,
+    /// Seven days.
+    Week,
+    /// Thirty days.
+    Month,
+    /// Three hundred sixty-five days.
+    Year,
+}
+
+impl Period {
+    /// Number of seconds in this period.
+    pub const fn as_secs(self) -> u64 {
+        match self {
+            Period::Second => 1,
+            Period::Minute => 60,
+            Period::Hour => 3_600,
+            Period::Day => 86_400,
+            Period::Week => 7 * 86_400,
+            Period::Month => 30 * 86_400,
+            Period::Year => 365 * 86_400,
+        }
+    }
+
+    /// Symbol of this period, as used after the `/` of a rate (`3.6GB/h`).
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Period::Second => "s",
+            Period::Minute => "min",
+            Period::Hour => "h",
+            Period::Day => "d",
+            Period::Week => "w",
+            Period::Month => "mo",
+            Period::Year => "y",
+        }
+    }
+
+    /// Length of this period.
+    pub const fn duration(self) -> Duration {
+        Duration::from_secs(self.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Period;
+
+    #[test]
+    fn as_secs() {
+        assert_eq!(Period::Second.as_secs(), 1);
+        assert_eq!(Period::Minute.as_secs(), 60);
+        assert_eq!(Period::Hour.as_secs(), 3_600);
+        assert_eq!(Period::Day.as_secs(), 86_400);
+        assert_eq!(Period::Week.as_secs(), 604_800);
+        assert_eq!(Period::Month.as_secs(), 2_592_000);
+        assert_eq!(Period::Year.as_secs(), 31_536_000);
+    }
+}
@@ -22,6 +22,7 @@
 //! "bity::pps::deserialize")]` and `#[serde(with = "bity::pps")]` attributes.
 //!
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use indoc::indoc;
 //! use serde::{Deserialize, Serialize};
 //!
@@ -63,9 +64,31 @@
 //!         highest = "12kp/s"
 //!     "#}
 //! );
+//! # }
 //! ```
 
-use crate::{error::Error, packet};
+use alloc::{format, string::String};
+use core::{fmt, time::Duration};
+
+use crate::{
+    error::Error,
+    packet,
+    si::{self, Prefix},
+    IntoDuration, Period,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str =
+    r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTPeE]?p?\s*(?:/s|ps)?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["p/s", "pps"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "p/s".len();
 
 /// Parse a packet-rate SI prefixed string into a number.
 ///
@@ -89,6 +112,20 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
     packet::parse(crate::strip_per_second(input))
 }
 
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::pps::parse_const;
+///
+/// assert_eq!(parse_const("1.5kpps"), Some(1_500));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    packet::parse_const(crate::strip_per_second_const(input))
+}
+
 /// Format an integer into a packet-rate SI prefixed string.
 ///
 /// This is equivalent to colling `format!("{}/s", packet::format(input))`.
@@ -105,11 +142,272 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// assert_eq!(format(12_000), "12kp/s");
 /// ```
 pub fn format(input: u64) -> String {
-    format!("{}/s", packet::format(input))
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (packet-rate) using a given SI
+/// prefix, whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}/s",
+/// packet::format_with_prefix(input, prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{pps::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300Mp/s");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push_str("p/s");
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::pps::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 12_300).unwrap();
+/// assert_eq!(output, "12.3kp/s");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    packet::format_into(output, input)?;
+    output.write_str("/s")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::pps::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 12_300).unwrap();
+/// assert_eq!(&buffer[..len], b"12.3kp/s");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Compute the packet-rate (per-second) of a volume of packets transferred over a
+/// given duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. A zero duration is treated as an infinite rate,
+/// except for a zero volume which always gives a zero rate.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{packet, pps};
+///
+/// let rate = pps::from_volume(packet::parse("12Mp").unwrap(), Duration::from_secs(95));
+/// assert_eq!(rate, 126_316);
+/// assert_eq!(pps::format(rate), "126.31kp/s");
+/// ```
+pub fn from_volume(volume: u64, duration: impl IntoDuration) -> u64 {
+    crate::rate::from_volume(volume, duration.into_duration())
+}
+
+/// Compute the volume of packets transferred at a given packet-rate over a given
+/// duration.
+///
+/// The result is rounded to the nearest integer and saturates to `u64::MAX`
+/// instead of overflowing. Use [`checked_volume_over`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{packet, pps};
+///
+/// let volume = pps::volume_over(pps::parse("50kp/s").unwrap(), Duration::from_secs(6 * 3_600));
+/// assert_eq!(packet::format(volume), "1.08Gp");
+/// assert_eq!(pps::volume_over(u64::MAX, Duration::from_secs(2)), u64::MAX);
+/// ```
+pub fn volume_over(rate: u64, duration: impl IntoDuration) -> u64 {
+    checked_volume_over(rate, duration).unwrap_or(u64::MAX)
+}
+
+/// Like [`volume_over`] but returns `None` if the volume doesn't fit in an
+/// `u64`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::pps::checked_volume_over;
+///
+/// assert_eq!(checked_volume_over(1_000, Duration::from_millis(1_500)), Some(1_500));
+/// assert_eq!(checked_volume_over(u64::MAX, Duration::from_secs(2)), None);
+/// ```
+pub fn checked_volume_over(rate: u64, duration: impl IntoDuration) -> Option<u64> {
+    crate::rate::checked_volume_over(rate, duration.into_duration())
+}
+
+/// Compute the time needed to transfer a volume of packets at a given packet-rate.
+///
+/// The result is rounded to the nearest nanosecond. A zero rate is treated as
+/// an infinite transfer time and gives `Duration::MAX`, except for a zero
+/// volume which always gives `Duration::ZERO`.
+///
+/// # Examples
+/// ```
+/// use bity::{packet, pps};
+///
+/// let time = pps::time_to_transfer(packet::parse("100Gp").unwrap(), pps::parse("42.9Mp/s").unwrap());
+/// assert_eq!(time.as_secs(), 2_331);
+/// ```
+pub fn time_to_transfer(volume: u64, rate: u64) -> Duration {
+    crate::rate::time_to_transfer(volume, rate)
+}
+
+/// Format the time needed to transfer a volume of packets at a given
+/// packet-rate, alongside the volume and the rate.
+///
+/// The duration is formatted in a compact humantime-like style, truncated to
+/// the second.
+///
+/// # Examples
+/// ```
+/// use bity::pps::format_time_to_transfer;
+///
+/// assert_eq!(
+///     format_time_to_transfer(100_000_000_000, 42_900_000),
+///     "100Gp in 38m51s at 42.9Mp/s"
+/// );
+/// ```
+#[cfg(feature = "duration")]
+pub fn format_time_to_transfer(volume: u64, rate: u64) -> String {
+    format!(
+        "{} in {} at {}",
+        packet::format(volume),
+        crate::rate::format_duration(time_to_transfer(volume, rate)),
+        format(rate)
+    )
+}
+
+/// Compute the volume of packets transferred over a whole [`Period`] at a
+/// given sustained packet-rate.
+///
+/// This is equivalent to calling `volume_over(rate, period.duration())`, refer
+/// to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{packet, pps, Period};
+///
+/// assert_eq!(packet::format(pps::volume_per(512_000, Period::Month)), "1.32Tp");
+/// ```
+pub fn volume_per(rate: u64, period: Period) -> u64 {
+    volume_over(rate, period.duration())
+}
+
+/// Compute the sustained packet-rate (per-second) needed to transfer a volume of
+/// packets over a whole [`Period`].
+///
+/// This is equivalent to calling `from_volume(volume, period.duration())`,
+/// refer to [`Period`] for the length of each period.
+///
+/// # Examples
+/// ```
+/// use bity::{packet, pps, Period};
+///
+/// assert_eq!(pps::from_volume_per(packet::parse("3Gp").unwrap(), Period::Day), 34_722);
+/// ```
+pub fn from_volume_per(volume: u64, period: Period) -> u64 {
+    from_volume(volume, period.duration())
+}
+
+/// Format a per-second packet-rate into a SI prefixed string using a different
+/// time base.
+///
+/// This is equivalent to calling `format!("{}/{}",
+/// packet::format(volume_per(input, period)), period.symbol())`.
+///
+/// # Examples
+/// ```
+/// use bity::{pps::format_per, Period};
+///
+/// assert_eq!(format_per(1_000_000, Period::Second), "1Mp/s");
+/// assert_eq!(format_per(1_000_000, Period::Hour), "3.6Gp/h");
+/// assert_eq!(format_per(1_000_000, Period::Day), "86.4Gp/d");
+/// ```
+pub fn format_per(input: u64, period: Period) -> String {
+    format!("{}/{}", packet::format(volume_per(input, period)), period.symbol())
+}
+
+/// Compute the data-rate (in bytes per second) of a packet-rate given an
+/// average packet size (in bytes).
+///
+/// The result saturates to `u64::MAX` instead of overflowing. Use
+/// [`checked_to_bandwidth`] to detect overflows.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, byteps, pps};
+///
+/// let bandwidth = pps::to_bandwidth(pps::parse("1.488Mpps").unwrap(), byte::parse("64B").unwrap());
+/// assert_eq!(byteps::format(bandwidth), "95.23MB/s");
+/// assert_eq!(pps::to_bandwidth(u64::MAX, 2), u64::MAX);
+/// ```
+pub fn to_bandwidth(rate: u64, packet_size: u64) -> u64 {
+    checked_to_bandwidth(rate, packet_size).unwrap_or(u64::MAX)
+}
+
+/// Like [`to_bandwidth`] but returns `None` if the data-rate doesn't fit in an
+/// `u64`.
+///
+/// # Examples
+/// ```
+/// use bity::pps::checked_to_bandwidth;
+///
+/// assert_eq!(checked_to_bandwidth(1_000, 1_500), Some(1_500_000));
+/// assert_eq!(checked_to_bandwidth(u64::MAX, 2), None);
+/// ```
+pub fn checked_to_bandwidth(rate: u64, packet_size: u64) -> Option<u64> {
+    rate.checked_mul(packet_size)
+}
+
+/// Compute the packet-rate of a data-rate (in bytes per second) given an
+/// average packet size (in bytes).
+///
+/// The result is rounded to the nearest integer. A zero packet size is treated
+/// as an infinite packet-rate and gives `u64::MAX`, except for a zero data-rate
+/// which always gives a zero packet-rate.
+///
+/// # Examples
+/// ```
+/// use bity::{byte, byteps, pps};
+///
+/// let rate = pps::from_bandwidth(byteps::parse("10MB/s").unwrap(), byte::parse("1.5kB").unwrap());
+/// assert_eq!(pps::format(rate), "6.66kp/s");
+/// assert_eq!(pps::from_bandwidth(1_000, 0), u64::MAX);
+/// ```
+pub fn from_bandwidth(bandwidth: u64, packet_size: u64) -> u64 {
+    if packet_size == 0 {
+        return if bandwidth == 0 { 0 } else { u64::MAX };
+    }
+    let rounded = (u128::from(bandwidth) + u128::from(packet_size) / 2) / u128::from(packet_size);
+    rounded as u64
 }
 
 #[cfg(feature = "serde")]
 crate::impl_serde!(
+    expecting: r#"a packet-rate like "1.5kp/s", "12Mpps" or 1500"#,
+    example: 5_000_000,
     ser:
     /// Serialize a given `u64` into a SI prefixed packet-rate string.
     ///
@@ -176,6 +474,9 @@ crate::impl_serde!(
     /// ```
 );
 
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -196,4 +497,24 @@ mod tests {
         assert_eq!(super::format(1_234), "1.23kp/s");
         assert_eq!(super::format(12_000), "12kp/s");
     }
+
+    #[test]
+    fn to_bandwidth() {
+        assert_eq!(super::to_bandwidth(1_000, 1_500), 1_500_000);
+        assert_eq!(super::to_bandwidth(0, 1_500), 0);
+        assert_eq!(super::to_bandwidth(1_000, 0), 0);
+        assert_eq!(super::to_bandwidth(u64::MAX, 1), u64::MAX);
+        assert_eq!(super::to_bandwidth(u64::MAX, 2), u64::MAX);
+        assert_eq!(super::checked_to_bandwidth(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn from_bandwidth() {
+        assert_eq!(super::from_bandwidth(1_500_000, 1_500), 1_000);
+        assert_eq!(super::from_bandwidth(1_000, 3), 333); // Round down.
+        assert_eq!(super::from_bandwidth(2_000, 3), 667); // Round up.
+        assert_eq!(super::from_bandwidth(u64::MAX, 1), u64::MAX);
+        assert_eq!(super::from_bandwidth(0, 0), 0);
+        assert_eq!(super::from_bandwidth(1, 0), u64::MAX);
+    }
 }
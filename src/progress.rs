@@ -0,0 +1,201 @@
+//! Transfer progress formatting (byte oriented).
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::progress;
+//!
+//! assert_eq!(progress::format(1_200_000_000, 4_500_000_000), "1.2GB / 4.5GB (26%)");
+//! assert_eq!(progress::format_compact(1_200_000_000, 4_500_000_000), "1.2GB/4.5GB 26%");
+//! assert_eq!(
+//!     progress::format_bar(1_200_000_000, 4_500_000_000, 10),
+//!     "[##--------] 1.2GB / 4.5GB (26%)"
+//! );
+//! ```
+//!
+//! The remaining time can be estimated from the current data-rate, for
+//! example measured by a [`Throughput`](crate::meter::Throughput) tracker:
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//!
+//! use bity::{meter::Throughput, progress};
+//!
+//! let start = Instant::now();
+//! let mut throughput = Throughput::new(Duration::from_secs(5));
+//! throughput.record(0, start);
+//! throughput.record(42_900_000, start + Duration::from_secs(1));
+//!
+//! let eta = progress::eta(32_217_900_000, 42_900_000, throughput.rate());
+//! assert_eq!(eta, Duration::from_secs(750));
+//! assert_eq!(
+//!     progress::format_eta(32_217_900_000, 42_900_000, throughput.rate()),
+//!     "42.9MB/s, 12m30s remaining"
+//! );
+//! ```
+
+use alloc::{format, string::String};
+use core::time::Duration;
+
+use crate::{byte, byteps};
+
+/// Completion percentage, rounded down (and saturated).
+///
+/// A zero total is considered complete.
+fn percent(current: u64, total: u64) -> u64 {
+    if total == 0 {
+        return 100;
+    }
+    u64::try_from(u128::from(current) * 100 / u128::from(total)).unwrap_or(u64::MAX)
+}
+
+/// Format the progress of a transfer, as `current / total (percent%)`.
+///
+/// Sizes are formatted using [`byte::format`] and the percentage is rounded
+/// down. A zero total is considered complete.
+///
+/// # Examples
+/// ```
+/// use bity::progress::format;
+///
+/// assert_eq!(format(1_200_000_000, 4_500_000_000), "1.2GB / 4.5GB (26%)");
+/// assert_eq!(format(0, 12_000), "0B / 12kB (0%)");
+/// assert_eq!(format(0, 0), "0B / 0B (100%)");
+/// ```
+pub fn format(current: u64, total: u64) -> String {
+    format!(
+        "{} / {} ({}%)",
+        byte::format(current),
+        byte::format(total),
+        percent(current, total)
+    )
+}
+
+/// Like [`format`] but without spaces and parentheses, as `current/total
+/// percent%`.
+///
+/// # Examples
+/// ```
+/// use bity::progress::format_compact;
+///
+/// assert_eq!(format_compact(1_200_000_000, 4_500_000_000), "1.2GB/4.5GB 26%");
+/// ```
+pub fn format_compact(current: u64, total: u64) -> String {
+    format!("{}/{} {}%", byte::format(current), byte::format(total), percent(current, total))
+}
+
+/// Like [`format`] but prefixed by a progress bar of `width` characters.
+///
+/// # Examples
+/// ```
+/// use bity::progress::format_bar;
+///
+/// assert_eq!(format_bar(1_200_000_000, 4_500_000_000, 10), "[##--------] 1.2GB / 4.5GB (26%)");
+/// assert_eq!(format_bar(12_000, 12_000, 4), "[####] 12kB / 12kB (100%)");
+/// ```
+pub fn format_bar(current: u64, total: u64, width: usize) -> String {
+    // Computed on 128 bits, as the product may not fit in an `usize`.
+    let filled = (u128::from(percent(current, total).min(100)) * width as u128 / 100) as usize;
+    format!(
+        "[{}{}] {}",
+        "#".repeat(filled),
+        "-".repeat(width - filled),
+        format(current, total)
+    )
+}
+
+/// Estimate the time remaining to complete a transfer of `total` bytes,
+/// `completed` of which are already transferred, at a given data-rate (in
+/// bytes per second).
+///
+/// A zero rate is treated as an infinite remaining time and gives
+/// `Duration::MAX`, except for a completed transfer which always gives
+/// `Duration::ZERO`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::progress::eta;
+///
+/// assert_eq!(eta(4_500_000_000, 1_200_000_000, 42_900_000), Duration::from_nanos(76_923_076_923));
+/// assert_eq!(eta(4_500_000_000, 4_500_000_000, 0), Duration::ZERO);
+/// assert_eq!(eta(4_500_000_000, 1_200_000_000, 0), Duration::MAX);
+/// ```
+pub fn eta(total: u64, completed: u64, rate: u64) -> Duration {
+    byteps::time_to_transfer(total.saturating_sub(completed), rate)
+}
+
+/// Format the current data-rate and the estimated remaining time of a
+/// transfer, as `rate, eta remaining`.
+///
+/// The remaining time is formatted in a compact humantime-like style,
+/// truncated to the second. If the transfer is stalled (zero rate), the
+/// remaining time is replaced by `stalled`.
+///
+/// Refer to [`eta`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::progress::format_eta;
+///
+/// assert_eq!(format_eta(4_500_000_000, 1_200_000_000, 42_900_000), "42.9MB/s, 1m16s remaining");
+/// assert_eq!(format_eta(4_500_000_000, 1_200_000_000, 0), "0B/s, stalled");
+/// ```
+#[cfg(feature = "duration")]
+pub fn format_eta(total: u64, completed: u64, rate: u64) -> String {
+    let eta = eta(total, completed, rate);
+    if eta == Duration::MAX {
+        return format!("{}, stalled", byteps::format(rate));
+    }
+    format!("{}, {} remaining", byteps::format(rate), crate::rate::format_duration(eta))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn format() {
+        assert_eq!(super::format(1_200_000_000, 4_500_000_000), "1.2GB / 4.5GB (26%)");
+        assert_eq!(super::format(999, 1_000), "999B / 1kB (99%)");
+        assert_eq!(super::format(1_000, 1_000), "1kB / 1kB (100%)");
+        assert_eq!(super::format(1_500, 1_000), "1.5kB / 1kB (150%)");
+        assert_eq!(super::format(u64::MAX, u64::MAX), "18.44EB / 18.44EB (100%)");
+        assert_eq!(super::format(0, 0), "0B / 0B (100%)");
+        assert_eq!(super::format(u64::MAX, 1), "18.44EB / 1B (18446744073709551615%)");
+    }
+
+    #[test]
+    fn format_compact() {
+        assert_eq!(super::format_compact(1_200_000_000, 4_500_000_000), "1.2GB/4.5GB 26%");
+        assert_eq!(super::format_compact(0, 0), "0B/0B 100%");
+    }
+
+    #[test]
+    fn eta() {
+        use std::time::Duration;
+
+        assert_eq!(super::eta(1_000, 0, 100), Duration::from_secs(10));
+        assert_eq!(super::eta(1_000, 500, 100), Duration::from_secs(5));
+        assert_eq!(super::eta(1_000, 1_000, 100), Duration::ZERO);
+        assert_eq!(super::eta(1_000, 2_000, 100), Duration::ZERO);
+        assert_eq!(super::eta(1_000, 1_000, 0), Duration::ZERO);
+        assert_eq!(super::eta(1_000, 0, 0), Duration::MAX);
+    }
+
+    #[cfg(feature = "duration")]
+    #[test]
+    fn format_eta() {
+        assert_eq!(super::format_eta(1_000, 0, 100), "100B/s, 10s remaining");
+        assert_eq!(super::format_eta(1_000, 1_000, 100), "100B/s, 0s remaining");
+        assert_eq!(super::format_eta(1_000, 0, 0), "0B/s, stalled");
+    }
+
+    #[test]
+    fn format_bar() {
+        assert_eq!(super::format_bar(0, 1_000, 4), "[----] 0B / 1kB (0%)");
+        assert_eq!(super::format_bar(500, 1_000, 4), "[##--] 500B / 1kB (50%)");
+        assert_eq!(super::format_bar(1_500, 1_000, 4), "[####] 1.5kB / 1kB (150%)");
+        assert_eq!(super::format_bar(500, 1_000, 0), "[] 500B / 1kB (50%)");
+        assert_eq!(super::format_bar(u64::MAX, 1, 2), "[##] 18.44EB / 1B (18446744073709551615%)");
+    }
+}
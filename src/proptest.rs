@@ -0,0 +1,210 @@
+//! [`proptest`](https://docs.rs/proptest) strategies, generating values and
+//! inputs following the grammar of the parsing functions.
+//!
+//! Each unit family has a `<family>_string` strategy generating strings
+//! accepted by its parsing function, and a `near_<family>_string` strategy
+//! generating strings matching its `PATTERN`, which may still be rejected
+//! (like numbers that don't fit in a `u64`), to test error handling.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::proptest::{byte_string, near_byte_string};
+//! use proptest::{prop_assert, proptest};
+//!
+//! proptest!(|(input in byte_string())| {
+//!     prop_assert!(bity::byte::parse(&input).is_ok());
+//! });
+//!
+//! proptest!(|(input in near_byte_string())| {
+//!     // Must not panic.
+//!     let _ = bity::byte::parse(&input);
+//! });
+//! ```
+
+use std::fmt::Debug;
+
+use proptest::{
+    prelude::{prop_oneof, Just, Strategy},
+    sample::select,
+    string::string_regex,
+};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, unit::Unit};
+
+/// Whitespaces accepted around the number and the unit.
+const WHITESPACES: &[&str] = &["", " ", "  ", "\t"];
+
+/// Integers spread over all the magnitudes, so all the SI prefixes are
+/// equally exercised (most of the `u64`s are above `10^18`).
+///
+/// # Examples
+/// ```
+/// use bity::proptest::value;
+/// use proptest::{prop_assert, proptest};
+///
+/// proptest!(|(input in value())| {
+///     prop_assert!(bity::si::parse(&bity::si::format(input)).is_ok());
+/// });
+/// ```
+pub fn value() -> impl Strategy<Value = u64> {
+    (0..=20u32).prop_flat_map(|digits| 0..=10u64.saturating_pow(digits))
+}
+
+/// Limits holding a [`value`], or unlimited.
+pub fn limit<U: Debug + Clone>() -> impl Strategy<Value = Limit<U>> {
+    prop_oneof![
+        1 => Just(Limit::unlimited()),
+        4 => value().prop_map(Limit::new),
+    ]
+}
+
+/// Relative values (as ppm, up to `200%`), or absolute [`value`]s.
+#[cfg(feature = "percent")]
+pub fn relative_or_absolute<U: Debug + Clone>() -> impl Strategy<Value = RelativeOrAbsolute<U>> {
+    prop_oneof![
+        (0..=2 * crate::percent::ONE).prop_map(RelativeOrAbsolute::relative),
+        value().prop_map(RelativeOrAbsolute::absolute),
+    ]
+}
+
+/// Strings accepted by `U::parse`: [`value`]s formatted using `U::format`,
+/// with whitespaces inserted around the number and the unit.
+///
+/// # Examples
+/// ```
+/// use bity::{proptest::string, unit::Byteps};
+/// use proptest::{prop_assert, proptest};
+///
+/// proptest!(|(input in string::<Byteps>())| {
+///     prop_assert!(bity::byteps::parse(&input).is_ok());
+/// });
+/// ```
+pub fn string<U: Unit>() -> impl Strategy<Value = String> {
+    value()
+        .prop_map(U::format)
+        // Some units can't parse back their largest values (like compression
+        // ratios, parsed into ppm before being divided).
+        .prop_filter("unparsable formatted value", |formatted| U::parse(formatted).is_ok())
+        .prop_flat_map(|formatted| {
+            let whitespaces = select(WHITESPACES);
+            (whitespaces.clone(), whitespaces.clone(), whitespaces).prop_map(
+                move |(before, between, after)| {
+                    let position = formatted
+                        .bytes()
+                        .position(|b| !b.is_ascii_digit() && b != b'.')
+                        .unwrap_or(formatted.len());
+                    let (number, unit) = formatted.split_at(position);
+                    if unit.is_empty() {
+                        format!("{before}{number}{after}")
+                    } else {
+                        format!("{before}{number}{between}{unit}{after}")
+                    }
+                },
+            )
+        })
+}
+
+/// Strings matching a `PATTERN` (like [`byte::PATTERN`](crate::byte::PATTERN)),
+/// accepted or not by the matching parsing function.
+///
+/// # Panics
+/// Panics if `pattern` isn't a valid regular expression.
+pub fn near_string(pattern: &str) -> impl Strategy<Value = String> {
+    // Anchors aren't supported by `string_regex`, and are implied.
+    let pattern = pattern.trim_start_matches('^').trim_end_matches('$');
+    string_regex(pattern).expect("invalid pattern")
+}
+
+macro_rules! strategies {
+    ($(
+        $(#[cfg($cfg:meta)])?
+        $module:ident: $unit:ident, $string:ident, $near_string:ident,
+    )*) => {
+        $(
+            #[doc = concat!("Strings accepted by [`", stringify!($module), "::parse`](crate::", stringify!($module), "::parse), refer to [`string`].")]
+            $(#[cfg($cfg)])?
+            pub fn $string() -> impl Strategy<Value = String> {
+                string::<crate::unit::$unit>()
+            }
+
+            #[doc = concat!("Strings matching [`", stringify!($module), "::PATTERN`](crate::", stringify!($module), "::PATTERN), refer to [`near_string`].")]
+            $(#[cfg($cfg)])?
+            pub fn $near_string() -> impl Strategy<Value = String> {
+                near_string(crate::$module::PATTERN)
+            }
+        )*
+    };
+}
+
+strategies! {
+    si: Si, si_string, near_si_string,
+    #[cfg(feature = "bit")]
+    bit: Bit, bit_string, near_bit_string,
+    #[cfg(feature = "byte")]
+    byte: Byte, byte_string, near_byte_string,
+    #[cfg(feature = "packet")]
+    packet: Packet, packet_string, near_packet_string,
+    #[cfg(feature = "bps")]
+    bps: Bps, bps_string, near_bps_string,
+    #[cfg(feature = "byteps")]
+    byteps: Byteps, byteps_string, near_byteps_string,
+    #[cfg(feature = "pps")]
+    pps: Pps, pps_string, near_pps_string,
+    #[cfg(feature = "bpp")]
+    bpp: Bpp, bpp_string, near_bpp_string,
+    #[cfg(feature = "hz")]
+    hz: Hz, hz_string, near_hz_string,
+    #[cfg(feature = "watt")]
+    watt: Watt, watt_string, near_watt_string,
+    #[cfg(feature = "ops")]
+    ops: Ops, ops_string, near_ops_string,
+    #[cfg(feature = "percent")]
+    percent: Percent, percent_string, near_percent_string,
+    #[cfg(feature = "compression")]
+    compression: Compression, compression_string, near_compression_string,
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, proptest};
+
+    use crate::unit::{Si, Unit};
+
+    proptest! {
+        #[test]
+        fn string(input in super::si_string()) {
+            prop_assert!(Si::parse(&input).is_ok());
+        }
+
+        #[test]
+        fn near_string(input in super::near_si_string()) {
+            prop_assert!(regex::Regex::new(crate::si::PATTERN).unwrap().is_match(&input));
+            let _ = Si::parse(&input);
+        }
+
+        #[test]
+        fn limit(limit in super::limit::<Si>()) {
+            prop_assert!(crate::limit::Limit::<Si>::parse(&limit.to_string()).is_ok());
+        }
+    }
+
+    #[cfg(feature = "byteps")]
+    proptest! {
+        #[test]
+        fn byteps(input in super::byteps_string(), near in super::near_byteps_string()) {
+            prop_assert!(crate::byteps::parse(&input).is_ok());
+            let _ = crate::byteps::parse(&near);
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    proptest! {
+        #[test]
+        fn compression(input in super::compression_string()) {
+            prop_assert!(crate::compression::parse(&input).is_ok());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! [PyO3](https://docs.rs/pyo3) bindings, to parse and format values from
+//! Python the same way as from Rust.
+//!
+//! The `bity` Python module has a `parse_<family>` and a `format_<family>`
+//! function per unit family. Parsing errors are raised as `bity.ParseError`,
+//! a subclass of `ValueError`, and durations are exchanged as
+//! `datetime.timedelta`s.
+//!
+//! The extension module is built with [maturin](https://www.maturin.rs), or
+//! with:
+//!
+//! ```sh
+//! cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib
+//! ```
+//!
+//! # Examples
+//!
+//! ```python
+//! import bity
+//!
+//! bity.parse_bit("5.2Gb")  # 5200000000
+//! bity.format_byte(1500)  # "1.5kB"
+//!
+//! try:
+//!     bity.parse_byteps("5XB/s")
+//! except bity.ParseError as err:
+//!     print(err)  # invalid unit "XB"
+//! ```
+
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*};
+
+create_exception!(bity, ParseError, PyValueError, "Error raised by the parsing functions.");
+
+impl From<crate::Error<'_>> for PyErr {
+    fn from(err: crate::Error<'_>) -> Self {
+        ParseError::new_err(err.to_string())
+    }
+}
+
+macro_rules! bindings {
+    ($(
+        $(#[cfg($cfg:meta)])?
+        $parse:ident => $parse_fn:path, $format:ident => $format_fn:path: $value:ty,
+    )*) => {
+        $(
+            #[doc = concat!("Parse a string using [`", stringify!($parse_fn), "`].")]
+            $(#[cfg($cfg)])?
+            #[pyfunction]
+            pub fn $parse(input: &str) -> PyResult<$value> {
+                Ok($parse_fn(input)?)
+            }
+
+            #[doc = concat!("Format a value using [`", stringify!($format_fn), "`].")]
+            $(#[cfg($cfg)])?
+            #[pyfunction]
+            pub fn $format(input: $value) -> String {
+                $format_fn(input)
+            }
+        )*
+
+        fn add_functions(module: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(
+                $(#[cfg($cfg)])?
+                module.add_function(wrap_pyfunction!($parse, module)?)?;
+                $(#[cfg($cfg)])?
+                module.add_function(wrap_pyfunction!($format, module)?)?;
+            )*
+            Ok(())
+        }
+    };
+}
+
+bindings! {
+    parse_si => crate::si::parse, format_si => crate::si::format: u64,
+    #[cfg(feature = "bit")]
+    parse_bit => crate::bit::parse, format_bit => crate::bit::format: u64,
+    #[cfg(feature = "byte")]
+    parse_byte => crate::byte::parse, format_byte => crate::byte::format: u64,
+    #[cfg(feature = "packet")]
+    parse_packet => crate::packet::parse, format_packet => crate::packet::format: u64,
+    #[cfg(feature = "bps")]
+    parse_bps => crate::bps::parse, format_bps => crate::bps::format: u64,
+    #[cfg(feature = "byteps")]
+    parse_byteps => crate::byteps::parse, format_byteps => crate::byteps::format: u64,
+    #[cfg(feature = "pps")]
+    parse_pps => crate::pps::parse, format_pps => crate::pps::format: u64,
+    #[cfg(feature = "bpp")]
+    parse_bpp => crate::bpp::parse, format_bpp => crate::bpp::format: u64,
+    #[cfg(feature = "hz")]
+    parse_hz => crate::hz::parse, format_hz => crate::hz::format: u64,
+    #[cfg(feature = "watt")]
+    parse_watt => crate::watt::parse, format_watt => crate::watt::format: u64,
+    #[cfg(feature = "ops")]
+    parse_ops => crate::ops::parse, format_ops => crate::ops::format: u64,
+    #[cfg(feature = "percent")]
+    parse_percent => crate::percent::parse, format_percent => crate::percent::format: u64,
+    #[cfg(feature = "compression")]
+    parse_compression => crate::compression::parse, format_compression => crate::compression::format: u64,
+    #[cfg(feature = "db")]
+    parse_db => crate::db::parse, format_db => crate::db::format: i64,
+    #[cfg(feature = "db")]
+    parse_dbm => crate::db::parse_dbm, format_dbm => crate::db::format_dbm: i64,
+    #[cfg(feature = "duration")]
+    parse_duration => crate::duration::parse, format_duration => crate::duration::format: std::time::Duration,
+}
+
+/// The `bity` Python module.
+#[pymodule]
+pub fn bity(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("ParseError", module.py().get_type::<ParseError>())?;
+    add_functions(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+
+    fn run(code: &std::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "bity").unwrap();
+            super::bity(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("bity", module).unwrap();
+            py.run(code, Some(&globals), None).unwrap();
+        });
+    }
+
+    #[test]
+    fn parse() {
+        run(c_str!(
+            r#"
+from datetime import timedelta
+
+assert bity.parse_bit("5.2Gb") == 5_200_000_000
+assert bity.parse_byteps("5MB/s") == 5_000_000
+assert bity.parse_dbm("-20dBm") == -20_000
+assert bity.parse_duration("1m30s") == timedelta(seconds=90)
+
+try:
+    bity.parse_byteps("5XB/s")
+    assert False
+except bity.ParseError as err:
+    assert isinstance(err, ValueError)
+    assert str(err) == 'invalid unit "XB"'
+"#
+        ));
+    }
+
+    #[test]
+    fn format() {
+        run(c_str!(
+            r#"
+from datetime import timedelta
+
+assert bity.format_byte(1_500) == "1.5kB"
+assert bity.format_db(-1_500) == "-1.5dB"
+assert bity.format_duration(timedelta(seconds=90)) == "1m30s"
+
+try:
+    bity.format_byte(-1)
+    assert False
+except OverflowError:
+    pass
+"#
+        ));
+    }
+}
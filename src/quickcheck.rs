@@ -0,0 +1,233 @@
+//! [`quickcheck`](https://docs.rs/quickcheck) support, for property tests.
+//!
+//! [`Limit`], [`RelativeOrAbsolute`], [`Prefix`] and [`Period`] implement
+//! [`Arbitrary`], and [`ValidInput`] generates strings accepted by the parsing
+//! functions of a unit, shrinking towards short inputs without whitespaces.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{quickcheck::ValidInput, unit::Byte};
+//! use quickcheck::quickcheck;
+//!
+//! fn parse(input: ValidInput<Byte>) -> bool {
+//!     bity::byte::parse(input.as_str()).is_ok()
+//! }
+//!
+//! quickcheck(parse as fn(ValidInput<Byte>) -> bool);
+//! ```
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use quickcheck::{Arbitrary, Gen};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, si::Prefix, unit::Unit, Period};
+
+/// Whitespaces accepted around the number and the unit.
+const WHITESPACES: &[&str] = &["", " ", "  ", "\t"];
+
+/// A string accepted by `U::parse`, generated by its [`Arbitrary`]
+/// implementation.
+///
+/// An arbitrary value (of an arbitrary magnitude) is formatted using
+/// `U::format`, and whitespaces are inserted around the number and the unit.
+/// It shrinks by removing the whitespaces, then towards smaller values.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ValidInput<U> {
+    input: String,
+    value: u64,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit> ValidInput<U> {
+    fn new(value: u64, whitespaces: [&str; 3]) -> Option<Self> {
+        let formatted = U::format(value);
+        // Some units can't parse back their largest values (like compression
+        // ratios, parsed into ppm before being divided).
+        U::parse(&formatted).ok()?;
+        let (number, unit) = formatted.split_at(
+            formatted
+                .bytes()
+                .position(|b| !b.is_ascii_digit() && b != b'.')
+                .unwrap_or(formatted.len()),
+        );
+        let [before, between, after] = whitespaces;
+        let input = if unit.is_empty() {
+            format!("{before}{number}{after}")
+        } else {
+            format!("{before}{number}{between}{unit}{after}")
+        };
+        Some(Self {
+            input,
+            value,
+            unit: PhantomData,
+        })
+    }
+}
+
+impl<U> ValidInput<U> {
+    /// The generated input.
+    pub fn as_str(&self) -> &str {
+        &self.input
+    }
+
+    /// The value formatted into the input (parsing the input may give a
+    /// lower value, as formatting drops the less significant digits).
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Convert into the generated input.
+    pub fn into_string(self) -> String {
+        self.input
+    }
+}
+
+impl<U> Debug for ValidInput<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.input, f)
+    }
+}
+
+impl<U: Unit + Clone + 'static> Arbitrary for ValidInput<U> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let whitespaces = [(); 3].map(|()| *g.choose(WHITESPACES).unwrap_or(&""));
+        loop {
+            if let Some(input) = Self::new(value(g), whitespaces) {
+                return input;
+            }
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let trimmed = Self::new(self.value, ["", "", ""]).filter(|input| input.input != self.input);
+        Box::new(
+            trimmed.into_iter().chain(
+                self.value
+                    .shrink()
+                    .filter_map(|value| Self::new(value, ["", "", ""])),
+            ),
+        )
+    }
+}
+
+/// Generate an integer spread over all the magnitudes, so all the SI
+/// prefixes are equally exercised.
+fn value(g: &mut Gen) -> u64 {
+    let digits = u32::arbitrary(g) % 21;
+    u64::arbitrary(g) % 10u64.saturating_pow(digits).saturating_add(1)
+}
+
+impl<U: Clone + 'static> Arbitrary for Limit<U> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.choose(&[true, false, false, false]) == Some(&true) {
+            Limit::unlimited()
+        } else {
+            Limit::new(value(g))
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.value().shrink().map(Limit::from))
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Clone + 'static> Arbitrary for RelativeOrAbsolute<U> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        if bool::arbitrary(g) {
+            RelativeOrAbsolute::relative(u64::arbitrary(g) % (2 * crate::percent::ONE + 1))
+        } else {
+            RelativeOrAbsolute::absolute(value(g))
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match (self.as_relative(), self.as_absolute()) {
+            (Some(ppm), _) => Box::new(ppm.shrink().map(RelativeOrAbsolute::relative)),
+            (None, value) => Box::new(
+                value
+                    .unwrap_or_default()
+                    .shrink()
+                    .map(RelativeOrAbsolute::absolute),
+            ),
+        }
+    }
+}
+
+impl Arbitrary for Prefix {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[
+            Prefix::None,
+            Prefix::Kilo,
+            Prefix::Mega,
+            Prefix::Giga,
+            Prefix::Tera,
+            Prefix::Peta,
+            Prefix::Exa,
+        ])
+        .unwrap_or(&Prefix::None)
+    }
+}
+
+impl Arbitrary for Period {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[
+            Period::Second,
+            Period::Minute,
+            Period::Hour,
+            Period::Day,
+            Period::Week,
+            Period::Month,
+            Period::Year,
+        ])
+        .unwrap_or(&Period::Second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    use super::ValidInput;
+    use crate::{
+        limit::Limit,
+        unit::{Si, Unit},
+    };
+
+    #[test]
+    fn valid_input() {
+        fn parse(input: ValidInput<Si>) -> bool {
+            Si::parse(input.as_str()).is_ok()
+        }
+        quickcheck(parse as fn(ValidInput<Si>) -> bool);
+
+        #[cfg(feature = "compression")]
+        {
+            use crate::unit::Compression;
+
+            fn parse_compression(input: ValidInput<Compression>) -> bool {
+                Compression::parse(input.as_str()).is_ok()
+            }
+            quickcheck(parse_compression as fn(ValidInput<Compression>) -> bool);
+        }
+    }
+
+    #[test]
+    fn shrink() {
+        let input = ValidInput::<Si>::new(1_500, [" ", "\t", ""]).unwrap();
+        assert_eq!(input.as_str(), " 1.5\tk");
+        let shrunk = input.shrink().collect::<Vec<_>>();
+        assert_eq!(shrunk[0].as_str(), "1.5k");
+        assert!(shrunk[1..].iter().all(|input| input.value() < 1_500));
+        assert!(shrunk.iter().all(|input| Si::parse(input.as_str()).is_ok()));
+
+        let limit = Limit::<Si>::arbitrary(&mut Gen::new(100));
+        assert!(limit.shrink().all(|shrunk| shrunk != limit));
+    }
+}
@@ -0,0 +1,325 @@
+use core::time::Duration;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Conversion into a [`std::time::Duration`], used by the functions working
+/// with durations.
+///
+/// Enabling the `chrono` or `time` features implements this trait for
+/// [`chrono::TimeDelta`] and [`time::Duration`]. Negative durations are
+/// converted to [`Duration::ZERO`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::byteps;
+///
+/// assert_eq!(byteps::from_volume(1_000, Duration::from_secs(2)), 500);
+/// # #[cfg(feature = "chrono")]
+/// assert_eq!(byteps::from_volume(1_000, chrono::TimeDelta::seconds(2)), 500);
+/// # #[cfg(feature = "time")]
+/// assert_eq!(byteps::from_volume(1_000, time::Duration::seconds(2)), 500);
+/// ```
+pub trait IntoDuration {
+    /// Convert into a [`std::time::Duration`].
+    fn into_duration(self) -> Duration;
+}
+
+impl IntoDuration for Duration {
+    fn into_duration(self) -> Duration {
+        self
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl IntoDuration for chrono::TimeDelta {
+    fn into_duration(self) -> Duration {
+        self.to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+#[cfg(feature = "time")]
+impl IntoDuration for time::Duration {
+    fn into_duration(self) -> Duration {
+        self.try_into().unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Divide and round to the nearest integer, `None` if the result doesn't fit
+/// in an `u64`.
+fn checked_div_round(numerator: u128, denominator: u128) -> Option<u64> {
+    u64::try_from(numerator.checked_add(denominator / 2)? / denominator).ok()
+}
+
+/// Per-second rate of a `volume` transferred over `duration`.
+///
+/// A zero `duration` saturates to `u64::MAX` (unless `volume` is also zero).
+pub(crate) fn from_volume(volume: u64, duration: Duration) -> u64 {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return if volume == 0 { 0 } else { u64::MAX };
+    }
+    checked_div_round(u128::from(volume) * NANOS_PER_SEC, nanos).unwrap_or(u64::MAX)
+}
+
+/// Compute the per-second rate between two readings of a monotonic counter
+/// (bytes, packets, ...) taken `elapsed` apart.
+///
+/// The result is rounded to the nearest integer and can directly be formatted
+/// using the rate modules ([`bps::format`](crate::bps::format),
+/// [`pps::format`](crate::pps::format), ...).
+///
+/// Returns `None` if:
+/// - `elapsed` is zero, as no rate can be computed
+/// - `curr_count` is lower than `prev_count`, which usually means that the
+///   counter was reset (use [`rate_between_counter32`] or
+///   [`rate_between_counter64`] for counters that wrap around)
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bps, rate_between};
+///
+/// let rate = rate_between(1_000_000, 26_000_000, Duration::from_secs(10)).unwrap();
+/// assert_eq!(bps::format(rate), "2.5Mb/s");
+///
+/// assert_eq!(rate_between(1_000, 2_000, Duration::ZERO), None);
+/// assert_eq!(rate_between(2_000, 1_000, Duration::from_secs(1)), None);
+/// ```
+pub fn rate_between(prev_count: u64, curr_count: u64, elapsed: impl IntoDuration) -> Option<u64> {
+    let elapsed = elapsed.into_duration();
+    if elapsed.is_zero() {
+        return None;
+    }
+    Some(from_volume(curr_count.checked_sub(prev_count)?, elapsed))
+}
+
+/// Like [`rate_between`] but for 32-bit counters that wrap around to zero
+/// after reaching `u32::MAX` (SNMP `Counter32` semantics).
+///
+/// A `curr_count` lower than `prev_count` is considered as a single
+/// wraparound. As counter resets (discontinuities) can't be distinguished from
+/// wraparounds, they will produce a wrong rate.
+///
+/// Returns `None` if `elapsed` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bps, rate_between_counter32};
+///
+/// let rate = rate_between_counter32(u32::MAX - 999, 1_000, Duration::from_secs(1)).unwrap();
+/// assert_eq!(bps::format(rate), "2kb/s");
+/// ```
+pub fn rate_between_counter32(
+    prev_count: u32,
+    curr_count: u32,
+    elapsed: impl IntoDuration,
+) -> Option<u64> {
+    rate_between(0, u64::from(curr_count.wrapping_sub(prev_count)), elapsed)
+}
+
+/// Like [`rate_between`] but for 64-bit counters that wrap around to zero
+/// after reaching `u64::MAX` (SNMP `Counter64` semantics).
+///
+/// A `curr_count` lower than `prev_count` is considered as a single
+/// wraparound. As counter resets (discontinuities) can't be distinguished from
+/// wraparounds, they will produce a wrong rate.
+///
+/// Returns `None` if `elapsed` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use bity::{bps, rate_between_counter64};
+///
+/// let rate = rate_between_counter64(u64::MAX - 999, 1_000, Duration::from_secs(1)).unwrap();
+/// assert_eq!(bps::format(rate), "2kb/s");
+/// ```
+pub fn rate_between_counter64(
+    prev_count: u64,
+    curr_count: u64,
+    elapsed: impl IntoDuration,
+) -> Option<u64> {
+    rate_between(0, curr_count.wrapping_sub(prev_count), elapsed)
+}
+
+/// Volume transferred at a per-second `rate` over `duration`, `None` on
+/// overflow.
+#[cfg(any(feature = "bps", feature = "byteps", feature = "pps", feature = "ops"))]
+pub(crate) fn checked_volume_over(rate: u64, duration: Duration) -> Option<u64> {
+    checked_div_round(u128::from(rate).checked_mul(duration.as_nanos())?, NANOS_PER_SEC)
+}
+
+/// Time needed to transfer `volume` at a per-second `rate`.
+///
+/// A zero `rate` saturates to `Duration::MAX` (unless `volume` is also zero).
+#[cfg(any(feature = "bps", feature = "byteps", feature = "pps"))]
+pub(crate) fn time_to_transfer(volume: u64, rate: u64) -> Duration {
+    if rate == 0 {
+        return if volume == 0 {
+            Duration::ZERO
+        } else {
+            Duration::MAX
+        };
+    }
+    let numerator = u128::from(volume) * NANOS_PER_SEC;
+    let nanos = (numerator + u128::from(rate) / 2) / u128::from(rate);
+    match u64::try_from(nanos / NANOS_PER_SEC) {
+        Ok(secs) => Duration::new(secs, (nanos % NANOS_PER_SEC) as u32),
+        Err(_) => Duration::MAX,
+    }
+}
+
+/// Format a duration in a compact humantime-like style (`1d2h3m4s`, `250ms`).
+///
+/// Durations of at least a second are truncated to the second, shorter ones
+/// to the millisecond.
+#[cfg(all(
+    feature = "duration",
+    any(feature = "bps", feature = "byteps", feature = "pps")
+))]
+pub(crate) fn format_duration(duration: Duration) -> alloc::string::String {
+    if duration < Duration::from_secs(1) {
+        crate::duration::format(Duration::from_millis(duration.as_millis() as u64))
+    } else {
+        crate::duration::format(Duration::from_secs(duration.as_secs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use super::IntoDuration;
+
+        assert_eq!(
+            chrono::TimeDelta::milliseconds(1_500).into_duration(),
+            Duration::from_millis(1_500)
+        );
+        assert_eq!(chrono::TimeDelta::seconds(-1).into_duration(), Duration::ZERO);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use super::IntoDuration;
+
+        assert_eq!(
+            time::Duration::milliseconds(1_500).into_duration(),
+            Duration::from_millis(1_500)
+        );
+        assert_eq!(time::Duration::seconds(-1).into_duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn from_volume() {
+        assert_eq!(super::from_volume(1_000, Duration::from_secs(1)), 1_000);
+        assert_eq!(super::from_volume(1_000, Duration::from_secs(2)), 500);
+        assert_eq!(super::from_volume(1_000, Duration::from_millis(500)), 2_000);
+        assert_eq!(super::from_volume(0, Duration::from_secs(1)), 0);
+
+        // Rounding.
+        assert_eq!(super::from_volume(2, Duration::from_secs(3)), 1); // 0.66.
+        assert_eq!(super::from_volume(1, Duration::from_secs(3)), 0); // 0.33.
+        assert_eq!(super::from_volume(1, Duration::from_secs(2)), 1); // 0.5.
+
+        // Overflow and zero duration.
+        assert_eq!(super::from_volume(u64::MAX, Duration::from_secs(1)), u64::MAX);
+        assert_eq!(super::from_volume(u64::MAX, Duration::from_nanos(1)), u64::MAX);
+        assert_eq!(super::from_volume(1, Duration::ZERO), u64::MAX);
+        assert_eq!(super::from_volume(0, Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn rate_between() {
+        assert_eq!(super::rate_between(0, 1_000, Duration::from_secs(1)), Some(1_000));
+        assert_eq!(super::rate_between(1_000, 1_000, Duration::from_secs(1)), Some(0));
+        assert_eq!(super::rate_between(1_000, 2_000, Duration::from_millis(300)), Some(3_333));
+        assert_eq!(super::rate_between(0, u64::MAX, Duration::from_millis(1)), Some(u64::MAX));
+
+        // Edge cases.
+        assert_eq!(super::rate_between(0, 0, Duration::ZERO), None);
+        assert_eq!(super::rate_between(0, 1_000, Duration::ZERO), None);
+        assert_eq!(super::rate_between(1_000, 0, Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn rate_between_counter32() {
+        let second = Duration::from_secs(1);
+        assert_eq!(super::rate_between_counter32(0, 1_000, second), Some(1_000));
+        assert_eq!(super::rate_between_counter32(u32::MAX, 0, second), Some(1));
+        assert_eq!(super::rate_between_counter32(u32::MAX - 999, 1_000, second), Some(2_000));
+        assert_eq!(super::rate_between_counter32(1, 0, second), Some(u64::from(u32::MAX)));
+        assert_eq!(super::rate_between_counter32(0, 1_000, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn rate_between_counter64() {
+        let second = Duration::from_secs(1);
+        assert_eq!(super::rate_between_counter64(0, 1_000, second), Some(1_000));
+        assert_eq!(super::rate_between_counter64(u64::MAX, 0, second), Some(1));
+        assert_eq!(super::rate_between_counter64(u64::MAX - 999, 1_000, second), Some(2_000));
+        assert_eq!(super::rate_between_counter64(1, 0, second), Some(u64::MAX));
+        assert_eq!(super::rate_between_counter64(0, 1_000, Duration::ZERO), None);
+    }
+
+    #[cfg(any(feature = "bps", feature = "byteps", feature = "pps", feature = "ops"))]
+    #[test]
+    fn checked_volume_over() {
+        assert_eq!(super::checked_volume_over(1_000, Duration::from_secs(2)), Some(2_000));
+        assert_eq!(super::checked_volume_over(1_000, Duration::from_millis(1_500)), Some(1_500));
+        assert_eq!(super::checked_volume_over(1_000, Duration::ZERO), Some(0));
+        assert_eq!(super::checked_volume_over(0, Duration::MAX), Some(0));
+
+        // Rounding.
+        assert_eq!(super::checked_volume_over(1, Duration::from_millis(400)), Some(0));
+        assert_eq!(super::checked_volume_over(1, Duration::from_millis(500)), Some(1));
+        assert_eq!(super::checked_volume_over(1, Duration::from_millis(600)), Some(1));
+
+        // Overflows.
+        assert_eq!(super::checked_volume_over(u64::MAX, Duration::from_secs(1)), Some(u64::MAX));
+        assert_eq!(super::checked_volume_over(u64::MAX, Duration::from_secs(2)), None);
+        assert_eq!(super::checked_volume_over(u64::MAX, Duration::MAX), None);
+    }
+
+    #[cfg(any(feature = "bps", feature = "byteps", feature = "pps"))]
+    #[test]
+    fn time_to_transfer() {
+        assert_eq!(super::time_to_transfer(1_000, 1_000), Duration::from_secs(1));
+        assert_eq!(super::time_to_transfer(1_000, 2_000), Duration::from_millis(500));
+        assert_eq!(super::time_to_transfer(1, 3), Duration::from_nanos(333_333_333));
+        assert_eq!(super::time_to_transfer(2, 3), Duration::from_nanos(666_666_667));
+        assert_eq!(super::time_to_transfer(0, 1_000), Duration::ZERO);
+
+        // Zero rate and overflows.
+        assert_eq!(super::time_to_transfer(0, 0), Duration::ZERO);
+        assert_eq!(super::time_to_transfer(1, 0), Duration::MAX);
+        assert_eq!(super::time_to_transfer(u64::MAX, 1), Duration::from_secs(u64::MAX));
+    }
+
+    #[cfg(all(
+        feature = "duration",
+        any(feature = "bps", feature = "byteps", feature = "pps")
+    ))]
+    #[test]
+    fn format_duration() {
+        assert_eq!(super::format_duration(Duration::ZERO), "0s");
+        assert_eq!(super::format_duration(Duration::from_millis(250)), "250ms");
+        assert_eq!(super::format_duration(Duration::from_millis(1_250)), "1s");
+        assert_eq!(super::format_duration(Duration::from_secs(750)), "12m30s");
+        assert_eq!(super::format_duration(Duration::from_secs(3_600)), "1h");
+        assert_eq!(super::format_duration(Duration::from_secs(93_784)), "1d2h3m4s");
+    }
+}
@@ -0,0 +1,345 @@
+//! Values relative to a total (`10%`) or absolute (`5GB`), like disk
+//! watermarks.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use bity::{relative::RelativeOrAbsolute, unit::Byte};
+//! use indoc::indoc;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Watermarks {
+//!     low: RelativeOrAbsolute<Byte>,
+//!     high: RelativeOrAbsolute<Byte>,
+//! }
+//!
+//! let watermarks = toml::from_str::<Watermarks>(indoc! {r#"
+//!     low = "10%"
+//!     high = "5GB"
+//! "#})
+//! .unwrap();
+//!
+//! let disk = 100_000_000_000;
+//! assert_eq!(watermarks.low.resolve(disk), 10_000_000_000);
+//! assert_eq!(watermarks.high.resolve(disk), 5_000_000_000);
+//! # }
+//! ```
+
+use core::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{percent, unit::Unit, Error};
+
+/// A value relative to a total (a ppm ratio, parsed from a percent or ppm
+/// string), or absolute (a value of a given [`Unit`]).
+///
+/// It's parsed from a string ending with `%` or `ppm` (relative, refer to
+/// [`percent::parse`]) or a string of the unit (absolute), and formatted back
+/// the same way.
+///
+/// If the `serde` feature is enabled, it implements `Serialize` and
+/// `Deserialize`, also accepting integers (absolute). Non human readable
+/// formats use a `Relative(ppm)` or `Absolute(value)` enum. If the `rkyv`
+/// feature is enabled, it implements rkyv's `Archive`, `Serialize` and
+/// `Deserialize`, and if the `borsh` feature is enabled, `BorshSerialize` and
+/// `BorshDeserialize` (using the same enum).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct RelativeOrAbsolute<U> {
+    value: Value,
+    #[cfg_attr(feature = "borsh", borsh(skip))]
+    unit: PhantomData<U>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename = "RelativeOrAbsolute"))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+enum Value {
+    Relative(u64),
+    Absolute(u64),
+}
+
+impl<U> RelativeOrAbsolute<U> {
+    /// Create a value relative to the total, as a ppm ratio (`1_000_000`
+    /// being the total).
+    pub const fn relative(ppm: u64) -> Self {
+        Self {
+            value: Value::Relative(ppm),
+            unit: PhantomData,
+        }
+    }
+
+    /// Create an absolute value.
+    pub const fn absolute(value: u64) -> Self {
+        Self {
+            value: Value::Absolute(value),
+            unit: PhantomData,
+        }
+    }
+
+    /// Ppm ratio, if relative.
+    pub const fn as_relative(&self) -> Option<u64> {
+        match self.value {
+            Value::Relative(ppm) => Some(ppm),
+            Value::Absolute(_) => None,
+        }
+    }
+
+    /// Absolute value, if absolute.
+    pub const fn as_absolute(&self) -> Option<u64> {
+        match self.value {
+            Value::Relative(_) => None,
+            Value::Absolute(value) => Some(value),
+        }
+    }
+
+    /// Whether the value is relative to the total.
+    pub const fn is_relative(&self) -> bool {
+        matches!(self.value, Value::Relative(_))
+    }
+
+    /// Resolve the value against a given total, using [`percent::of`] for
+    /// relative values (rounded to the nearest integer).
+    ///
+    /// Absolute values are returned as is, even if greater than the total.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{relative::RelativeOrAbsolute, unit::Byte};
+    ///
+    /// assert_eq!(RelativeOrAbsolute::<Byte>::relative(125_000).resolve(2_000), 250);
+    /// assert_eq!(RelativeOrAbsolute::<Byte>::absolute(5_000).resolve(2_000), 5_000);
+    /// ```
+    pub fn resolve(&self, total: u64) -> u64 {
+        match self.value {
+            Value::Relative(ppm) => percent::of(ppm, total),
+            Value::Absolute(value) => value,
+        }
+    }
+}
+
+impl<U: Unit> RelativeOrAbsolute<U> {
+    /// Parse a percent or ppm string (relative) or a string of the unit
+    /// (absolute).
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{relative::RelativeOrAbsolute, unit::Byte, Error};
+    ///
+    /// type Watermark = RelativeOrAbsolute<Byte>;
+    ///
+    /// assert_eq!(Watermark::parse("12.5%").unwrap(), Watermark::relative(125_000));
+    /// assert_eq!(Watermark::parse("300ppm").unwrap(), Watermark::relative(300));
+    /// assert_eq!(Watermark::parse("5GB").unwrap(), Watermark::absolute(5_000_000_000));
+    /// assert!(matches!(Watermark::parse("5Gx"), Err(Error::InvalidUnit("Gx"))));
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, Error<'_>> {
+        let trimmed = input.trim_end();
+        if trimmed.ends_with('%') || trimmed.ends_with("ppm") {
+            percent::parse(input).map(Self::relative)
+        } else {
+            U::parse(input).map(Self::absolute)
+        }
+    }
+}
+
+impl<U: Unit> Display for RelativeOrAbsolute<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Value::Relative(ppm) => f.write_str(&percent::format(ppm)),
+            Value::Absolute(value) => f.write_str(&U::format(value)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<U: Unit> serde::Serialize for RelativeOrAbsolute<U> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            self.value.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, U: Unit> serde::Deserialize<'de> for RelativeOrAbsolute<U> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return Value::deserialize(deserializer).map(|value| Self {
+                value,
+                unit: PhantomData,
+            });
+        }
+        crate::serde::deserialize(
+            deserializer,
+            U::EXPECTING,
+            U::UNITS,
+            U::NUMBERS,
+            |n| u64::try_from(n).ok().map(Self::absolute),
+            Self::parse,
+        )
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<U: schemars::JsonSchema> schemars::JsonSchema for RelativeOrAbsolute<U> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        format!("RelativeOrAbsolute{}", U::schema_name()).into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        format!("bity::relative::RelativeOrAbsolute<{}>", U::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "anyOf": [
+                generator.subschema_for::<U>(),
+                {
+                    "description": r#"a percentage of the total like "10%" or "300ppm""#,
+                    "type": "string",
+                    "pattern": r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:%|ppm)\s*$",
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<U: utoipa::ToSchema> utoipa::PartialSchema for RelativeOrAbsolute<U> {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{AnyOfBuilder, ObjectBuilder, Ref, Type};
+
+        AnyOfBuilder::new()
+            .item(Ref::from_schema_name(U::name()))
+            .item(
+                ObjectBuilder::new()
+                    .description(Some(r#"a percentage of the total like "10%" or "300ppm""#))
+                    .schema_type(Type::String)
+                    .pattern(Some(r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:%|ppm)\s*$"))
+                    .examples(["10%"]),
+            )
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<U: utoipa::ToSchema> utoipa::ToSchema for RelativeOrAbsolute<U> {
+    fn name() -> std::borrow::Cow<'static, str> {
+        format!("RelativeOrAbsolute{}", U::name()).into()
+    }
+
+    fn schemas(
+        schemas: &mut Vec<(String, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>)>,
+    ) {
+        schemas.push((U::name().into_owned(), U::schema()));
+        U::schemas(schemas);
+    }
+}
+
+#[cfg(all(test, feature = "byte", feature = "pps"))]
+mod tests {
+    use super::RelativeOrAbsolute;
+    use crate::{
+        error::Error,
+        unit::{Byte, Pps},
+    };
+
+    type Watermark = RelativeOrAbsolute<Byte>;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Watermark::parse("10%").unwrap(), Watermark::relative(100_000));
+        assert_eq!(Watermark::parse(" 0.5 % ").unwrap(), Watermark::relative(5_000));
+        assert_eq!(Watermark::parse("300ppm").unwrap(), Watermark::relative(300));
+        assert_eq!(Watermark::parse("5GB").unwrap(), Watermark::absolute(5_000_000_000));
+        assert_eq!(Watermark::parse("12").unwrap(), Watermark::absolute(12));
+        assert_eq!(
+            RelativeOrAbsolute::<Pps>::parse("1.5kpps").unwrap(),
+            RelativeOrAbsolute::absolute(1_500)
+        );
+        assert!(matches!(Watermark::parse("12k%"), Err(Error::InvalidUnit("k%"))));
+        assert!(matches!(Watermark::parse("5Gb"), Err(Error::InvalidUnit("Gb"))));
+    }
+
+    #[test]
+    fn accessors() {
+        assert_eq!(Watermark::relative(300).as_relative(), Some(300));
+        assert_eq!(Watermark::relative(300).as_absolute(), None);
+        assert!(Watermark::relative(300).is_relative());
+        assert_eq!(Watermark::absolute(12).as_absolute(), Some(12));
+        assert!(!Watermark::absolute(12).is_relative());
+    }
+
+    #[test]
+    fn resolve() {
+        assert_eq!(Watermark::relative(100_000).resolve(5_000), 500);
+        assert_eq!(Watermark::relative(1).resolve(499_999), 0);
+        assert_eq!(Watermark::relative(1).resolve(500_000), 1);
+        assert_eq!(Watermark::relative(2_000_000).resolve(u64::MAX), u64::MAX);
+        assert_eq!(Watermark::absolute(5_000).resolve(1_000), 5_000);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Watermark::relative(125_000).to_string(), "12.5%");
+        assert_eq!(Watermark::absolute(5_000_000_000).to_string(), "5GB");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Watermarks {
+            low: Watermark,
+            high: Watermark,
+        }
+
+        let watermarks = Watermarks {
+            low: Watermark::relative(100_000),
+            high: Watermark::absolute(5_000_000_000),
+        };
+        assert_eq!(
+            toml::from_str::<Watermarks>("low = \"10%\"\nhigh = 5_000_000_000").unwrap(),
+            watermarks
+        );
+        assert_eq!(toml::to_string(&watermarks).unwrap(), "low = \"10%\"\nhigh = \"5GB\"\n");
+        assert_eq!(
+            toml::from_str::<Watermarks>("low = \"10%\"\nhigh = \"5Gb\"")
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Gb" in "5Gb" (did you mean "GB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        let bytes = bincode::serialize(&watermarks).unwrap();
+        assert_eq!(bincode::deserialize::<Watermarks>(&bytes).unwrap(), watermarks);
+    }
+}
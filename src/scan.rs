@@ -0,0 +1,121 @@
+//! Byte scanning used to split the number and the unit of the inputs.
+//!
+//! With the `swar` feature, eight bytes are checked at once using integer
+//! arithmetic (SIMD within a register), which speeds up the parsing of long
+//! inputs, like zero-padded or space-aligned log tokens.
+
+/// Index of the first ASCII letter of `bytes`, or its length if there is none.
+pub(crate) fn letter_position(bytes: &[u8]) -> usize {
+    #[cfg(feature = "swar")]
+    return swar::position(bytes, swar::letters, |b| b.is_ascii_alphabetic());
+    #[cfg(not(feature = "swar"))]
+    return scalar_position(bytes, |b| b.is_ascii_alphabetic());
+}
+
+/// Index of the first byte of `bytes` that isn't an ASCII digit, or its length
+/// if there is none.
+pub(crate) fn digits_end(bytes: &[u8]) -> usize {
+    #[cfg(feature = "swar")]
+    return swar::position(bytes, swar::non_digits, |b| !b.is_ascii_digit());
+    #[cfg(not(feature = "swar"))]
+    return scalar_position(bytes, |b| !b.is_ascii_digit());
+}
+
+#[cfg_attr(feature = "swar", allow(dead_code))]
+fn scalar_position(bytes: &[u8], matches: impl Fn(u8) -> bool) -> usize {
+    bytes
+        .iter()
+        .position(|&b| matches(b))
+        .unwrap_or(bytes.len())
+}
+
+#[cfg(feature = "swar")]
+mod swar {
+    const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HIGHS: u64 = u64::from_ne_bytes([0x80; 8]);
+    const CASE: u64 = u64::from_ne_bytes([0x20; 8]);
+
+    /// Index of the first byte matching `mask` (eight bytes at a time) and
+    /// `matches` (for the remaining bytes).
+    pub(super) fn position(bytes: &[u8], mask: fn(u64) -> u64, matches: fn(u8) -> bool) -> usize {
+        let mut chunks = bytes.chunks_exact(8);
+        let mut offset = 0;
+        for chunk in chunks.by_ref() {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            let found = mask(u64::from_le_bytes(word));
+            if found != 0 {
+                return offset + (found.trailing_zeros() / 8) as usize;
+            }
+            offset += 8;
+        }
+        offset + super::scalar_position(chunks.remainder(), matches)
+    }
+
+    /// High bit of the bytes that are at least `min`, for bytes below `0x80`
+    /// (which can't carry into the next byte).
+    fn at_least(word: u64, min: u8) -> u64 {
+        word.wrapping_add(ONES * u64::from(0x80 - min)) & HIGHS
+    }
+
+    /// High bit of the ASCII letters of `word`.
+    pub(super) fn letters(word: u64) -> u64 {
+        let ascii = word & !HIGHS;
+        // Lowercase the letters, without moving other bytes into their range.
+        let lower = ascii | CASE;
+        at_least(lower, b'a') & !at_least(lower, b'z' + 1) & !word & HIGHS
+    }
+
+    /// High bit of the bytes of `word` that aren't ASCII digits.
+    pub(super) fn non_digits(word: u64) -> u64 {
+        let ascii = word & !HIGHS;
+        let digits = at_least(ascii, b'0') & !at_least(ascii, b'9' + 1) & !word;
+        !digits & HIGHS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn letter_position() {
+        assert_eq!(super::letter_position(b""), 0);
+        assert_eq!(super::letter_position(b"12"), 2);
+        assert_eq!(super::letter_position(b"1.5kB"), 3);
+        assert_eq!(super::letter_position(b"000000000001.5 kB"), 15);
+        assert_eq!(super::letter_position(b"1234567890123456@[`{Z"), 20);
+        assert_eq!(super::letter_position("12345678é".as_bytes()), 10);
+    }
+
+    #[test]
+    fn digits_end() {
+        assert_eq!(super::digits_end(b""), 0);
+        assert_eq!(super::digits_end(b"12"), 2);
+        assert_eq!(super::digits_end(b"1.5"), 1);
+        assert_eq!(super::digits_end(b"0123456789012345/"), 16);
+        assert_eq!(super::digits_end("12345678é".as_bytes()), 8);
+        assert_eq!(super::digits_end(b"12345678:"), 8);
+    }
+
+    #[cfg(feature = "swar")]
+    #[test]
+    fn scalar() {
+        // Every pair of bytes, at every position of a 16 bytes input.
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                for position in [0, 7, 8, 14] {
+                    let mut bytes = *b"0000000000000000";
+                    bytes[position] = a;
+                    bytes[position + 1] = b;
+                    assert_eq!(
+                        super::letter_position(&bytes),
+                        super::scalar_position(&bytes, |b| b.is_ascii_alphabetic())
+                    );
+                    assert_eq!(
+                        super::digits_end(&bytes),
+                        super::scalar_position(&bytes, |b| !b.is_ascii_digit())
+                    );
+                }
+            }
+        }
+    }
+}
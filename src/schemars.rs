@@ -0,0 +1,76 @@
+use schemars::{json_schema, Schema};
+
+/// Schema of an integer or a string matching `pattern`, as accepted by the
+/// deserializers of the unit modules.
+pub(crate) fn schema(description: &str, pattern: &str) -> Schema {
+    json_schema!({
+        "description": description,
+        "anyOf": [
+            { "type": "integer", "minimum": 0 },
+            { "type": "string", "pattern": pattern },
+        ],
+    })
+}
+
+#[cfg(all(test, feature = "byte"))]
+mod tests {
+    use schemars::SchemaGenerator;
+    use serde_json::json;
+
+    #[test]
+    fn schema() {
+        assert_eq!(
+            crate::byte::json_schema(&mut SchemaGenerator::default()).to_value(),
+            json!({
+                "description": r#"a data size in bytes like "1.5kB", "12MB" or 1500"#,
+                "anyOf": [
+                    { "type": "integer", "minimum": 0 },
+                    {
+                        "type": "string",
+                        "pattern": crate::byte::PATTERN,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[cfg(all(feature = "bps", feature = "percent"))]
+    #[test]
+    fn derive() {
+        use schemars::JsonSchema;
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Configuration {
+            #[schemars(schema_with = "crate::bps::json_schema")]
+            bandwidth: u64,
+            #[schemars(with = "Option<crate::unit::Byte>")]
+            quota: Option<u64>,
+            limit: crate::limit::ByteLimit,
+            watermark: crate::relative::RelativeOrAbsolute<crate::unit::Byte>,
+        }
+
+        let schema = schemars::schema_for!(Configuration).to_value();
+        assert_eq!(
+            schema["properties"]["bandwidth"]["description"],
+            r#"a data-rate like "1.5kb/s", "12Mbps" or 1500"#
+        );
+        assert_eq!(
+            schema["properties"]["quota"],
+            json!({ "anyOf": [{ "$ref": "#/$defs/Byte" }, { "type": "null" }] })
+        );
+        assert_eq!(schema["properties"]["limit"], json!({ "$ref": "#/$defs/ByteLimit" }));
+        assert_eq!(
+            schema["$defs"]["ByteLimit"]["anyOf"][1],
+            json!({ "enum": ["unlimited", "none"] })
+        );
+        assert_eq!(
+            schema["$defs"]["RelativeOrAbsoluteByte"]["anyOf"][1]["pattern"],
+            r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*(?:%|ppm)\s*$"
+        );
+        assert_eq!(
+            schema["$defs"]["Byte"]["description"],
+            r#"a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+    }
+}
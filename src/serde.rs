@@ -1,26 +1,1424 @@
-use serde::Deserialize;
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Formatter},
+    iter,
+    marker::PhantomData,
+};
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-pub(crate) enum IntOrString {
-    Int(u64),
+use serde::{
+    de::{self, Unexpected},
+    ser::{self, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::Error;
+
+/// Error quoting the input failing to be parsed, suggesting the closest of
+/// `units` if the unit is invalid.
+fn parse_error<E>(err: Error<'_>, input: &str, expecting: &str, units: &[&str]) -> E
+where
+    E: de::Error,
+{
+    match err.suggestion(units) {
+        Some(unit) => E::custom(format_args!(
+            "{err} in {input:?} (did you mean {unit:?}?), expected {expecting}"
+        )),
+        None => E::custom(format_args!("{err} in {input:?}, expected {expecting}")),
+    }
+}
+
+/// How bare numbers (integers and floats) are deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numbers {
+    /// As is, floats being only accepted if they hold an exact integer (like
+    /// `1500` bytes).
+    Exact,
+    /// Parsed like strings without unit (like `0.5` being a ratio for
+    /// percents).
+    Parsed,
+}
+
+/// Visitor accepting integers (converted using `from_int`), strings and
+/// structured `{ value, unit }` maps (parsed using `parse`). Floats are
+/// accepted if they hold an exact integer.
+///
+/// With [`Numbers::Parsed`], integers and floats are parsed using `parse`
+/// instead.
+struct Visitor<I, P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    from_int: I,
+    parse: P,
+}
+
+impl<I, P> Visitor<I, P> {
+    /// Parse a number like a string without unit, reporting `unexpected` on
+    /// failure.
+    fn parse_number<T, E>(
+        self,
+        number: impl fmt::Display,
+        unexpected: Unexpected<'_>,
+    ) -> Result<T, E>
+    where
+        P: FnOnce(&str) -> Result<T, Error<'_>>,
+        E: de::Error,
+    {
+        let expecting = self.expecting;
+        (self.parse)(&format!("{number}")).map_err(|_| E::invalid_value(unexpected, &expecting))
+    }
+}
+
+impl<'de, T, I, P> de::Visitor<'de> for Visitor<I, P>
+where
+    I: FnOnce(i128) -> Option<T>,
+    P: FnOnce(&str) -> Result<T, Error<'_>>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        if self.numbers == Numbers::Parsed {
+            return self.parse_number(v, Unexpected::Unsigned(v));
+        }
+        let expecting = self.expecting;
+        (self.from_int)(i128::from(v))
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &expecting))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        if self.numbers == Numbers::Parsed {
+            return self.parse_number(v, Unexpected::Signed(v));
+        }
+        let expecting = self.expecting;
+        (self.from_int)(i128::from(v))
+            .ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &expecting))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        // Floats are displayed without exponent, so they can be parsed.
+        if self.numbers == Numbers::Parsed {
+            return self.parse_number(v, Unexpected::Float(v));
+        }
+        // Only accept floats holding an exact integer (`1.5e6`).
+        let expecting = self.expecting;
+        let n = v as i128;
+        if n as f64 != v {
+            return Err(E::invalid_value(Unexpected::Float(v), &expecting));
+        }
+        (self.from_int)(n).ok_or_else(|| E::invalid_value(Unexpected::Float(v), &expecting))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        (self.parse)(v).map_err(|err| parse_error(err, v, self.expecting, self.units))
+    }
+
+    // Strings borrowed from the input (e.g. JSON read from a slice) are parsed
+    // in place, without allocating a `String`.
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(v)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<T, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        visit_structured(map, self.expecting, self.units, self.parse)
+    }
+}
+
+/// Deserialize a given integer or string, using `from_int` for integers
+/// (`None` meaning out of range) and `parse` for strings.
+pub(crate) fn deserialize<'de, D, T>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    from_int: impl FnOnce(i128) -> Option<T>,
+    parse: impl FnOnce(&str) -> Result<T, Error<'_>>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(Visitor {
+        expecting,
+        units,
+        numbers,
+        from_int,
+        parse,
+    })
+}
+
+/// Deserialize a given integer or string into an `u64`, using `parse` for
+/// strings, or a plain integer if the deserializer isn't human readable.
+/// Errors quote the input, followed by `expecting` (like `a data size in bytes
+/// like "1.5kB"`).
+pub fn deserialize_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl FnOnce(&str) -> Result<u64, Error<'_>>,
+) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return u64::deserialize(deserializer);
+    }
+    deserialize(deserializer, expecting, units, numbers, |n| u64::try_from(n).ok(), parse)
+}
+
+/// Inclusive bounds, formatted using `format` in error messages.
+struct Bounds<F> {
+    min: u64,
+    max: u64,
+    format: F,
+}
+
+impl<F> Bounds<F>
+where
+    F: Fn(u64) -> String,
+{
+    fn check<E>(&self, value: u64, input: impl fmt::Display) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        if (self.min..=self.max).contains(&value) {
+            return Ok(value);
+        }
+        Err(E::custom(format_args!(
+            "{input} is out of range, expected between {} and {}",
+            (self.format)(self.min),
+            (self.format)(self.max)
+        )))
+    }
+}
+
+/// Visitor accepting integers and strings (parsed using `parse`) within
+/// `bounds`, quoting the input and the bounds otherwise.
+struct BoundedVisitor<P, F> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    bounds: Bounds<F>,
+    parse: P,
+}
+
+impl<P, F> BoundedVisitor<P, F> {
+    /// Unbounded visitor converting the input.
+    fn inner(
+        expecting: &'static str,
+        units: &'static [&'static str],
+        numbers: Numbers,
+        parse: P,
+    ) -> Visitor<fn(i128) -> Option<u64>, P> {
+        Visitor {
+            expecting,
+            units,
+            numbers,
+            from_int: |n| u64::try_from(n).ok(),
+            parse,
+        }
+    }
+}
+
+impl<'de, P, F> de::Visitor<'de> for BoundedVisitor<P, F>
+where
+    P: FnOnce(&str) -> Result<u64, Error<'_>>,
+    F: Fn(u64) -> String,
+{
+    type Value = u64;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        let value =
+            Self::inner(self.expecting, self.units, self.numbers, self.parse).visit_u64(v)?;
+        self.bounds.check(value, v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        let value =
+            Self::inner(self.expecting, self.units, self.numbers, self.parse).visit_i64(v)?;
+        self.bounds.check(value, v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        let value =
+            Self::inner(self.expecting, self.units, self.numbers, self.parse).visit_f64(v)?;
+        self.bounds.check(value, v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        let value =
+            Self::inner(self.expecting, self.units, self.numbers, self.parse).visit_str(v)?;
+        self.bounds.check(value, format_args!("{v:?}"))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(v)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<u64, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let value =
+            Self::inner(self.expecting, self.units, self.numbers, self.parse).visit_map(map)?;
+        self.bounds
+            .check(value, format_args!("{:?}", (self.bounds.format)(value)))
+    }
+}
+
+/// Deserialize a given integer or string into an `u64` within `min..=max`,
+/// using `parse` for strings and `format` for the bounds in error messages.
+#[allow(clippy::too_many_arguments)]
+pub fn deserialize_bounded_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    min: u64,
+    max: u64,
+    parse: impl FnOnce(&str) -> Result<u64, Error<'_>>,
+    format: impl Fn(u64) -> String,
+) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bounds = Bounds { min, max, format };
+    if !deserializer.is_human_readable() {
+        let value = u64::deserialize(deserializer)?;
+        return bounds.check(value, value);
+    }
+    deserializer.deserialize_any(BoundedVisitor {
+        expecting,
+        units,
+        numbers,
+        bounds,
+        parse,
+    })
+}
+
+/// Visitor accepting only strings (parsed using `parse`), rejecting integers
+/// with a hint built using `format`.
+struct StrictVisitor<P, F> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    parse: P,
+    format: F,
+}
+
+impl<P, F> StrictVisitor<P, F>
+where
+    F: FnOnce(u64) -> String,
+{
+    fn missing_unit<E>(self, v: impl fmt::Display, n: Option<u64>) -> E
+    where
+        E: de::Error,
+    {
+        match n {
+            Some(n) => E::custom(format_args!(
+                "missing unit in {v}, use a string like {:?}",
+                (self.format)(n)
+            )),
+            None => E::custom(format_args!("missing unit in {v}, use a SI prefixed string")),
+        }
+    }
+}
+
+impl<'de, P, F> de::Visitor<'de> for StrictVisitor<P, F>
+where
+    P: FnOnce(&str) -> Result<u64, Error<'_>>,
+    F: FnOnce(u64) -> String,
+{
+    type Value = u64;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.missing_unit(v, Some(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.missing_unit(v, u64::try_from(v).ok()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        Err(self.missing_unit(v, None))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        (self.parse)(v).map_err(|err| parse_error(err, v, self.expecting, self.units))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<u64, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(v)
+    }
+}
+
+/// Deserialize a given string into an `u64`, using `parse`, rejecting integers
+/// (to avoid ambiguities, like bits and bytes) with a hint built using
+/// `format`. Non human readable deserializers still use plain integers.
+pub fn deserialize_strict_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    parse: impl FnOnce(&str) -> Result<u64, Error<'_>>,
+    format: impl FnOnce(u64) -> String,
+) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return u64::deserialize(deserializer);
+    }
+    deserializer.deserialize_any(StrictVisitor {
+        expecting,
+        units,
+        parse,
+        format,
+    })
+}
+
+/// Serialize a given `u64` into a string using `format`, or as a plain integer
+/// if the serializer isn't human readable (like bincode or postcard).
+pub fn serialize_with<S>(
+    value: u64,
+    serializer: S,
+    format: impl FnOnce(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format(value))
+    } else {
+        serializer.serialize_u64(value)
+    }
+}
+
+/// A `u64` serialized using [`serialize_with`].
+struct Formatted<'a, F>(u64, &'a F);
+
+impl<F> Serialize for Formatted<'_, F>
+where
+    F: Fn(u64) -> String,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_with(self.0, serializer, self.1)
+    }
+}
+
+/// Serialize a given `Option<u64>` into an optional string, using `format`
+/// (refer to [`serialize_with`] for non human readable serializers).
+pub fn serialize_option_with<S>(
+    value: Option<u64>,
+    serializer: S,
+    format: impl Fn(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_some(&Formatted(value, &format)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Visitor accepting `null` (`None`), the `keywords` strings (`None`, like
+/// `unlimited`), integers and strings (parsed using `parse`).
+struct OptionVisitor<P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    keywords: &'static [&'static str],
+    parse: P,
+}
+
+impl<'de, P> de::Visitor<'de> for OptionVisitor<P>
+where
+    P: FnOnce(&str) -> Result<u64, Error<'_>>,
+{
+    type Value = Option<u64>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)?;
+        for keyword in self.keywords {
+            write!(f, ", {keyword:?}")?;
+        }
+        f.write_str(" or null")
+    }
+
+    fn visit_none<E>(self) -> Result<Option<u64>, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Option<u64>, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return u64::deserialize(deserializer).map(Some);
+        }
+        deserialize(
+            deserializer,
+            self.expecting,
+            self.units,
+            self.numbers,
+            |n| u64::try_from(n).ok().map(Some),
+            |input| match input.trim() {
+                keyword if self.keywords.contains(&keyword) => Ok(None),
+                _ => (self.parse)(input).map(Some),
+            },
+        )
+    }
+}
+
+/// Deserialize a given `null`, `unlimited` string, integer or string into an
+/// `Option<u64>`, using `parse` for strings.
+pub fn deserialize_option_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl FnOnce(&str) -> Result<u64, Error<'_>>,
+) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(OptionVisitor {
+        expecting,
+        units,
+        numbers,
+        keywords: &["unlimited"],
+        parse,
+    })
+}
+
+/// Serialize a given limit (`None` meaning unlimited) into the `unlimited`
+/// string or a string using `format` (refer to [`serialize_option_with`] for
+/// non human readable serializers).
+pub(crate) fn serialize_limit_with<S>(
+    value: Option<u64>,
+    serializer: S,
+    format: impl Fn(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        None if serializer.is_human_readable() => serializer.serialize_str("unlimited"),
+        _ => serialize_option_with(value, serializer, format),
+    }
+}
+
+/// Deserialize a given `null`, `unlimited` or `none` string, integer or string
+/// into a limit (`None` meaning unlimited), using `parse` for strings.
+pub(crate) fn deserialize_limit_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl FnOnce(&str) -> Result<u64, Error<'_>>,
+) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(OptionVisitor {
+        expecting,
+        units,
+        numbers,
+        keywords: &["unlimited", "none"],
+        parse,
+    })
+}
+
+/// Seed deserializing a sequence element using `parse`.
+struct Element<P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: P,
+}
+
+impl<'de, P> de::DeserializeSeed<'de> for Element<P>
+where
+    P: FnOnce(&str) -> Result<u64, Error<'_>>,
+{
+    type Value = u64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with(deserializer, self.expecting, self.units, self.numbers, self.parse)
+    }
+}
+
+/// Visitor accepting a sequence of integers and strings (parsed using
+/// `parse`).
+struct SeqVisitor<P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: P,
+}
+
+impl<'de, P> de::Visitor<'de> for SeqVisitor<P>
+where
+    P: Fn(&str) -> Result<u64, Error<'_>> + Copy,
+{
+    type Value = Vec<u64>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of {}", self.expecting)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Vec<u64>, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4_096));
+        while let Some(value) = seq.next_element_seed(Element {
+            expecting: self.expecting,
+            units: self.units,
+            numbers: self.numbers,
+            parse: self.parse,
+        })? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+/// Deserialize a given sequence of integers or strings into a `Vec<u64>`,
+/// using `parse` for strings.
+pub fn deserialize_vec_with<'de, D>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl Fn(&str) -> Result<u64, Error<'_>> + Copy,
+) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(SeqVisitor {
+        expecting,
+        units,
+        numbers,
+        parse,
+    })
+}
+
+/// Serialize a given slice of `u64` into a sequence of strings, using `format`
+/// (refer to [`serialize_with`] for non human readable serializers).
+pub fn serialize_vec_with<S>(
+    values: &[u64],
+    serializer: S,
+    format: impl Fn(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(values.iter().map(|value| Formatted(*value, &format)))
+}
+
+/// Visitor accepting a map of keys and integers or strings (parsed using
+/// `parse`).
+struct MapValuesVisitor<K, M, P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: P,
+    marker: PhantomData<fn() -> (K, M)>,
+}
+
+impl<'de, K, M, P> de::Visitor<'de> for MapValuesVisitor<K, M, P>
+where
+    K: Deserialize<'de>,
+    M: Default + Extend<(K, u64)>,
+    P: Fn(&str) -> Result<u64, Error<'_>> + Copy,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a map of {}", self.expecting)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut values = M::default();
+        while let Some(key) = map.next_key()? {
+            let value = map.next_value_seed(Element {
+                expecting: self.expecting,
+                units: self.units,
+                numbers: self.numbers,
+                parse: self.parse,
+            })?;
+            values.extend(iter::once((key, value)));
+        }
+        Ok(values)
+    }
+}
+
+/// Deserialize a given map of integers or strings into a map of `u64` (like
+/// `HashMap<K, u64>` or `BTreeMap<K, u64>`), using `parse` for strings.
+pub fn deserialize_map_values_with<'de, D, K, M>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl Fn(&str) -> Result<u64, Error<'_>> + Copy,
+) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    M: Default + Extend<(K, u64)>,
+{
+    deserializer.deserialize_map(MapValuesVisitor {
+        expecting,
+        units,
+        numbers,
+        parse,
+        marker: PhantomData,
+    })
+}
+
+/// Serialize a given map of `u64` into a map of strings, using `format` (refer
+/// to [`serialize_with`] for non human readable serializers).
+pub fn serialize_map_values_with<'a, S, K, M>(
+    values: &'a M,
+    serializer: S,
+    format: impl Fn(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + 'a,
+    &'a M: IntoIterator<Item = (&'a K, &'a u64)>,
+{
+    serializer.collect_map(
+        values
+            .into_iter()
+            .map(|(key, value)| (key, Formatted(*value, &format))),
+    )
+}
+
+/// Visitor accepting a map with integer or string (parsed using `parse`) keys.
+struct MapKeysVisitor<V, M, P> {
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: P,
+    marker: PhantomData<fn() -> (V, M)>,
+}
+
+impl<'de, V, M, P> de::Visitor<'de> for MapKeysVisitor<V, M, P>
+where
+    V: Deserialize<'de>,
+    M: Default + Extend<(u64, V)>,
+    P: Fn(&str) -> Result<u64, Error<'_>> + Copy,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a map with keys being {}", self.expecting)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut values = M::default();
+        while let Some(key) = map.next_key_seed(Element {
+            expecting: self.expecting,
+            units: self.units,
+            numbers: self.numbers,
+            parse: self.parse,
+        })? {
+            let value = map.next_value()?;
+            values.extend(iter::once((key, value)));
+        }
+        Ok(values)
+    }
+}
+
+/// Deserialize a given map with integer or string keys into a map keyed by
+/// `u64` (like `HashMap<u64, V>` or `BTreeMap<u64, V>`), using `parse` for
+/// strings.
+pub fn deserialize_map_keys_with<'de, D, V, M>(
+    deserializer: D,
+    expecting: &'static str,
+    units: &'static [&'static str],
+    numbers: Numbers,
+    parse: impl Fn(&str) -> Result<u64, Error<'_>> + Copy,
+) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+    M: Default + Extend<(u64, V)>,
+{
+    deserializer.deserialize_map(MapKeysVisitor {
+        expecting,
+        units,
+        numbers,
+        parse,
+        marker: PhantomData,
+    })
+}
+
+/// Serialize a given map keyed by `u64` into a map with string keys, using
+/// `format` (refer to [`serialize_with`] for non human readable serializers).
+///
+/// Keys that `parse` doesn't read back as is (as `format` rounds them) are
+/// serialized as plain integer strings, so distinct keys never collide.
+pub fn serialize_map_keys_with<'a, S, V, M>(
+    values: &'a M,
+    serializer: S,
+    format: impl Fn(u64) -> String,
+    parse: impl Fn(&str) -> Result<u64, Error<'_>>,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize + 'a,
+    &'a M: IntoIterator<Item = (&'a u64, &'a V)>,
+{
+    let format = |key| {
+        let formatted = format(key);
+        if parse(&formatted).is_ok_and(|parsed| parsed == key) {
+            formatted
+        } else {
+            key.to_string()
+        }
+    };
+    serializer.collect_map(
+        values
+            .into_iter()
+            .map(|(key, value)| (Formatted(*key, &format), value)),
+    )
+}
+
+/// Number of a structured `{ value, unit }` map.
+enum Number {
+    Integer(i128),
+    Float(f64),
     String(String),
 }
 
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Integer(n) => write!(f, "{n}"),
+            Number::Float(n) => write!(f, "{n}"),
+            Number::String(n) => f.write_str(n),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NumberVisitor;
+
+        impl de::Visitor<'_> for NumberVisitor {
+            type Value = Number;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a number")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Number, E> {
+                Ok(Number::Integer(i128::from(v)))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Number, E> {
+                Ok(Number::Integer(i128::from(v)))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Number, E> {
+                Ok(Number::Float(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Number, E> {
+                Ok(Number::String(v.to_owned()))
+            }
+        }
+
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+/// Read a structured `{ value, unit }` map (`unit` being optional) and parse
+/// its concatenation using `parse`.
+fn visit_structured<'de, A, T>(
+    mut map: A,
+    expecting: &str,
+    units: &[&str],
+    parse: impl FnOnce(&str) -> Result<T, Error<'_>>,
+) -> Result<T, A::Error>
+where
+    A: de::MapAccess<'de>,
+{
+    const FIELDS: &[&str] = &["value", "unit"];
+
+    let (mut value, mut unit) = (None::<Number>, None::<String>);
+    while let Some(key) = map.next_key::<String>()? {
+        match key.as_str() {
+            "value" if value.is_some() => return Err(de::Error::duplicate_field("value")),
+            "value" => value = Some(map.next_value()?),
+            "unit" if unit.is_some() => return Err(de::Error::duplicate_field("unit")),
+            "unit" => unit = Some(map.next_value()?),
+            _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+        }
+    }
+    let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+    let input = format!("{value}{}", unit.unwrap_or_default());
+    parse(&input).map_err(|err| parse_error(err, &input, expecting, units))
+}
+
+/// Serialize a given `u64` into a structured `{ value, unit }` map (like `{
+/// "value": 88.1, "unit": "TB" }`), splitting the string formatted using
+/// `format` after its number, or as a plain integer if the serializer isn't
+/// human readable.
+pub fn serialize_structured_with<S>(
+    value: u64,
+    serializer: S,
+    format: impl FnOnce(u64) -> String,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return serializer.serialize_u64(value);
+    }
+
+    let formatted = format(value);
+    let (number, unit) = formatted.split_at(
+        formatted
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(formatted.len()),
+    );
+    let mut map = serializer.serialize_struct("Quantity", 2)?;
+    match number.parse::<u64>() {
+        Ok(number) => map.serialize_field("value", &number)?,
+        Err(_) => {
+            map.serialize_field("value", &number.parse::<f64>().map_err(ser::Error::custom)?)?
+        }
+    }
+    map.serialize_field("unit", unit)?;
+    map.end()
+}
+
+/// Generate the `Bounded` and `Quantized` types and the `option`, `vec`, `map_values`,
+/// `map_keys`, `as_int`, `strict` and `structured` submodules of a unit module, using its
+/// `parse` and `format` functions.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_submodules {
+    () => {
+        $crate::__serde_submodules!(as_int:);
+    };
+    (as_int: $(#[$as_int_doc:meta])*) => {
+        /// Serde helpers for `u64` fields bounded to `MIN..=MAX`, usable with
+        /// `#[serde(with = "...::Bounded::<MIN, MAX>")]`.
+        ///
+        /// Out of range values fail to deserialize, with a message quoting the
+        /// input and the bounds.
+        #[derive(Debug, Clone, Copy)]
+        pub struct Bounded<const MIN: u64, const MAX: u64>;
+
+        impl<const MIN: u64, const MAX: u64> Bounded<MIN, MAX> {
+            /// Serialize a given `u64` into a SI prefixed string.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                serialize(value, serializer)
+            }
+
+            /// Deserialize a given integer or SI prefixed string into an `u64`,
+            /// failing if it isn't within `MIN..=MAX`.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::deserialize_bounded_with(
+                    deserializer,
+                    EXPECTING,
+                    UNITS,
+                    NUMBERS,
+                    MIN,
+                    MAX,
+                    parse,
+                    format,
+                )
+            }
+        }
+
+        /// Serde helpers for `u64` fields serialized after rounding to
+        /// `DIGITS` significant digits (using
+        /// [`si::quantize`]($crate::si::quantize)), usable with
+        /// `#[serde(with = "...::Quantized::<DIGITS>")]`.
+        ///
+        /// This is lossy on purpose (`5_099_876` is serialized as `5.1M` with
+        /// two digits), for tools rewriting configurations and preferring
+        /// stable and tidy values over noisy exact ones.
+        #[derive(Debug, Clone, Copy)]
+        pub struct Quantized<const DIGITS: u32>;
+
+        impl<const DIGITS: u32> Quantized<DIGITS> {
+            /// Serialize a given `u64`, rounded to `DIGITS` significant
+            /// digits, into a SI prefixed string.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                serialize(&$crate::si::quantize(*value, DIGITS), serializer)
+            }
+
+            /// Deserialize a given integer or SI prefixed string into an `u64`
+            /// (as is).
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                deserialize(deserializer)
+            }
+        }
+
+        /// Serde helpers for `Option<u64>` fields.
+        ///
+        /// `None` is serialized as `null` (use `#[serde(skip_serializing_if =
+        /// "Option::is_none")]` for formats without `null`, like TOML). `null`
+        /// and the `"unlimited"` string are deserialized as `None`. Use
+        /// `#[serde(default)]` to also accept missing fields.
+        pub mod option {
+            /// Serialize a given `Option<u64>` into an optional SI prefixed
+            /// string.
+            pub fn serialize<S>(
+                value: &::core::option::Option<u64>,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serialize_option_with(*value, serializer, super::format)
+            }
+
+            /// Deserialize a given `null`, `"unlimited"`, integer or SI
+            /// prefixed string into an `Option<u64>`.
+            pub fn deserialize<'de, D>(
+                deserializer: D,
+            ) -> ::core::result::Result<::core::option::Option<u64>, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::deserialize_option_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    super::NUMBERS,
+                    super::parse,
+                )
+            }
+        }
+
+        /// Serde helpers for `Vec<u64>` fields, serialized as sequences of SI
+        /// prefixed strings.
+        pub mod vec {
+            /// Serialize a given slice of `u64` into a sequence of SI prefixed
+            /// strings.
+            pub fn serialize<S>(
+                value: &[u64],
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serialize_vec_with(value, serializer, super::format)
+            }
+
+            /// Deserialize a given sequence of integers or SI prefixed strings
+            /// into a `Vec<u64>`.
+            pub fn deserialize<'de, D>(
+                deserializer: D,
+            ) -> ::core::result::Result<$crate::__private::Vec<u64>, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::deserialize_vec_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    super::NUMBERS,
+                    super::parse,
+                )
+            }
+        }
+
+        /// Serde helpers for maps of `u64` (like `HashMap<K, u64>` or
+        /// `BTreeMap<K, u64>`), serialized as maps of SI prefixed strings.
+        pub mod map_values {
+            /// Serialize a given map of `u64` into a map of SI prefixed
+            /// strings.
+            pub fn serialize<'a, S, K, M>(
+                value: &'a M,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+                K: $crate::__private::serde::Serialize + 'a,
+                &'a M: ::core::iter::IntoIterator<Item = (&'a K, &'a u64)>,
+            {
+                $crate::__private::serialize_map_values_with(value, serializer, super::format)
+            }
+
+            /// Deserialize a given map of integers or SI prefixed strings into
+            /// a map of `u64`.
+            pub fn deserialize<'de, D, K, M>(deserializer: D) -> ::core::result::Result<M, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+                K: $crate::__private::serde::Deserialize<'de>,
+                M: ::core::default::Default + ::core::iter::Extend<(K, u64)>,
+            {
+                $crate::__private::deserialize_map_values_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    super::NUMBERS,
+                    super::parse,
+                )
+            }
+        }
+
+        /// Serde helpers for maps keyed by `u64` (like `HashMap<u64, V>` or
+        /// `BTreeMap<u64, V>`), serialized with SI prefixed string keys (or
+        /// plain integer strings for keys that would be rounded).
+        pub mod map_keys {
+            /// Serialize a given map keyed by `u64` into a map with SI prefixed
+            /// string keys.
+            pub fn serialize<'a, S, V, M>(
+                value: &'a M,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+                V: $crate::__private::serde::Serialize + 'a,
+                &'a M: ::core::iter::IntoIterator<Item = (&'a u64, &'a V)>,
+            {
+                $crate::__private::serialize_map_keys_with(
+                    value,
+                    serializer,
+                    super::format,
+                    super::parse,
+                )
+            }
+
+            /// Deserialize a given map with integer or SI prefixed string keys
+            /// into a map keyed by `u64`.
+            pub fn deserialize<'de, D, V, M>(deserializer: D) -> ::core::result::Result<M, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+                V: $crate::__private::serde::Deserialize<'de>,
+                M: ::core::default::Default + ::core::iter::Extend<(u64, V)>,
+            {
+                $crate::__private::deserialize_map_keys_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    super::NUMBERS,
+                    super::parse,
+                )
+            }
+        }
+
+        /// Serde helpers for `u64` fields always serialized as plain integers
+        /// (lossless), while still accepting SI prefixed strings when
+        /// deserializing. Integers are always taken as is.
+        $(#[$as_int_doc])*
+        pub mod as_int {
+            /// Serialize a given `u64` as a plain integer.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                serializer.serialize_u64(*value)
+            }
+
+            /// Deserialize a given integer or SI prefixed string into an `u64`.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::deserialize_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    $crate::__private::Numbers::Exact,
+                    super::parse,
+                )
+            }
+        }
+
+        /// Serde helpers for `u64` fields only accepting SI prefixed strings
+        /// when deserializing: bare integers are rejected, asking for a unit.
+        pub mod strict {
+            /// Serialize a given `u64` into a SI prefixed string.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                super::serialize(value, serializer)
+            }
+
+            /// Deserialize a given SI prefixed string into an `u64`, rejecting
+            /// integers.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::__private::deserialize_strict_with(
+                    deserializer,
+                    super::EXPECTING,
+                    super::UNITS,
+                    super::parse,
+                    super::format,
+                )
+            }
+        }
+
+        /// Serde helpers for `u64` fields serialized as structured `{ value,
+        /// unit }` maps (like `{ "value": 88.1, "unit": "TB" }`).
+        pub mod structured {
+            /// Serialize a given `u64` into a `{ value, unit }` map.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serialize_structured_with(*value, serializer, super::format)
+            }
+
+            /// Deserialize a given `{ value, unit }` map, integer or SI
+            /// prefixed string into an `u64`.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                super::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// Generate the `Prefixed` type of a unit module, using its `parse` and
+/// `format_with_prefix` functions.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_prefixed {
+    () => {
+        /// Serde helpers for `u64` fields always serialized using the given SI
+        /// prefix symbol (`k`, `M`, `G`, `T`, `P` or `E`), whatever their
+        /// magnitude, usable with `#[serde(with = "...::Prefixed::<'M'>")]`.
+        ///
+        /// Any SI prefixed string is accepted when deserializing.
+        #[derive(Debug, Clone, Copy)]
+        pub struct Prefixed<const PREFIX: char>;
+
+        impl<const PREFIX: char> Prefixed<PREFIX> {
+            const SI_PREFIX: $crate::si::Prefix = match $crate::si::Prefix::from_symbol(PREFIX) {
+                ::core::option::Option::Some(prefix) => prefix,
+                ::core::option::Option::None => ::core::panic!("invalid SI prefix symbol"),
+            };
+
+            /// Serialize a given `u64` into a string using the SI prefix.
+            pub fn serialize<S>(
+                value: &u64,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serialize_with(*value, serializer, |value| {
+                    format_with_prefix(value, Self::SI_PREFIX)
+                })
+            }
+
+            /// Deserialize a given integer or SI prefixed string into an `u64`.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<u64, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                deserialize(deserializer)
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_serde {
     (
+        expecting: $expecting:expr,
+        example: $example:expr,
         ser: $(#[$doc1:meta])*
         de: $(#[$doc2:meta])*
     ) => {
+        $crate::impl_serde!(
+            expecting: $expecting,
+            example: $example,
+            numbers: Exact,
+            ser: $(#[$doc1])*
+            de: $(#[$doc2])*
+        );
+    };
+    (
+        expecting: $expecting:expr,
+        example: $example:expr,
+        numbers: $numbers:ident,
+        ser: $(#[$doc1:meta])*
+        de: $(#[$doc2:meta])*
+    ) => {
+        $crate::impl_serde!(
+            expecting: $expecting,
+            example: $example,
+            numbers: $numbers,
+            ser: $(#[$doc1])*
+            de: $(#[$doc2])*
+            as_int:
+        );
+    };
+    (
+        expecting: $expecting:expr,
+        example: $example:expr,
+        numbers: $numbers:ident,
+        ser: $(#[$doc1:meta])*
+        de: $(#[$doc2:meta])*
+        as_int: $(#[$doc3:meta])*
+    ) => {
+        /// Short description of the accepted inputs, with a few examples,
+        /// quoted in deserialization errors.
+        #[cfg(feature = "serde")]
+        pub const EXPECTING: &str = $expecting;
+
+        /// How bare numbers are deserialized.
+        #[cfg(feature = "serde")]
+        #[doc(hidden)]
+        pub const NUMBERS: $crate::serde::Numbers = $crate::serde::Numbers::$numbers;
+
+        /// JSON schema of the values accepted by `deserialize` (an integer or a
+        /// string matching a pattern), usable with `#[schemars(schema_with =
+        /// "...")]`.
+        #[cfg(feature = "schemars")]
+        pub fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+            $crate::schemars::schema(EXPECTING, PATTERN)
+        }
+
+        /// OpenAPI schema of the values accepted by `deserialize` (a string
+        /// matching a pattern or an integer), usable with
+        /// `#[schema(schema_with = ...)]`.
+        #[cfg(feature = "utoipa")]
+        pub fn openapi_schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+            $crate::utoipa::schema(EXPECTING, PATTERN, format($example))
+        }
+
         $(#[$doc1])*
         #[cfg(feature = "serde")]
         pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer,
         {
-            serializer.serialize_str(&format(*value))
+            $crate::serde::serialize_with(*value, serializer, format)
         }
 
         $(#[$doc2])*
@@ -29,15 +1427,659 @@ macro_rules! impl_serde {
         where
             D: serde::Deserializer<'de>,
         {
-            Ok(
-                match <crate::serde::IntOrString as serde::Deserialize>::deserialize(deserializer)?
-                {
-                    crate::serde::IntOrString::Int(n) => n,
-                    crate::serde::IntOrString::String(s) => {
-                        parse(&s).map_err(|err| <D::Error as serde::de::Error>::custom(err))?
-                    }
-                },
-            )
+            $crate::serde::deserialize_with(deserializer, EXPECTING, UNITS, NUMBERS, parse)
         }
+
+        #[cfg(feature = "serde")]
+        $crate::__serde_submodules!(as_int: $(#[$doc3])*);
     };
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "byte", feature = "bps"))]
+    use serde::Deserialize;
+
+    #[cfg(feature = "byte")]
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Configuration {
+        #[serde(with = "crate::byte")]
+        quota: u64,
+    }
+
+    #[cfg(feature = "byte")]
+    fn from_toml(quota: &str) -> Result<Configuration, String> {
+        toml::from_str(&format!("quota = {quota}")).map_err(|err| err.message().to_owned())
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn deserialize() {
+        assert_eq!(from_toml("12").unwrap(), Configuration { quota: 12 });
+        assert_eq!(from_toml(r#""1.5kB""#).unwrap(), Configuration { quota: 1_500 });
+
+        assert_eq!(
+            from_toml("-12").unwrap_err(),
+            "invalid value: integer `-12`, expected a data size in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+        assert_eq!(from_toml("1.5e6").unwrap(), Configuration { quota: 1_500_000 });
+        assert_eq!(from_toml("0.0").unwrap(), Configuration { quota: 0 });
+        assert_eq!(
+            from_toml("1.5").unwrap_err(),
+            "invalid value: floating point `1.5`, expected a data size in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+        assert_eq!(
+            from_toml("-1.0").unwrap_err(),
+            "invalid value: floating point `-1.0`, expected a data size in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+        assert_eq!(
+            from_toml("1e20").unwrap_err(),
+            "invalid value: floating point `100000000000000000000.0`, expected a data size \
+             in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+        assert!(from_toml("nan").is_err());
+        assert!(from_toml("inf").is_err());
+        assert_eq!(
+            from_toml("true").unwrap_err(),
+            "invalid type: boolean `true`, expected a data size in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+        assert_eq!(
+            from_toml(r#""1.5kb""#).unwrap_err(),
+            r#"invalid unit "kb" in "1.5kb" (did you mean "kB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        // Structured maps.
+        assert_eq!(
+            from_toml(r#"{ value = 5, unit = "GB" }"#).unwrap(),
+            Configuration {
+                quota: 5_000_000_000
+            }
+        );
+        assert_eq!(
+            from_toml(r#"{ value = 1.5, unit = "kB" }"#).unwrap(),
+            Configuration { quota: 1_500 }
+        );
+        assert_eq!(
+            from_toml(r#"{ value = 5, unit = "Gb" }"#).unwrap_err(),
+            r#"invalid unit "Gb" in "5Gb" (did you mean "GB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn option() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(
+                default,
+                with = "crate::byte::option",
+                skip_serializing_if = "Option::is_none"
+            )]
+            quota: Option<u64>,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1.5kB""#).unwrap(),
+            Configuration { quota: Some(1_500) }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 12").unwrap(),
+            Configuration { quota: Some(12) }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "unlimited""#).unwrap(),
+            Configuration { quota: None }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = { value = 2, unit = "kB" }"#).unwrap(),
+            Configuration { quota: Some(2_000) }
+        );
+        assert_eq!(toml::from_str::<Configuration>("").unwrap(), Configuration { quota: None });
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "12kb""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "kb" in "12kb" (did you mean "kB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        assert_eq!(
+            toml::to_string(&Configuration { quota: Some(1_500) }).unwrap(),
+            "quota = \"1.5kB\"\n"
+        );
+        assert_eq!(toml::to_string(&Configuration { quota: None }).unwrap(), "");
+
+        // Formats with `null`.
+        let deserializer = serde::de::value::UnitDeserializer::<serde::de::value::Error>::new();
+        assert_eq!(
+            super::deserialize_option_with(
+                deserializer,
+                crate::byte::EXPECTING,
+                crate::byte::UNITS,
+                crate::byte::NUMBERS,
+                crate::byte::parse
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn vec() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::vec")]
+            tiers: Vec<u64>,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"tiers = ["1GB", "10GB", 100]"#).unwrap(),
+            Configuration {
+                tiers: vec![1_000_000_000, 10_000_000_000, 100]
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("tiers = []").unwrap(),
+            Configuration { tiers: vec![] }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"tiers = ["1GB", "10Gb"]"#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Gb" in "10Gb" (did you mean "GB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"tiers = "1GB""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid type: string "1GB", expected a sequence of a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        assert_eq!(
+            toml::to_string(&Configuration {
+                tiers: vec![1_000_000_000, 10_000_000_000]
+            })
+            .unwrap(),
+            "tiers = [\"1GB\", \"10GB\"]\n"
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn map_values() {
+        use std::collections::{BTreeMap, HashMap};
+
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::map_values")]
+            limits: HashMap<String, u64>,
+            #[serde(with = "crate::byte::map_values")]
+            sorted: BTreeMap<String, u64>,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(indoc::indoc! {r#"
+                limits = { alice = "5GB", bob = "500MB", carol = 12 }
+                sorted = {}
+            "#})
+            .unwrap(),
+            Configuration {
+                limits: HashMap::from([
+                    ("alice".to_owned(), 5_000_000_000),
+                    ("bob".to_owned(), 500_000_000),
+                    ("carol".to_owned(), 12),
+                ]),
+                sorted: BTreeMap::new(),
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"limits = { alice = "5Gb" }"#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Gb" in "5Gb" (did you mean "GB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        assert_eq!(
+            toml::to_string(&Configuration {
+                limits: HashMap::new(),
+                sorted: BTreeMap::from([
+                    ("alice".to_owned(), 5_000_000_000),
+                    ("bob".to_owned(), 500_000_000),
+                ]),
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                [limits]
+
+                [sorted]
+                alice = "5GB"
+                bob = "500MB"
+            "#}
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn map_keys() {
+        use std::collections::BTreeMap;
+
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::map_keys")]
+            buckets: BTreeMap<u64, String>,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(
+                r#"buckets = { "1kB" = "small", "64kB" = "large", 12 = "tiny" }"#
+            )
+            .unwrap(),
+            Configuration {
+                buckets: BTreeMap::from([
+                    (12, "tiny".to_owned()),
+                    (1_000, "small".to_owned()),
+                    (64_000, "large".to_owned()),
+                ]),
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"buckets = { "1kb" = "small" }"#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "kb" in "1kb" (did you mean "kB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+
+        // Keys are normalized.
+        assert_eq!(
+            toml::to_string(&Configuration {
+                buckets: BTreeMap::from([
+                    (1_000, "small".to_owned()),
+                    (64_000, "large".to_owned())
+                ]),
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                [buckets]
+                1kB = "small"
+                64kB = "large"
+            "#}
+        );
+
+        // Keys that would be rounded are kept exact.
+        let configuration = Configuration {
+            buckets: BTreeMap::from([
+                (1_000_000, "first".to_owned()),
+                (1_000_001, "second".to_owned()),
+                (1_000_002, "third".to_owned()),
+            ]),
+        };
+        let json = serde_json::to_string(&configuration).unwrap();
+        assert_eq!(json, r#"{"buckets":{"1MB":"first","1000001":"second","1000002":"third"}}"#);
+        assert_eq!(serde_json::from_str::<Configuration>(&json).unwrap(), configuration);
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn as_int() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::as_int")]
+            quota: u64,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1.5kB""#).unwrap(),
+            Configuration { quota: 1_500 }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 12").unwrap(),
+            Configuration { quota: 12 }
+        );
+        assert_eq!(toml::to_string(&Configuration { quota: 12_345 }).unwrap(), "quota = 12345\n");
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn strict() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::strict")]
+            quota: u64,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1.5kB""#).unwrap(),
+            Configuration { quota: 1_500 }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 1500")
+                .unwrap_err()
+                .message(),
+            r#"missing unit in 1500, use a string like "1.5kB""#
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = -12")
+                .unwrap_err()
+                .message(),
+            "missing unit in -12, use a SI prefixed string"
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 1.5")
+                .unwrap_err()
+                .message(),
+            "missing unit in 1.5, use a SI prefixed string"
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = true")
+                .unwrap_err()
+                .message(),
+            "invalid type: boolean `true`, expected a data size in bytes like \"1.5kB\", \"12MB\" or 1500"
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn bounded() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::Bounded::<1_000, 1_000_000_000>")]
+            quota: u64,
+        }
+
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1.5kB""#).unwrap(),
+            Configuration { quota: 1_500 }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 1_000").unwrap(),
+            Configuration { quota: 1_000 }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "500B""#)
+                .unwrap_err()
+                .message(),
+            r#""500B" is out of range, expected between 1kB and 1GB"#
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>("quota = 1e10")
+                .unwrap_err()
+                .message(),
+            "10000000000 is out of range, expected between 1kB and 1GB"
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1kb""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "kb" in "1kb" (did you mean "kB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = { value = 12, unit = "GB" }"#)
+                .unwrap_err()
+                .message(),
+            r#""12GB" is out of range, expected between 1kB and 1GB"#
+        );
+        assert_eq!(
+            toml::to_string(&Configuration { quota: 1_500 }).unwrap(),
+            "quota = \"1.5kB\"\n"
+        );
+
+        let bytes = bincode::serialize(&Configuration { quota: 1_500 }).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Configuration>(&bytes).unwrap(),
+            Configuration { quota: 1_500 }
+        );
+        let bytes = bincode::serialize(&Configuration { quota: 12 }).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Configuration>(&bytes)
+                .unwrap_err()
+                .to_string(),
+            "12 is out of range, expected between 1kB and 1GB"
+        );
+    }
+
+    #[cfg(all(feature = "bps", feature = "byte"))]
+    #[test]
+    fn prefixed() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::Prefixed::<'M'>")]
+            quota: u64,
+            #[serde(with = "crate::bps::Prefixed::<'k'>")]
+            bandwidth: u64,
+        }
+
+        assert_eq!(
+            toml::to_string(&Configuration {
+                quota: 12_300_000_000,
+                bandwidth: 1_500,
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                quota = "12300MB"
+                bandwidth = "1.5kb/s"
+            "#}
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(indoc::indoc! {r#"
+                quota = "12.3GB"
+                bandwidth = 1_500
+            "#})
+            .unwrap(),
+            Configuration {
+                quota: 12_300_000_000,
+                bandwidth: 1_500,
+            }
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn quantized() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::si::Quantized::<2>")]
+            users: u64,
+            #[serde(with = "crate::byte::Quantized::<3>")]
+            quota: u64,
+        }
+
+        assert_eq!(
+            toml::to_string(&Configuration {
+                users: 5_099_876,
+                quota: 1_234_567,
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                users = "5.1M"
+                quota = "1.23MB"
+            "#}
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(indoc::indoc! {r#"
+                users = 5_099_876
+                quota = "1.2345MB"
+            "#})
+            .unwrap(),
+            Configuration {
+                users: 5_099_876,
+                quota: 1_234_500,
+            }
+        );
+        assert_eq!(
+            bincode::serialize(&Configuration {
+                users: 5_099_876,
+                quota: 1,
+            })
+            .unwrap(),
+            bincode::serialize(&(5_100_000u64, 1u64)).unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "bps", feature = "byte"))]
+    #[test]
+    fn structured() {
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte::structured")]
+            quota: u64,
+            #[serde(with = "crate::bps::structured")]
+            bandwidth: u64,
+        }
+
+        let configuration = Configuration {
+            quota: 88_100_000_000_000,
+            bandwidth: 12,
+        };
+        let serialized = indoc::indoc! {r#"
+            [quota]
+            value = 88.1
+            unit = "TB"
+
+            [bandwidth]
+            value = 12
+            unit = "b/s"
+        "#};
+        assert_eq!(toml::to_string(&configuration).unwrap(), serialized);
+        assert_eq!(toml::from_str::<Configuration>(serialized).unwrap(), configuration);
+
+        let from_toml = |quota: &str| {
+            toml::from_str::<Configuration>(&format!(
+                "quota = {quota}\nbandwidth = {{ value = 1 }}"
+            ))
+            .map(|configuration| configuration.quota)
+            .map_err(|err| err.message().to_owned())
+        };
+        assert_eq!(from_toml(r#"{ value = "5", unit = "GB" }"#).unwrap(), 5_000_000_000);
+        assert_eq!(from_toml(r#"{ value = 1.5, unit = "kB" }"#).unwrap(), 1_500);
+        assert_eq!(from_toml("{ value = 12 }").unwrap(), 12);
+        assert_eq!(from_toml(r#"{ unit = "kB" }"#).unwrap_err(), "missing field `value`");
+        assert_eq!(
+            from_toml(r#"{ value = 5, units = "kB" }"#).unwrap_err(),
+            "unknown field `units`, expected `value` or `unit`"
+        );
+        assert_eq!(
+            from_toml(r#"{ value = 5, unit = "kb" }"#).unwrap_err(),
+            r#"invalid unit "kb" in "5kb" (did you mean "kB"?), expected a data size in bytes like "1.5kB", "12MB" or 1500"#
+        );
+        assert_eq!(from_toml(r#""5kB""#).unwrap(), 5_000);
+    }
+
+    #[cfg(all(feature = "byte", feature = "db", feature = "duration"))]
+    #[test]
+    fn binary() {
+        use std::collections::BTreeMap;
+
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Configuration {
+            #[serde(with = "crate::byte")]
+            quota: u64,
+            #[serde(with = "crate::byte::option")]
+            limit: Option<u64>,
+            #[serde(with = "crate::byte::vec")]
+            tiers: Vec<u64>,
+            #[serde(with = "crate::byte::map_values")]
+            quotas: BTreeMap<String, u64>,
+            #[serde(with = "crate::byte::map_keys")]
+            buckets: BTreeMap<u64, String>,
+        }
+
+        let configuration = Configuration {
+            quota: 88_100_000_000_000,
+            limit: Some(1_500),
+            tiers: vec![1_000, 2_000],
+            quotas: BTreeMap::from([("alice".to_owned(), 3_000)]),
+            buckets: BTreeMap::from([(64_000, "large".to_owned())]),
+        };
+        let bytes = bincode::serialize(&configuration).unwrap();
+        assert_eq!(bytes[..8], 88_100_000_000_000u64.to_le_bytes());
+        assert_eq!(bincode::deserialize::<Configuration>(&bytes).unwrap(), configuration);
+
+        #[derive(serde::Serialize, Deserialize, PartialEq, Debug)]
+        struct Link {
+            #[serde(with = "crate::db")]
+            margin: i64,
+            #[serde(with = "crate::db::dbm")]
+            sensitivity: i64,
+            #[serde(with = "crate::duration")]
+            timeout: std::time::Duration,
+        }
+
+        let link = Link {
+            margin: 3_500,
+            sensitivity: -28_500,
+            timeout: std::time::Duration::from_millis(1_500),
+        };
+        let bytes = bincode::serialize(&link).unwrap();
+        assert_eq!(bytes[..8], 3_500i64.to_le_bytes());
+        assert_eq!(bincode::deserialize::<Link>(&bytes).unwrap(), link);
+
+        // Human readable formats still use strings.
+        assert_eq!(
+            toml::to_string(&configuration).unwrap(),
+            indoc::indoc! {r#"
+                quota = "88.1TB"
+                limit = "1.5kB"
+                tiers = ["1kB", "2kB"]
+
+                [quotas]
+                alice = "3kB"
+
+                [buckets]
+                64kB = "large"
+            "#}
+        );
+    }
+
+    #[cfg(feature = "bps")]
+    #[test]
+    fn expecting() {
+        #[derive(Deserialize, Debug)]
+        struct Link {
+            #[serde(with = "crate::bps")]
+            #[allow(dead_code)]
+            bandwidth: u64,
+        }
+
+        assert_eq!(
+            toml::from_str::<Link>(r#"bandwidth = "1Gb/s/s""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Gb/s" in "1Gb/s/s" (did you mean "Gb/s"?), expected a data-rate like "1.5kb/s", "12Mbps" or 1500"#
+        );
+        assert_eq!(
+            toml::from_str::<Link>(r#"bandwidth = { value = 1, unit = "Hz" }"#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "Hz" in "1Hz", expected a data-rate like "1.5kb/s", "12Mbps" or 1500"#
+        );
+    }
+
+    #[cfg(feature = "byte")]
+    #[test]
+    fn deserialize_str() {
+        use serde::de::value::{BorrowedStrDeserializer, Error, StrDeserializer};
+
+        assert_eq!(
+            super::deserialize_with(
+                BorrowedStrDeserializer::<Error>::new("1.5kB"),
+                crate::byte::EXPECTING,
+                crate::byte::UNITS,
+                crate::byte::NUMBERS,
+                crate::byte::parse
+            )
+            .unwrap(),
+            1_500
+        );
+        assert_eq!(
+            super::deserialize_with(
+                StrDeserializer::<Error>::new("1.5kB"),
+                crate::byte::EXPECTING,
+                crate::byte::UNITS,
+                crate::byte::NUMBERS,
+                crate::byte::parse,
+            )
+            .unwrap(),
+            1_500
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! [`serde_with`](https://docs.rs/serde_with) interoperability.
+//!
+//! Each type of this module implements [`SerializeAs<u64>`] and
+//! [`DeserializeAs<u64>`] using the matching unit module, so they compose with
+//! `Option`, `Vec`, maps, ... through `#[serde_as(as = "...")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//!
+//! use bity::serde_with::{Bps, Byte};
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//! use serde_with::serde_as;
+//!
+//! #[serde_as]
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Configuration {
+//!     #[serde_as(as = "Option<Byte>")]
+//!     quota: Option<u64>,
+//!     #[serde_as(as = "Vec<Bps>")]
+//!     tiers: Vec<u64>,
+//!     #[serde_as(as = "BTreeMap<Byte, _>")]
+//!     buckets: BTreeMap<u64, String>,
+//! }
+//!
+//! let configuration = Configuration {
+//!     quota: Some(1_500),
+//!     tiers: vec![1_000_000, 10_000_000],
+//!     buckets: BTreeMap::from([(64_000, "large".to_owned())]),
+//! };
+//! let serialized = indoc! {r#"
+//!     quota = "1.5kB"
+//!     tiers = ["1Mb/s", "10Mb/s"]
+//!
+//!     [buckets]
+//!     64kB = "large"
+//! "#};
+//!
+//! assert_eq!(toml::to_string(&configuration).unwrap(), serialized);
+//! assert_eq!(toml::from_str::<Configuration>(serialized).unwrap(), configuration);
+//! ```
+
+use serde::{Deserializer, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+
+macro_rules! adapters {
+    ($($(#[doc = $doc:literal])* $(#[cfg($cfg:meta)])? $name:ident => $module:ident,)*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            $(#[cfg($cfg)])?
+            impl SerializeAs<u64> for $name {
+                fn serialize_as<S>(source: &u64, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    crate::$module::serialize(source, serializer)
+                }
+            }
+
+            $(#[cfg($cfg)])?
+            impl<'de> DeserializeAs<'de, u64> for $name {
+                fn deserialize_as<D>(deserializer: D) -> Result<u64, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    crate::$module::deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+adapters! {
+    /// Adapter using [`si`](crate::si).
+    Si => si,
+    /// Adapter using [`bit`](crate::bit).
+    #[cfg(feature = "bit")]
+    Bit => bit,
+    /// Adapter using [`byte`](crate::byte).
+    #[cfg(feature = "byte")]
+    Byte => byte,
+    /// Adapter using [`packet`](crate::packet).
+    #[cfg(feature = "packet")]
+    Packet => packet,
+    /// Adapter using [`bps`](crate::bps).
+    #[cfg(feature = "bps")]
+    Bps => bps,
+    /// Adapter using [`byteps`](crate::byteps).
+    #[cfg(feature = "byteps")]
+    Byteps => byteps,
+    /// Adapter using [`pps`](crate::pps).
+    #[cfg(feature = "pps")]
+    Pps => pps,
+    /// Adapter using [`bpp`](crate::bpp).
+    #[cfg(feature = "bpp")]
+    Bpp => bpp,
+    /// Adapter using [`hz`](crate::hz).
+    #[cfg(feature = "hz")]
+    Hz => hz,
+    /// Adapter using [`watt`](crate::watt).
+    #[cfg(feature = "watt")]
+    Watt => watt,
+    /// Adapter using [`ops`](crate::ops).
+    #[cfg(feature = "ops")]
+    Ops => ops,
+    /// Adapter using [`percent`](crate::percent).
+    #[cfg(feature = "percent")]
+    Percent => percent,
+    /// Adapter using [`compression`](crate::compression).
+    #[cfg(feature = "compression")]
+    Compression => compression,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+    use serde_with::serde_as;
+
+    use super::{Bit, Pps};
+
+    #[serde_as]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Configuration {
+        #[serde_as(as = "Bit")]
+        quota: u64,
+        #[serde_as(as = "Option<Pps>")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<u64>,
+        #[serde_as(as = "HashMap<_, Vec<Bit>>")]
+        #[serde(default)]
+        tiers: HashMap<String, Vec<u64>>,
+    }
+
+    #[test]
+    fn serde_as() {
+        assert_eq!(
+            toml::from_str::<Configuration>(indoc::indoc! {r#"
+                quota = "1.5kb"
+                limit = 12
+
+                [tiers]
+                alice = ["1kb", 2_000]
+            "#})
+            .unwrap(),
+            Configuration {
+                quota: 1_500,
+                limit: Some(12),
+                tiers: HashMap::from([("alice".to_owned(), vec![1_000, 2_000])]),
+            }
+        );
+        assert_eq!(
+            toml::from_str::<Configuration>(r#"quota = "1.5kx""#)
+                .unwrap_err()
+                .message(),
+            r#"invalid unit "kx" in "1.5kx" (did you mean "kb"?), expected a data size in bits like "1.5kb", "12Mb" or 1500"#
+        );
+        assert_eq!(
+            toml::to_string(&Configuration {
+                quota: 1_500,
+                limit: None,
+                tiers: HashMap::new(),
+            })
+            .unwrap(),
+            "quota = \"1.5kb\"\n\n[tiers]\n"
+        );
+    }
+}
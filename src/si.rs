@@ -20,6 +20,7 @@
 //! "bity::si::deserialize")]` and `#[serde(with = "bity::si")]` attributes.
 //!
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use indoc::indoc;
 //! use serde::{Deserialize, Serialize};
 //!
@@ -55,11 +56,13 @@
 //!         instances = "5"
 //!     "#}
 //! );
+//! # }
 //! ```
 
-use std::fmt::Write;
+use alloc::{borrow::ToOwned, collections::TryReserveError, string::String, vec::Vec};
+use core::fmt;
 
-use crate::error::Error;
+use crate::error::{check_number, is_multiple_units, Error};
 
 const KILO: u64 = 1_000;
 const MEGA: u64 = 1_000_000;
@@ -68,6 +71,41 @@ const TERA: u64 = 1_000_000_000_000;
 const PETA: u64 = 1_000_000_000_000_000;
 const EXA: u64 = 1_000_000_000_000_000_000;
 
+/// Regular expression matching the strings accepted by [`parse`], to validate
+/// inputs outside of Rust (JSON schemas, database `CHECK` constraints,
+/// frontend forms...).
+///
+/// Matching inputs may still fail to parse if their value is too large to be
+/// represented.
+///
+/// # Examples
+/// ```
+/// use bity::si::{parse, PATTERN};
+/// use regex::Regex;
+///
+/// let pattern = Regex::new(PATTERN).unwrap();
+/// assert!(pattern.is_match("1.5k") && parse("1.5k").is_ok());
+/// assert!(!pattern.is_match("1.5kk") && parse("1.5kk").is_err());
+/// ```
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?\s*$";
+
+/// Units accepted after the SI prefix, none as SI values are unitless.
+pub const UNITS: &[&str] = &[];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], to size buffers at compile time.
+///
+/// The longest string is `u64::MAX` formatted with [`Prefix::Kilo`]: 17
+/// integer digits, a dot, two fraction digits and the symbol.
+///
+/// # Examples
+/// ```
+/// use bity::si::{format_with_prefix, Prefix, MAX_FORMATTED_LEN};
+///
+/// assert_eq!(format_with_prefix(u64::MAX, Prefix::Kilo).len(), MAX_FORMATTED_LEN);
+/// ```
+pub const MAX_FORMATTED_LEN: usize = 21;
+
 /// Parse a SI prefixed string into a number.
 ///
 /// Only "positive" and multiple of `1_000^n` prefixes are supported (kilo,
@@ -98,14 +136,14 @@ const EXA: u64 = 1_000_000_000_000_000_000;
 /// assert_eq!(parse("12k ").unwrap(), 12_000);
 /// assert_eq!(parse("12 k").unwrap(), 12_000);
 /// // Invalids.
-/// assert!(matches!(parse("k"), Err(Error::ParseIntError("", None))));
+/// assert!(matches!(parse("k"), Err(Error::EmptyInput)));
 /// assert!(matches!(parse(".k"), Err(Error::ParseIntError(".", None))));
-/// assert!(matches!(parse("1.1."), Err(Error::ParseIntError("1.", Some(_)))));
-/// assert!(matches!(parse("1.1.k"), Err(Error::ParseIntError("1.", Some(_)))));
-/// assert!(matches!(parse("1.1.1k"), Err(Error::ParseIntError("1.1", Some(_)))));
-/// assert!(matches!(parse(".1.1k"), Err(Error::ParseIntError("1.1", Some(_)))));
-/// assert!(matches!(parse("12kk"), Err(Error::InvalidUnit("kk"))));
-/// assert!(matches!(parse("12kM"), Err(Error::InvalidUnit("kM"))));
+/// assert!(matches!(parse("1.1."), Err(Error::TrailingGarbage("."))));
+/// assert!(matches!(parse("1.1.k"), Err(Error::TrailingGarbage("."))));
+/// assert!(matches!(parse("1.1.1k"), Err(Error::TrailingGarbage(".1"))));
+/// assert!(matches!(parse(".1.1k"), Err(Error::TrailingGarbage(".1"))));
+/// assert!(matches!(parse("12kk"), Err(Error::MultipleUnits("kk"))));
+/// assert!(matches!(parse("12kM"), Err(Error::MultipleUnits("kM"))));
 /// assert!(matches!(parse("12k M"), Err(Error::InvalidUnit("k M"))));
 /// ```
 pub fn parse(input: &str) -> Result<u64, Error<'_>> {
@@ -118,7 +156,9 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// Like `parse`, at most one additional unit can be used.
 ///
 /// Unlike `parse`, the additional units passed will be matched
-/// case-sensitively.
+/// case-sensitively. They are matched before looking for the SI prefix, so
+/// units starting with a prefix letter (like `msg`) can be used without
+/// prefix.
 ///
 /// # Examples
 /// ```
@@ -128,6 +168,10 @@ pub fn parse(input: &str) -> Result<u64, Error<'_>> {
 /// assert_eq!(parse_with_additional_units("12", additional_units).unwrap(), 12);
 /// assert_eq!(parse_with_additional_units("12b", additional_units).unwrap(), 12 * 1);
 /// assert_eq!(parse_with_additional_units("12kB", additional_units).unwrap(), 12 * 1_000 * 8);
+///
+/// let additional_units = &[("msg", 1)];
+/// assert_eq!(parse_with_additional_units("12msg", additional_units).unwrap(), 12);
+/// assert_eq!(parse_with_additional_units("12mmsg", additional_units).unwrap(), 12_000_000);
 /// ```
 pub fn parse_with_additional_units<'a>(
     mut input: &'a str,
@@ -138,12 +182,8 @@ pub fn parse_with_additional_units<'a>(
     }
 
     input = input.trim();
-    let (mut value, original_unit_str) = input.split_at(
-        input
-            .bytes()
-            .position(|b| b.is_ascii_alphabetic())
-            .unwrap_or(input.len()),
-    );
+    let (mut value, original_unit_str) =
+        input.split_at(crate::scan::letter_position(input.as_bytes()));
 
     let mut unit_str = original_unit_str;
     let mut unit = 1;
@@ -159,7 +199,10 @@ pub fn parse_with_additional_units<'a>(
             _ => None,
         };
         if let Some(exponent) = exponent {
-            if additional_units.iter().all(|(s, _)| *s != &unit_str[..1]) {
+            if additional_units
+                .iter()
+                .all(|(s, _)| *s != &unit_str[..1] && *s != unit_str)
+            {
                 unit *= exponent;
                 unit_str = &unit_str[1..];
             }
@@ -170,7 +213,9 @@ pub fn parse_with_additional_units<'a>(
     if !unit_str.is_empty() {
         for &(additional_unit, addition_factor) in additional_units {
             if unit_str == additional_unit {
-                unit *= addition_factor;
+                unit = unit
+                    .checked_mul(addition_factor)
+                    .ok_or(Error::ParseIntError(value.trim(), None))?;
                 unit_str = "";
                 break;
             }
@@ -179,35 +224,295 @@ pub fn parse_with_additional_units<'a>(
 
     // Unit parsing should be over by now.
     if !unit_str.is_empty() {
+        let units = ["k", "K", "m", "M", "g", "G", "t", "T", "p", "P", "e", "E"]
+            .into_iter()
+            .chain(additional_units.iter().map(|(unit, _)| *unit))
+            .collect::<Vec<_>>();
+        if is_multiple_units(original_unit_str, &units) {
+            return Err(Error::MultipleUnits(original_unit_str));
+        }
         return Err(Error::InvalidUnit(original_unit_str));
     }
 
     value = value.trim();
+    check_number(value)?;
     let (integer_str, mut fraction_str) = value.split_once('.').unwrap_or((value, ""));
-    fraction_str = fraction_str.trim_end_matches('0');
     if integer_str.is_empty() && fraction_str.is_empty() {
         return Err(Error::ParseIntError(value, None));
     }
+    // `u64::from_str` accepts a sign, which isn't allowed after the dot.
+    if fraction_str.starts_with('+') {
+        return Err(Error::ParseIntError(fraction_str, None));
+    }
+    fraction_str = fraction_str.trim_end_matches('0');
+    // `10^20` doesn't fit in a `u64`.
+    if fraction_str.len() > 19 {
+        return Err(Error::FractionTooPrecise(fraction_str));
+    }
 
-    fn apply_unit(part: &str, unit: u64, reduce: u64) -> Result<u64, Error<'_>> {
+    fn parse_part(part: &str) -> Result<u64, Error<'_>> {
         if part.is_empty() {
             return Ok(0);
         }
-        Ok(part
-            .parse::<u64>()
-            .map_err(|err| Error::ParseIntError(part, Some(err)))?
-            * unit
-            / reduce)
-    }
-    Ok(apply_unit(integer_str, unit, 1)?
-        + apply_unit(fraction_str, unit, 10u64.pow(fraction_str.len() as u32))?)
+        part.parse::<u64>()
+            .map_err(|err| Error::ParseIntError(part, Some(err)))
+    }
+    let integer = parse_part(integer_str)?
+        .checked_mul(unit)
+        .ok_or(Error::ParseIntError(value, None))?;
+    // The fraction is below `1`, so its product with the unit fits in a `u64`.
+    let fraction = u128::from(parse_part(fraction_str)?) * u128::from(unit)
+        / 10u128.pow(fraction_str.len() as u32);
+    integer
+        .checked_add(fraction as u64)
+        .ok_or(Error::ParseIntError(value, None))
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::si::parse_const;
+///
+/// const USERS: u64 = match parse_const("1.5k") {
+///     Some(users) => users,
+///     None => panic!("invalid users count"),
+/// };
+/// assert_eq!(USERS, 1_500);
+/// assert_eq!(parse_const("12kk"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    parse_const_with_additional_units(input, &[])
+}
+
+/// Const version of [`parse_with_additional_units`], returning `None` if the
+/// input is invalid (or overflows).
+///
+/// # Examples
+/// ```
+/// use bity::si::parse_const_with_additional_units;
+///
+/// let additional_units = &[("b", 1), ("B", 8)];
+/// assert_eq!(parse_const_with_additional_units("12kB", additional_units), Some(96_000));
+/// assert_eq!(parse_const_with_additional_units("12kbit", additional_units), None);
+/// assert_eq!(parse_const_with_additional_units("12msg", &[("msg", 1)]), Some(12));
+/// ```
+pub const fn parse_const_with_additional_units(
+    input: &str,
+    additional_units: &[(&str, u64)],
+) -> Option<u64> {
+    if !input.is_ascii() {
+        return None;
+    }
+
+    let input = input.trim_ascii();
+    let mut split = 0;
+    while split < input.len() && !input.as_bytes()[split].is_ascii_alphabetic() {
+        split += 1;
+    }
+    let (value, unit_str) = input.split_at(split);
+    let mut unit_str = unit_str.as_bytes();
+
+    let mut unit = 1;
+    // Look for basic exponent first.
+    if !unit_str.is_empty() {
+        let exponent = match unit_str[0].to_ascii_lowercase() {
+            b'k' => Some(KILO),
+            b'm' => Some(MEGA),
+            b'g' => Some(GIGA),
+            b't' => Some(TERA),
+            b'p' => Some(PETA),
+            b'e' => Some(EXA),
+            _ => None,
+        };
+        if let Some(exponent) = exponent {
+            let (prefix, rest) = unit_str.split_at(1);
+            if find_unit(prefix, additional_units).is_none()
+                && find_unit(unit_str, additional_units).is_none()
+            {
+                unit = exponent;
+                unit_str = rest;
+            }
+        }
+    }
+
+    // Apply additional unit if one matches.
+    if !unit_str.is_empty() {
+        unit = match find_unit(unit_str, additional_units) {
+            Some(factor) => match unit.checked_mul(factor) {
+                Some(unit) => unit,
+                None => return None,
+            },
+            None => return None,
+        };
+    }
+
+    let value = value.trim_ascii();
+    let mut dot = 0;
+    while dot < value.len() && value.as_bytes()[dot] != b'.' {
+        dot += 1;
+    }
+    let (integer, mut fraction) = value.split_at(dot);
+    if !fraction.is_empty() {
+        fraction = fraction.split_at(1).1;
+    }
+    // `from_str_radix` accepts a sign, which isn't allowed after the dot.
+    if integer.is_empty() && fraction.is_empty() || matches!(fraction.as_bytes(), [b'+', ..]) {
+        return None;
+    }
+    while let [.., b'0'] = fraction.as_bytes() {
+        fraction = fraction.split_at(fraction.len() - 1).0;
+    }
+
+    const fn parse_part(part: &str) -> Option<u64> {
+        if part.is_empty() {
+            return Some(0);
+        }
+        match u64::from_str_radix(part, 10) {
+            Ok(part) => Some(part),
+            Err(_) => None,
+        }
+    }
+    let (Some(integer), Some(fraction), Some(reduce)) = (
+        parse_part(integer),
+        parse_part(fraction),
+        10u64.checked_pow(fraction.len() as u32),
+    ) else {
+        return None;
+    };
+    let Some(integer) = integer.checked_mul(unit) else {
+        return None;
+    };
+    // The fraction is below `1`, so its product with the unit fits in a `u64`.
+    let fraction = fraction as u128 * unit as u128 / reduce as u128;
+    integer.checked_add(fraction as u64)
+}
+
+/// Factor of the additional unit exactly matching `unit_str`, if any.
+const fn find_unit(unit_str: &[u8], additional_units: &[(&str, u64)]) -> Option<u64> {
+    let mut i = 0;
+    while i < additional_units.len() {
+        let (unit, factor) = additional_units[i];
+        if eq(unit_str, unit.as_bytes()) {
+            return Some(factor);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Const byte slices equality.
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A SI prefix, as used by [`format_with_prefix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub enum Prefix {
+    /// No prefix (`10^0`).
+    None,
+    /// `k` (`10^3`).
+    Kilo,
+    /// `M` (`10^6`).
+    Mega,
+    /// `G` (`10^9`).
+    Giga,
+    /// `T` (`10^12`).
+    Tera,
+    /// `P` (`10^15`).
+    Peta,
+    /// `E` (`10^18`).
+    Exa,
+}
+
+impl Prefix {
+    /// Prefix matching a given symbol (`k`, `M`, `G`, `T`, `P` or `E`).
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::si::Prefix;
+    ///
+    /// assert_eq!(Prefix::from_symbol('M'), Some(Prefix::Mega));
+    /// assert_eq!(Prefix::from_symbol('m'), None);
+    /// ```
+    pub const fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            'k' => Some(Prefix::Kilo),
+            'M' => Some(Prefix::Mega),
+            'G' => Some(Prefix::Giga),
+            'T' => Some(Prefix::Tera),
+            'P' => Some(Prefix::Peta),
+            'E' => Some(Prefix::Exa),
+            _ => None,
+        }
+    }
+
+    /// Symbol of the prefix, empty for [`Prefix::None`].
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Prefix::None => "",
+            Prefix::Kilo => "k",
+            Prefix::Mega => "M",
+            Prefix::Giga => "G",
+            Prefix::Tera => "T",
+            Prefix::Peta => "P",
+            Prefix::Exa => "E",
+        }
+    }
+
+    /// Factor of the prefix (`1_000` for [`Prefix::Kilo`]).
+    pub const fn factor(self) -> u64 {
+        match self {
+            Prefix::None => 1,
+            Prefix::Kilo => KILO,
+            Prefix::Mega => MEGA,
+            Prefix::Giga => GIGA,
+            Prefix::Tera => TERA,
+            Prefix::Peta => PETA,
+            Prefix::Exa => EXA,
+        }
+    }
+
+    /// Highest prefix not above a given non-zero integer.
+    fn of(input: u64) -> Self {
+        match input.ilog10() / 3 {
+            0 => Prefix::None,
+            1 => Prefix::Kilo,
+            2 => Prefix::Mega,
+            3 => Prefix::Giga,
+            4 => Prefix::Tera,
+            5 => Prefix::Peta,
+            _ => Prefix::Exa,
+        }
+    }
 }
 
 /// Format an integer into a SI prefixed string.
 ///
 /// The first "full" (if any) unit will be used (no `0.**`).
 ///
-/// At most two fraction digits will be displayed.
+/// At most two fraction digits will be displayed: the fraction is truncated
+/// (never rounded up), its leading zeroes are kept and its trailing zeroes are
+/// dropped, so `1_050` is `1.05k` and `1_005` is `1k`.
 ///
 /// # Examples
 ///
@@ -220,37 +525,311 @@ pub fn parse_with_additional_units<'a>(
 /// assert_eq!(format(123_456), "123.45k");
 /// assert_eq!(format(12_345_678), "12.34M");
 /// assert_eq!(format(1_200_000_000), "1.2G");
+/// assert_eq!(format(1_050), "1.05k");
+/// assert_eq!(format(1_005), "1k");
+/// assert_eq!(format(1_999), "1.99k");
 /// ```
 pub fn format(input: u64) -> String {
+    format_with_prefix(input, prefix_of(input))
+}
+
+/// Like [`format`], but returning an error instead of aborting if the string
+/// can't be allocated.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::try_format;
+///
+/// assert_eq!(try_format(1_234).unwrap(), "1.23k");
+/// assert_eq!(try_format(u64::MAX).unwrap(), "18.44E");
+/// ```
+pub fn try_format(input: u64) -> Result<String, TryReserveError> {
+    try_format_with_prefix(input, prefix_of(input))
+}
+
+/// Highest prefix not above a given integer, [`Prefix::None`] for `0`.
+pub(crate) fn prefix_of(input: u64) -> Prefix {
     if input == 0 {
-        return "0".to_owned();
-    }
-
-    let exponent = input.ilog10() / 3;
-    let unit = match exponent {
-        0 => "",
-        1 => "k",
-        2 => "M",
-        3 => "G",
-        4 => "T",
-        5 => "P",
-        _ => "E",
-    };
+        return Prefix::None;
+    }
+    Prefix::of(input)
+}
 
-    let mut output = String::with_capacity(8);
-    let exponent_base = 10u64.pow(exponent * 3);
-    let integer = input / exponent_base;
-    write!(output, "{integer}").expect("write error");
-    if input % exponent_base != 0 {
-        write!(output, ".{0:.2}", (input % exponent_base).to_string().trim_end_matches('0'))
-            .expect("write error");
+/// Format an integer into a string using a given SI prefix, whatever its
+/// magnitude.
+///
+/// Like [`format`], at most two fraction digits will be displayed.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::{format_with_prefix, Prefix};
+///
+/// assert_eq!(format_with_prefix(12_300_000_000, Prefix::Mega), "12300M");
+/// assert_eq!(format_with_prefix(1_234_567, Prefix::Mega), "1.23M");
+/// assert_eq!(format_with_prefix(500_000, Prefix::Mega), "0.5M");
+/// assert_eq!(format_with_prefix(12_345, Prefix::None), "12345");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    Digits::with_prefix(input, prefix).as_str().to_owned()
+}
+
+/// Like [`format_with_prefix`], but returning an error instead of aborting if
+/// the string can't be allocated.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::{try_format_with_prefix, Prefix};
+///
+/// assert_eq!(try_format_with_prefix(500_000, Prefix::Mega).unwrap(), "0.5M");
+/// ```
+pub fn try_format_with_prefix(input: u64, prefix: Prefix) -> Result<String, TryReserveError> {
+    let digits = Digits::with_prefix(input, prefix);
+    let mut output = String::new();
+    output.try_reserve_exact(digits.as_str().len())?;
+    output.push_str(digits.as_str());
+    Ok(output)
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a [`Formatter`](fmt::Formatter)) instead of allocating.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::format_into;
+///
+/// let mut output = String::from("users: ");
+/// format_into(&mut output, 1_234).unwrap();
+/// assert_eq!(output, "users: 1.23k");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    format_with_prefix_into(output, input, prefix_of(input))
+}
+
+/// Like [`format_with_prefix`], but writing into an existing writer instead
+/// of allocating.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::{format_with_prefix_into, Prefix};
+///
+/// let mut output = String::new();
+/// format_with_prefix_into(&mut output, 500_000, Prefix::Mega).unwrap();
+/// assert_eq!(output, "0.5M");
+/// ```
+pub fn format_with_prefix_into(
+    output: &mut impl fmt::Write,
+    input: u64,
+    prefix: Prefix,
+) -> fmt::Result {
+    output.write_str(Digits::with_prefix(input, prefix).as_str())
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, without
+/// allocating. Returns the number of bytes written, or `None` if the buffer is
+/// too small ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_234).unwrap();
+/// assert_eq!(&buffer[..len], b"1.23k");
+/// assert_eq!(format_to_slice(&mut [0; 4], 1_234), None);
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    write_to_slice(output, |writer| format_into(writer, input))
+}
+
+/// Run `write` with a writer filling `output`, returning the number of bytes
+/// written, or `None` if they don't fit.
+pub(crate) fn write_to_slice(
+    output: &mut [u8],
+    write: impl FnOnce(&mut SliceWriter<'_>) -> fmt::Result,
+) -> Option<usize> {
+    let mut writer = SliceWriter { output, len: 0 };
+    write(&mut writer).ok()?;
+    Some(writer.len)
+}
+
+/// A [`fmt::Write`] filling a byte slice, failing once it's full.
+pub(crate) struct SliceWriter<'a> {
+    output: &'a mut [u8],
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        self.output
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
     }
-    write!(output, "{unit}").expect("write error");
+}
+
+/// Like [`format`], but into any string type implementing [`fmt::Write`] and
+/// [`Default`], like a small-string type storing short strings inline.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::format_to;
+///
+/// let output: String = format_to(1_234);
+/// assert_eq!(output, "1.23k");
+/// ```
+pub fn format_to<S: fmt::Write + Default>(input: u64) -> S {
+    let mut output = S::default();
+    // Only the writer can fail, which string types don't.
+    let _ = format_into(&mut output, input);
     output
 }
 
+/// Like [`format`], but returning a [`CompactString`](compact_str::CompactString)
+/// (`compact_str` feature), which stores the result inline instead of
+/// allocating it on the heap.
+///
+/// # Examples
+///
+/// ```
+/// use bity::si::format_compact_str;
+///
+/// let output = format_compact_str(1_234_567);
+/// assert_eq!(output, "1.23M");
+/// assert!(!output.is_heap_allocated());
+/// ```
+#[cfg(feature = "compact_str")]
+pub fn format_compact_str(input: u64) -> compact_str::CompactString {
+    format_to(input)
+}
+
+/// A formatted number, written on the stack.
+pub(crate) struct Digits {
+    bytes: [u8; MAX_FORMATTED_LEN],
+    len: usize,
+}
+
+impl Digits {
+    /// Digits of an integer, without any prefix.
+    pub(crate) fn integer(input: u64) -> Self {
+        let mut digits = Self {
+            bytes: [0; MAX_FORMATTED_LEN],
+            len: 0,
+        };
+        digits.push_integer(input);
+        digits
+    }
+
+    /// Digits of an integer formatted using a given SI prefix.
+    fn with_prefix(input: u64, prefix: Prefix) -> Self {
+        let factor = prefix.factor();
+        let mut digits = Self::integer(input / factor);
+        // Keep two fraction digits and drop the trailing zeroes.
+        let hundredths = input % factor / (factor / 100).max(1);
+        if hundredths != 0 {
+            let (tenths, hundredths) = (hundredths / 10, hundredths % 10);
+            digits.push(b'.');
+            digits.push(b'0' + tenths as u8);
+            if hundredths != 0 {
+                digits.push(b'0' + hundredths as u8);
+            }
+        }
+        for &byte in prefix.symbol().as_bytes() {
+            digits.push(byte);
+        }
+        digits
+    }
+
+    /// Digits of a fixed-point number, `factor` (a power of ten) being the
+    /// representation of `1`, without trailing fraction zeroes.
+    #[cfg(feature = "percent")]
+    pub(crate) fn fixed(input: u64, factor: u64) -> Self {
+        let mut digits = Self::integer(input / factor);
+        let (mut fraction, mut unit) = (input % factor, factor / 10);
+        if fraction != 0 {
+            digits.push(b'.');
+        }
+        while fraction != 0 {
+            digits.push(b'0' + (fraction / unit) as u8);
+            fraction %= unit;
+            unit /= 10;
+        }
+        digits
+    }
+
+    fn push(&mut self, byte: u8) {
+        if let Some(slot) = self.bytes.get_mut(self.len) {
+            *slot = byte;
+            self.len += 1;
+        }
+    }
+
+    fn push_integer(&mut self, mut input: u64) {
+        // Digits come out in reverse order, from the least significant one.
+        let mut reversed = [0; 20];
+        let mut count = 0;
+        while let Some(slot) = reversed.get_mut(count) {
+            *slot = b'0' + (input % 10) as u8;
+            count += 1;
+            input /= 10;
+            if input == 0 {
+                break;
+            }
+        }
+        for &digit in reversed[..count].iter().rev() {
+            self.push(digit);
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only ASCII bytes are pushed.
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+/// Round an integer to a given number of significant digits (at least `1`),
+/// half away from zero.
+///
+/// This is lossy, but it allows formatting noisy values into tidy strings
+/// (the formatting functions truncate to two fraction digits).
+///
+/// # Examples
+/// ```
+/// use bity::si::{format, quantize};
+///
+/// assert_eq!(quantize(5_099_876, 2), 5_100_000);
+/// assert_eq!(format(5_099_876), "5.09M");
+/// assert_eq!(format(quantize(5_099_876, 2)), "5.1M");
+/// assert_eq!(quantize(1_234, 3), 1_230);
+/// assert_eq!(quantize(12, 3), 12);
+/// ```
+pub fn quantize(input: u64, significant: u32) -> u64 {
+    let digits = input.checked_ilog10().map_or(1, |log| log + 1);
+    let Some(factor) = 10u64.checked_pow(digits.saturating_sub(significant.max(1))) else {
+        return input;
+    };
+    let (quotient, remainder) = (input / factor, input % factor);
+    let quotient = if remainder >= factor - factor / 2 {
+        quotient + 1
+    } else {
+        quotient
+    };
+    quotient.checked_mul(factor).unwrap_or(input - remainder)
+}
+
 #[cfg(feature = "serde")]
 crate::impl_serde!(
+    expecting: r#"a SI prefixed number like "1.5k", "12M" or 1500"#,
+    example: 1_500,
     ser:
     /// Serialize a given `u64` into a SI prefixed string.
     ///
@@ -313,10 +892,69 @@ crate::impl_serde!(
     /// ```
 );
 
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
 
+    #[test]
+    fn parse_const() {
+        let additional_units = &[("b", 1), ("B", 8), ("page", 50)];
+        for input in [
+            "12",
+            "12.345k",
+            "0.12k",
+            "12.3P",
+            "18E",
+            "0.2",
+            "012.340k",
+            "12.3456k",
+            ".5k",
+            "5.k",
+            " 12k",
+            "12k ",
+            "12 k",
+            "+12",
+            "12kB",
+            "2kpage",
+            "1.5Pb",
+            "k",
+            ".k",
+            ".0k",
+            "1.+5k",
+            "1.1.",
+            "1.1.k",
+            ".1.1k",
+            "12kk",
+            "12kM",
+            "12k M",
+            "12kbit",
+            "12€",
+            "",
+            "18446744073709551615",
+            "1.25E",
+            "18.446744073709551615E",
+            "1.2345678901234567891E",
+            "0.1234567890123456789k",
+            "0.00000000000000000001",
+            "12.3456789P",
+            "1.5EB",
+        ] {
+            assert_eq!(
+                super::parse_const_with_additional_units(input, additional_units),
+                super::parse_with_additional_units(input, additional_units).ok(),
+                "{input:?}"
+            );
+        }
+
+        // Overflows.
+        assert_eq!(super::parse_const("19E"), None);
+        assert_eq!(super::parse_const_with_additional_units("3EB", additional_units), None);
+        assert_eq!(super::parse_const("18446744073709551616"), None);
+    }
+
     #[test]
     fn parse() {
         assert_eq!(super::parse("12.345k").unwrap(), 12_345);
@@ -343,14 +981,16 @@ mod tests {
         assert_eq!(super::parse("12 k").unwrap(), 12_000);
 
         // Invalids.
-        assert!(matches!(super::parse("k"), Err(Error::ParseIntError("", None))));
+        assert!(matches!(super::parse("k"), Err(Error::EmptyInput)));
         assert!(matches!(super::parse(".k"), Err(Error::ParseIntError(".", None))));
-        assert!(matches!(super::parse("1.1."), Err(Error::ParseIntError("1.", Some(_)))));
-        assert!(matches!(super::parse("1.1.k"), Err(Error::ParseIntError("1.", Some(_)))));
-        assert!(matches!(super::parse("1.1.1k"), Err(Error::ParseIntError("1.1", Some(_)))));
-        assert!(matches!(super::parse(".1.1k"), Err(Error::ParseIntError("1.1", Some(_)))));
-        assert!(matches!(super::parse("12kk"), Err(Error::InvalidUnit("kk"))));
-        assert!(matches!(super::parse("12kM"), Err(Error::InvalidUnit("kM"))));
+        assert_eq!(super::parse(".0k").unwrap(), 0);
+        assert!(matches!(super::parse("1.+5k"), Err(Error::TrailingGarbage("+5"))));
+        assert!(matches!(super::parse("1.1."), Err(Error::TrailingGarbage("."))));
+        assert!(matches!(super::parse("1.1.k"), Err(Error::TrailingGarbage("."))));
+        assert!(matches!(super::parse("1.1.1k"), Err(Error::TrailingGarbage(".1"))));
+        assert!(matches!(super::parse(".1.1k"), Err(Error::TrailingGarbage(".1"))));
+        assert!(matches!(super::parse("12kk"), Err(Error::MultipleUnits("kk"))));
+        assert!(matches!(super::parse("12kM"), Err(Error::MultipleUnits("kM"))));
         assert!(matches!(super::parse("12k M"), Err(Error::InvalidUnit("k M"))));
     }
 
@@ -375,19 +1015,19 @@ mod tests {
 
         assert!(matches!(
             super::parse_with_additional_units("12hh", additional_units),
-            Err(Error::InvalidUnit("hh"))
+            Err(Error::MultipleUnits("hh"))
         ));
         assert!(matches!(
             super::parse_with_additional_units("12HH", additional_units),
-            Err(Error::InvalidUnit("HH"))
+            Err(Error::MultipleUnits("HH"))
         ));
         assert!(matches!(
             super::parse_with_additional_units("12hH", additional_units),
-            Err(Error::InvalidUnit("hH"))
+            Err(Error::MultipleUnits("hH"))
         ));
         assert!(matches!(
             super::parse_with_additional_units("12Hh", additional_units),
-            Err(Error::InvalidUnit("Hh"))
+            Err(Error::MultipleUnits("Hh"))
         ));
         assert!(matches!(
             super::parse_with_additional_units("12Q", additional_units),
@@ -401,7 +1041,7 @@ mod tests {
         assert_eq!(super::parse_with_additional_units("12kAC", additional_units).unwrap(), 24_000);
         assert!(matches!(
             super::parse_with_additional_units("12ACk", additional_units),
-            Err(Error::InvalidUnit("ACk"))
+            Err(Error::MultipleUnits("ACk"))
         )); // Custom units should come last.
     }
 
@@ -426,5 +1066,109 @@ mod tests {
 
         // Extra.
         assert_eq!(super::format(1_200), "1.2k"); // Zeroes stripped.
+        assert_eq!(super::format(1_050), "1.05k"); // Leading fraction zero kept.
+        assert_eq!(super::format(1_080_000_000_000), "1.08T"); // Leading fraction zero kept.
+        assert_eq!(super::format(1_005), "1k"); // Truncated fraction.
+        assert_eq!(super::format(1_001_000), "1M"); // Truncated fraction.
+        assert_eq!(super::format(u64::MAX), "18.44E");
+    }
+
+    #[test]
+    fn try_format() {
+        for input in [0, 1, 1_050, 12_345_678, u64::MAX] {
+            assert_eq!(super::try_format(input).unwrap(), super::format(input));
+        }
+        assert_eq!(
+            super::try_format_with_prefix(u64::MAX, super::Prefix::None).unwrap(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            super::try_format_with_prefix(u64::MAX, super::Prefix::Kilo).unwrap(),
+            "18446744073709551.61k"
+        );
+    }
+
+    #[test]
+    fn format_into() {
+        struct Users(u64);
+
+        impl std::fmt::Display for Users {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                super::format_into(f, self.0)?;
+                f.write_str(" users")
+            }
+        }
+
+        assert_eq!(Users(1_234).to_string(), "1.23k users");
+        assert_eq!(Users(0).to_string(), "0 users");
+
+        let mut output = String::with_capacity(64);
+        for input in [0, 1_050, 12_345_678, u64::MAX] {
+            output.clear();
+            super::format_into(&mut output, input).unwrap();
+            assert_eq!(output, super::format(input));
+        }
+        output.clear();
+        super::format_with_prefix_into(&mut output, u64::MAX, super::Prefix::None).unwrap();
+        assert_eq!(output, "18446744073709551615");
+
+        // A single allocation, of the exact size.
+        assert_eq!(super::format(u64::MAX).capacity(), "18.44E".len());
+
+        assert_eq!(super::format_to::<String>(12_345_678), "12.34M");
+        #[cfg(feature = "compact_str")]
+        for input in [0, 1_050, 12_345_678, u64::MAX] {
+            let output = super::format_compact_str(input);
+            assert_eq!(output, super::format(input));
+            assert!(!output.is_heap_allocated());
+        }
+    }
+
+    #[test]
+    fn format_to_slice() {
+        let mut buffer = [0; super::MAX_FORMATTED_LEN];
+        for input in [0, 1_050, 12_345_678, u64::MAX] {
+            let len = super::format_to_slice(&mut buffer, input).unwrap();
+            assert_eq!(std::str::from_utf8(&buffer[..len]), Ok(super::format(input).as_str()));
+        }
+        let len = super::write_to_slice(&mut buffer, |writer| {
+            super::format_with_prefix_into(writer, u64::MAX, super::Prefix::None)
+        });
+        assert_eq!(len, Some(super::MAX_FORMATTED_LEN - 1));
+
+        assert_eq!(super::format_to_slice(&mut buffer[..5], 1_234), Some(5));
+        assert_eq!(super::format_to_slice(&mut buffer[..4], 1_234), None);
+        assert_eq!(super::format_to_slice(&mut [], 0), None);
+    }
+
+    #[test]
+    fn quantize() {
+        assert_eq!(super::quantize(0, 2), 0);
+        assert_eq!(super::quantize(7, 2), 7);
+        assert_eq!(super::quantize(5_099_876, 2), 5_100_000);
+        assert_eq!(super::quantize(5_049_999, 2), 5_000_000);
+        assert_eq!(super::quantize(5_050_000, 2), 5_100_000);
+        assert_eq!(super::quantize(1_234, 1), 1_000);
+        assert_eq!(super::quantize(1_234, 0), 1_000);
+        assert_eq!(super::quantize(1_234, 30), 1_234);
+        assert_eq!(super::quantize(999_999, 2), 1_000_000);
+        assert_eq!(super::quantize(15, 1), 20);
+        assert_eq!(super::quantize(u64::MAX, 2), 18_000_000_000_000_000_000);
+        assert_eq!(super::quantize(u64::MAX, 1), 10_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn format_with_prefix() {
+        use super::Prefix;
+
+        assert_eq!(super::format_with_prefix(0, Prefix::None), "0");
+        assert_eq!(super::format_with_prefix(0, Prefix::Mega), "0M");
+        assert_eq!(super::format_with_prefix(12_345, Prefix::None), "12345");
+        assert_eq!(super::format_with_prefix(12_345, Prefix::Kilo), "12.34k");
+        assert_eq!(super::format_with_prefix(12_345, Prefix::Mega), "0.01M");
+        assert_eq!(super::format_with_prefix(1_234, Prefix::Mega), "0M"); // Truncated fraction.
+        assert_eq!(super::format_with_prefix(12_300_000_000, Prefix::Mega), "12300M");
+        assert_eq!(super::format_with_prefix(u64::MAX, Prefix::Exa), "18.44E");
+        assert_eq!(super::format_with_prefix(u64::MAX, Prefix::Kilo), "18446744073709551.61k");
     }
 }
@@ -0,0 +1,91 @@
+//! [`sqlx`](https://docs.rs/sqlx) support: [`Limit`]s are stored as `BIGINT`
+//! columns, unlimited being `NULL`.
+//!
+//! Values above `i64::MAX` can't be stored, and negative values can't be
+//! read.
+
+use sqlx::{
+    encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type, TypeInfo, ValueRef,
+};
+
+use crate::limit::Limit;
+
+impl<U, DB: Database> Type<DB> for Limit<U>
+where
+    i64: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        i64::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        ty.is_null() || i64::compatible(ty)
+    }
+}
+
+impl<'q, U, DB: Database> Encode<'q, DB> for Limit<U>
+where
+    i64: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        match self.value() {
+            Some(value) => i64::try_from(value)?.encode_by_ref(buf),
+            None => Ok(IsNull::Yes),
+        }
+    }
+}
+
+impl<'r, U, DB: Database> Decode<'r, DB> for Limit<U>
+where
+    i64: Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(Limit::unlimited());
+        }
+        Ok(Limit::new(u64::try_from(i64::decode(value)?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Connection, Row, SqliteConnection};
+
+    use crate::{limit::Limit, unit::Si};
+
+    #[tokio::test]
+    async fn round_trip() {
+        let mut connection = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE quotas (quota BIGINT)")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        for limit in [Limit::<Si>::new(1_500), Limit::unlimited()] {
+            sqlx::query("INSERT INTO quotas VALUES (?)")
+                .bind(limit)
+                .execute(&mut connection)
+                .await
+                .unwrap();
+        }
+        let rows = sqlx::query("SELECT quota FROM quotas")
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+        let limits = rows
+            .iter()
+            .map(|row| row.get::<Limit<Si>, _>("quota"))
+            .collect::<Vec<_>>();
+        assert_eq!(limits, [Limit::new(1_500), Limit::unlimited()]);
+
+        assert!(sqlx::query("INSERT INTO quotas VALUES (?)")
+            .bind(Limit::<Si>::new(u64::MAX))
+            .execute(&mut connection)
+            .await
+            .is_err());
+        let row = sqlx::query("SELECT -1 AS quota")
+            .fetch_one(&mut connection)
+            .await
+            .unwrap();
+        assert!(row.try_get::<Limit<Si>, _>("quota").is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! Rate limiting.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::throttle::TokenBucket;
+//!
+//! let mut bucket = TokenBucket::new("10MB/s", "50MB").unwrap();
+//! assert!(bucket.try_consume(30_000_000));
+//! assert!(bucket.try_consume(20_000_000));
+//! assert!(!bucket.try_consume(20_000_000));
+//! ```
+//!
+//! Blocking I/O can be throttled using [`ThrottledReader`] and
+//! [`ThrottledWriter`]:
+//!
+//! ```
+//! use std::io::Read;
+//!
+//! use bity::{byteps, throttle::ThrottledReader};
+//!
+//! let data = vec![0; 300_000];
+//! let mut reader = ThrottledReader::new(&data[..], byteps::parse("1MB/s").unwrap());
+//! let mut output = Vec::new();
+//! reader.read_to_end(&mut output).unwrap();
+//! assert_eq!(output.len(), 300_000);
+//! ```
+
+use std::{
+    io::{self, Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{byte, byteps, error::Error};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Token bucket rate limiter, where each token is a byte.
+///
+/// The bucket is refilled at `rate` bytes per second, and can hold at most
+/// `burst` bytes. It starts full.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: u64,
+    burst: u64,
+    // Available bytes, multiplied by `NANOS_PER_SEC` to avoid precision loss
+    // while refilling.
+    tokens: u128,
+    last: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full token bucket from a data-rate and a burst size strings.
+    ///
+    /// The rate is parsed using [`byteps::parse`] and the burst size using
+    /// [`byte::parse`].
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{throttle::TokenBucket, Error};
+    ///
+    /// let bucket = TokenBucket::new("10MB/s", "50MB").unwrap();
+    /// assert_eq!(bucket.rate(), 10_000_000);
+    /// assert_eq!(bucket.burst(), 50_000_000);
+    ///
+    /// assert!(matches!(TokenBucket::new("10MB/s", "50Q"), Err(Error::InvalidUnit("Q"))));
+    /// ```
+    pub fn new<'a>(rate: &'a str, burst: &'a str) -> Result<Self, Error<'a>> {
+        Ok(Self::from_values(byteps::parse(rate)?, byte::parse(burst)?))
+    }
+
+    /// Create a full token bucket from a data-rate (in bytes per second) and a
+    /// burst size (in bytes).
+    pub fn from_values(rate: u64, burst: u64) -> Self {
+        Self {
+            rate,
+            burst,
+            tokens: u128::from(burst) * NANOS_PER_SEC,
+            last: Instant::now(),
+        }
+    }
+
+    /// Refill rate, in bytes per second.
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// Capacity of the bucket, in bytes.
+    pub fn burst(&self) -> u64 {
+        self.burst
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let Some(elapsed) = now.checked_duration_since(self.last) else {
+            return;
+        };
+        self.tokens = self
+            .tokens
+            .saturating_add(u128::from(self.rate).saturating_mul(elapsed.as_nanos()))
+            .min(u128::from(self.burst) * NANOS_PER_SEC);
+        self.last = now;
+    }
+
+    /// Number of bytes that can be consumed right now.
+    pub fn available(&mut self) -> u64 {
+        self.available_at(Instant::now())
+    }
+
+    /// Like [`available`](Self::available) but at a given instant.
+    pub fn available_at(&mut self, now: Instant) -> u64 {
+        self.refill(now);
+        (self.tokens / NANOS_PER_SEC) as u64
+    }
+
+    /// Consume `bytes` from the bucket if enough are available, returns
+    /// whether they were consumed.
+    ///
+    /// Consuming more bytes than the burst size will always fail.
+    pub fn try_consume(&mut self, bytes: u64) -> bool {
+        self.try_consume_at(bytes, Instant::now())
+    }
+
+    /// Like [`try_consume`](Self::try_consume) but at a given instant.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use bity::throttle::TokenBucket;
+    ///
+    /// let mut bucket = TokenBucket::new("1kB/s", "2kB").unwrap();
+    /// let now = Instant::now();
+    /// assert!(bucket.try_consume_at(2_000, now));
+    /// assert!(!bucket.try_consume_at(500, now + Duration::from_millis(250)));
+    /// assert!(bucket.try_consume_at(500, now + Duration::from_millis(500)));
+    /// ```
+    pub fn try_consume_at(&mut self, bytes: u64, now: Instant) -> bool {
+        self.refill(now);
+        let needed = u128::from(bytes) * NANOS_PER_SEC;
+        if needed > self.tokens {
+            return false;
+        }
+        self.tokens -= needed;
+        true
+    }
+
+    /// Number of bytes (at most `wanted`) that can be consumed at a given
+    /// instant, without consuming them.
+    ///
+    /// If less than `wanted` bytes (or the burst size if lower) are available,
+    /// returns how long to wait for them to be, or `None` if they never will
+    /// (the rate or the burst size is `0`).
+    fn allowance_at(&mut self, wanted: u64, now: Instant) -> Result<u64, Option<Duration>> {
+        if wanted == 0 {
+            return Ok(0);
+        }
+        self.refill(now);
+        let target = wanted.min(self.burst).max(1);
+        let missing = (u128::from(target) * NANOS_PER_SEC).saturating_sub(self.tokens);
+        if missing == 0 {
+            return Ok(wanted.min((self.tokens / NANOS_PER_SEC) as u64));
+        }
+        if self.rate == 0 || target > self.burst {
+            return Err(None);
+        }
+        let nanos = missing.div_ceil(u128::from(self.rate));
+        Err(Some(Duration::new(
+            u64::try_from(nanos / NANOS_PER_SEC).unwrap_or(u64::MAX),
+            (nanos % NANOS_PER_SEC) as u32,
+        )))
+    }
+
+    /// Consume `bytes` from the bucket, even if less are available.
+    fn consume(&mut self, bytes: u64) {
+        self.tokens = self
+            .tokens
+            .saturating_sub(u128::from(bytes) * NANOS_PER_SEC);
+    }
+}
+
+/// Error returned when the token bucket will never hold a single byte.
+fn never_allowed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "throttled with a data-rate or a burst size of 0")
+}
+
+/// Block until `wanted` bytes (or less) can be transferred, returns how many.
+///
+/// Fails if the bucket will never hold a single byte.
+fn wait_allowance(bucket: &mut TokenBucket, wanted: usize) -> io::Result<usize> {
+    loop {
+        match bucket.allowance_at(wanted as u64, Instant::now()) {
+            Ok(allowance) => return Ok(allowance as usize),
+            Err(Some(wait)) => thread::sleep(wait),
+            Err(None) => return Err(never_allowed()),
+        }
+    }
+}
+
+/// [`Read`] adapter limiting the data-rate of the wrapped reader using a
+/// [`TokenBucket`].
+///
+/// Reads block (sleep) until enough bytes are available in the bucket, and
+/// fail if the bucket will never hold a byte (its rate or burst size is `0`).
+#[derive(Debug)]
+pub struct ThrottledReader<R> {
+    inner: R,
+    bucket: TokenBucket,
+}
+
+impl<R> ThrottledReader<R> {
+    /// Wrap a reader, limiting it to `rate` bytes per second with a burst size
+    /// of one second worth of data.
+    pub fn new(inner: R, rate: u64) -> Self {
+        Self::with_bucket(inner, TokenBucket::from_values(rate, rate))
+    }
+
+    /// Wrap a reader, limiting it using the given token bucket.
+    pub fn with_bucket(inner: R, bucket: TokenBucket) -> Self {
+        Self { inner, bucket }
+    }
+
+    /// Get a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let allowance = wait_allowance(&mut self.bucket, buf.len())?;
+        let read = self.inner.read(&mut buf[..allowance])?;
+        self.bucket.consume(read as u64);
+        Ok(read)
+    }
+}
+
+/// [`Write`] adapter limiting the data-rate of the wrapped writer using a
+/// [`TokenBucket`].
+///
+/// Writes block (sleep) until enough bytes are available in the bucket, and
+/// fail if the bucket will never hold a byte (its rate or burst size is `0`).
+#[derive(Debug)]
+pub struct ThrottledWriter<W> {
+    inner: W,
+    bucket: TokenBucket,
+}
+
+impl<W> ThrottledWriter<W> {
+    /// Wrap a writer, limiting it to `rate` bytes per second with a burst size
+    /// of one second worth of data.
+    pub fn new(inner: W, rate: u64) -> Self {
+        Self::with_bucket(inner, TokenBucket::from_values(rate, rate))
+    }
+
+    /// Wrap a writer, limiting it using the given token bucket.
+    pub fn with_bucket(inner: W, bucket: TokenBucket) -> Self {
+        Self { inner, bucket }
+    }
+
+    /// Get a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let allowance = wait_allowance(&mut self.bucket, buf.len())?;
+        let written = self.inner.write(&buf[..allowance])?;
+        self.bucket.consume(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ThrottledReader, ThrottledWriter, TokenBucket};
+
+    #[test]
+    fn token_bucket() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut bucket = TokenBucket::from_values(1_000, 3_000);
+        bucket.last = start;
+        assert_eq!(bucket.available_at(at(0)), 3_000);
+
+        // Burst.
+        assert!(bucket.try_consume_at(3_000, at(0)));
+        assert!(!bucket.try_consume_at(1, at(0)));
+
+        // Refill.
+        assert_eq!(bucket.available_at(at(1)), 1);
+        assert!(!bucket.try_consume_at(1_000, at(999)));
+        assert!(bucket.try_consume_at(1_000, at(1_000)));
+        assert_eq!(bucket.available_at(at(1_000)), 0);
+
+        // Capacity.
+        assert_eq!(bucket.available_at(at(60_000)), 3_000);
+        assert!(!bucket.try_consume_at(3_001, at(120_000)));
+
+        // Past instants don't refill.
+        assert!(bucket.try_consume_at(3_000, at(120_000)));
+        assert_eq!(bucket.available_at(at(0)), 0);
+    }
+
+    #[test]
+    fn allowance() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut bucket = TokenBucket::from_values(1_000, 2_000);
+        bucket.last = start;
+
+        assert_eq!(bucket.allowance_at(0, at(0)), Ok(0));
+        assert_eq!(bucket.allowance_at(500, at(0)), Ok(500));
+        assert_eq!(bucket.allowance_at(5_000, at(0)), Ok(2_000));
+
+        bucket.consume(1_900);
+        assert_eq!(bucket.allowance_at(100, at(0)), Ok(100));
+        assert_eq!(bucket.allowance_at(1_000, at(0)), Err(Some(Duration::from_millis(900))));
+        assert_eq!(bucket.allowance_at(5_000, at(0)), Err(Some(Duration::from_millis(1_900))));
+        assert_eq!(bucket.allowance_at(1_000, at(900)), Ok(1_000));
+
+        bucket.consume(5_000);
+        assert_eq!(bucket.allowance_at(1, at(900)), Err(Some(Duration::from_millis(1))));
+        assert_eq!(bucket.allowance_at(0, at(900)), Ok(0));
+
+        let mut bucket = TokenBucket::from_values(0, 2_000);
+        bucket.consume(2_000);
+        assert_eq!(bucket.allowance_at(1, Instant::now()), Err(None));
+        let mut bucket = TokenBucket::from_values(1_000, 0);
+        assert_eq!(bucket.allowance_at(1, Instant::now()), Err(None));
+        assert_eq!(bucket.allowance_at(0, Instant::now()), Ok(0));
+    }
+
+    #[test]
+    fn token_bucket_extremes() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::from_values(u64::MAX, u64::MAX);
+        bucket.last = start;
+        assert!(bucket.try_consume_at(u64::MAX, start));
+        assert!(bucket.try_consume_at(u64::MAX, start + Duration::from_secs(1)));
+
+        let mut bucket = TokenBucket::from_values(0, 0);
+        assert!(bucket.try_consume_at(0, start));
+        assert!(!bucket.try_consume_at(1, start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn reader() {
+        use std::io::Read;
+
+        let data = vec![1; 150_000];
+        let mut reader = ThrottledReader::new(&data[..], 100_000);
+        let start = Instant::now();
+        let mut output = vec![0; 150_000];
+        reader.read_exact(&mut output).unwrap();
+        assert_eq!(output, data);
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn reader_empty_bucket() {
+        use std::io::{ErrorKind, Read};
+
+        let data = [1; 10];
+        let mut reader = ThrottledReader::new(&data[..], 0);
+        assert_eq!(reader.read(&mut []).unwrap(), 0);
+        assert_eq!(reader.read(&mut [0; 10]).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+        let mut reader = ThrottledReader::with_bucket(&data[..], TokenBucket::from_values(0, 5));
+        let mut output = [0; 5];
+        reader.read_exact(&mut output).unwrap();
+        assert!(reader.read(&mut output).is_err());
+    }
+
+    #[test]
+    fn writer() {
+        use std::io::Write;
+
+        let mut writer =
+            ThrottledWriter::with_bucket(Vec::new(), TokenBucket::from_values(100_000, 50_000));
+        let start = Instant::now();
+        writer.write_all(&[1; 100_000]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref().len(), 100_000);
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn writer_empty_bucket() {
+        use std::io::{ErrorKind, Write};
+
+        let mut writer =
+            ThrottledWriter::with_bucket(Vec::new(), TokenBucket::from_values(1_000, 0));
+        assert_eq!(writer.write(&[]).unwrap(), 0);
+        assert_eq!(writer.write(&[1; 10]).unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
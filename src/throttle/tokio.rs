@@ -0,0 +1,257 @@
+//! [`tokio`](https://docs.rs/tokio) throttled I/O adapters.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread", start_paused = true)]
+//! # async fn main() {
+//! use bity::{byteps, throttle::tokio::ThrottledReader};
+//! use tokio::io::AsyncReadExt;
+//!
+//! let data = vec![0; 3_000];
+//! let mut reader = ThrottledReader::new(&data[..], byteps::parse("1kB/s").unwrap());
+//!
+//! let start = tokio::time::Instant::now();
+//! let mut output = vec![0; 3_000];
+//! reader.read_exact(&mut output).await.unwrap();
+//! assert_eq!(start.elapsed().as_secs(), 2);
+//! # }
+//! ```
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::{Instant, Sleep},
+};
+
+use super::TokenBucket;
+
+/// Wait until `wanted` bytes (or less) can be transferred, returns how many.
+///
+/// Fails if the bucket will never hold a single byte.
+fn poll_allowance(
+    bucket: &mut TokenBucket,
+    sleep: &mut Option<Pin<Box<Sleep>>>,
+    wanted: u64,
+    cx: &mut Context<'_>,
+) -> Poll<io::Result<u64>> {
+    loop {
+        if let Some(sleep) = sleep.as_mut() {
+            ready!(sleep.as_mut().poll(cx));
+        }
+        *sleep = None;
+
+        match bucket.allowance_at(wanted, Instant::now().into_std()) {
+            Ok(allowance) => return Poll::Ready(Ok(allowance)),
+            Err(Some(wait)) => {
+                // Avoid overflowing the timer for very long waits.
+                let wait = wait.min(Duration::from_secs(86_400 * 365));
+                *sleep = Some(Box::pin(tokio::time::sleep(wait)));
+            }
+            Err(None) => return Poll::Ready(Err(super::never_allowed())),
+        }
+    }
+}
+
+/// [`AsyncRead`] adapter limiting the data-rate of the wrapped reader using a
+/// [`TokenBucket`].
+#[derive(Debug)]
+pub struct ThrottledReader<R> {
+    inner: R,
+    bucket: TokenBucket,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<R> ThrottledReader<R> {
+    /// Wrap a reader, limiting it to `rate` bytes per second with a burst size
+    /// of one second worth of data.
+    pub fn new(inner: R, rate: u64) -> Self {
+        Self::with_bucket(inner, TokenBucket::from_values(rate, rate))
+    }
+
+    /// Wrap a reader, limiting it using the given token bucket.
+    pub fn with_bucket(inner: R, bucket: TokenBucket) -> Self {
+        Self {
+            inner,
+            bucket,
+            sleep: None,
+        }
+    }
+
+    /// Get a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ThrottledReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let allowance =
+            ready!(poll_allowance(&mut this.bucket, &mut this.sleep, buf.remaining() as u64, cx))?;
+
+        let mut limited = ReadBuf::new(buf.initialize_unfilled_to(allowance as usize));
+        ready!(Pin::new(&mut this.inner).poll_read(cx, &mut limited))?;
+        let read = limited.filled().len();
+        buf.advance(read);
+        this.bucket.consume(read as u64);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// [`AsyncWrite`] adapter limiting the data-rate of the wrapped writer using a
+/// [`TokenBucket`].
+#[derive(Debug)]
+pub struct ThrottledWriter<W> {
+    inner: W,
+    bucket: TokenBucket,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<W> ThrottledWriter<W> {
+    /// Wrap a writer, limiting it to `rate` bytes per second with a burst size
+    /// of one second worth of data.
+    pub fn new(inner: W, rate: u64) -> Self {
+        Self::with_bucket(inner, TokenBucket::from_values(rate, rate))
+    }
+
+    /// Wrap a writer, limiting it using the given token bucket.
+    pub fn with_bucket(inner: W, bucket: TokenBucket) -> Self {
+        Self {
+            inner,
+            bucket,
+            sleep: None,
+        }
+    }
+
+    /// Get a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ThrottledWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let allowance =
+            ready!(poll_allowance(&mut this.bucket, &mut this.sleep, buf.len() as u64, cx))?;
+
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..allowance as usize]))?;
+        this.bucket.consume(written as u64);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        time::Instant,
+    };
+
+    use super::{ThrottledReader, ThrottledWriter};
+    use crate::throttle::TokenBucket;
+
+    #[tokio::test(start_paused = true)]
+    async fn reader() {
+        let data = vec![1; 10_000];
+        let mut reader = ThrottledReader::new(&data[..], 1_000);
+        let start = Instant::now();
+        let mut output = vec![0; 10_000];
+        reader.read_exact(&mut output).await.unwrap();
+        assert_eq!(output, data);
+        assert_eq!(start.elapsed().as_secs(), 9);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reader_burst() {
+        let data = vec![1; 10_000];
+        let mut reader =
+            ThrottledReader::with_bucket(&data[..], TokenBucket::from_values(1_000, 5_000));
+        let start = Instant::now();
+        let mut output = [0; 5_000];
+        reader.read_exact(&mut output).await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 0);
+        reader.read_exact(&mut output).await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reader_empty_bucket() {
+        let data = [1; 10];
+        let mut reader = ThrottledReader::new(&data[..], 0);
+        assert_eq!(reader.read(&mut []).await.unwrap(), 0);
+        assert_eq!(
+            reader.read(&mut [0; 10]).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let mut reader = ThrottledReader::with_bucket(&data[..], TokenBucket::from_values(0, 5));
+        let mut output = [0; 5];
+        reader.read_exact(&mut output).await.unwrap();
+        assert!(reader.read(&mut output).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writer() {
+        let mut writer = ThrottledWriter::new(Vec::new(), 2_000);
+        let start = Instant::now();
+        writer.write_all(&[1; 10_000]).await.unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(writer.get_ref().len(), 10_000);
+        assert_eq!(start.elapsed().as_secs(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writer_empty_bucket() {
+        let mut writer =
+            ThrottledWriter::with_bucket(Vec::new(), TokenBucket::from_values(1_000, 0));
+        assert_eq!(writer.write(&[]).await.unwrap(), 0);
+        assert_eq!(writer.write(&[1; 10]).await.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
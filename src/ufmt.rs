@@ -0,0 +1,293 @@
+//! [`ufmt`](https://docs.rs/ufmt) support, to format values on
+//! microcontrollers without the code size of `core::fmt`.
+//!
+//! [`Limit`], [`RelativeOrAbsolute`], [`Prefix`], [`Period`], [`Error`] and
+//! [`ErrorKind`] implement [`uDisplay`], and plain integers are formatted
+//! using [`formatted`] or written into any [`uWrite`] (like a
+//! `heapless::String`) using [`uformat`]. Values are written using the
+//! alloc-free `format_into` functions, without any `core::fmt` formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! # struct Buffer([u8; 32], usize);
+//! # impl ufmt::uWrite for Buffer {
+//! #     type Error = ();
+//! #     fn write_str(&mut self, s: &str) -> Result<(), ()> {
+//! #         let end = self.1 + s.len();
+//! #         self.0.get_mut(self.1..end).ok_or(())?.copy_from_slice(s.as_bytes());
+//! #         self.1 = end;
+//! #         Ok(())
+//! #     }
+//! # }
+//! # impl Buffer {
+//! #     fn as_str(&self) -> &str {
+//! #         core::str::from_utf8(&self.0[..self.1]).unwrap()
+//! #     }
+//! # }
+//! use bity::{limit::ByteLimit, ufmt::formatted, unit::Byteps};
+//! use ufmt::uwrite;
+//!
+//! // A fixed-size buffer implementing `uWrite`.
+//! let mut output = Buffer([0; 32], 0);
+//! uwrite!(
+//!     output,
+//!     "{} up to {}",
+//!     formatted::<Byteps>(5_200_000),
+//!     ByteLimit::new(1_500)
+//! )
+//! .unwrap();
+//! assert_eq!(output.as_str(), "5.2MB/s up to 1.5kB");
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use ufmt::{uDisplay, uWrite, Formatter};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, si::Prefix, unit::Unit, Error, ErrorKind, Period};
+
+/// Forward the strings written using [`fmt::Write`] to a ufmt formatter,
+/// keeping its error.
+struct Writer<'a, 'w, W: uWrite + ?Sized> {
+    f: &'a mut Formatter<'w, W>,
+    error: Option<W::Error>,
+}
+
+impl<W: uWrite + ?Sized> fmt::Write for Writer<'_, '_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.f.write_str(s).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// Run `format` with a [`fmt::Write`] forwarding to `f`, returning the error
+/// of its writer.
+fn write_with<W: uWrite + ?Sized>(
+    f: &mut Formatter<'_, W>,
+    format: impl FnOnce(&mut Writer<'_, '_, W>) -> fmt::Result,
+) -> Result<(), W::Error> {
+    let mut writer = Writer { f, error: None };
+    match (format(&mut writer), writer.error) {
+        (Err(_), Some(err)) => Err(err),
+        // The unit module reported an error without the writer failing, which
+        // the builtin units never do.
+        _ => Ok(()),
+    }
+}
+
+/// An integer formatted using the formatting of the unit `U`, created by
+/// [`formatted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Formatted<U> {
+    value: u64,
+    unit: PhantomData<U>,
+}
+
+/// Wrap an integer, so it's formatted like `U::format` would format it.
+///
+/// # Examples
+/// ```
+/// # struct Buffer([u8; 32], usize);
+/// # impl ufmt::uWrite for Buffer {
+/// #     type Error = ();
+/// #     fn write_str(&mut self, s: &str) -> Result<(), ()> {
+/// #         let end = self.1 + s.len();
+/// #         self.0.get_mut(self.1..end).ok_or(())?.copy_from_slice(s.as_bytes());
+/// #         self.1 = end;
+/// #         Ok(())
+/// #     }
+/// # }
+/// use bity::{ufmt::formatted, unit::Byte};
+/// use ufmt::uwrite;
+///
+/// // A fixed-size buffer implementing `uWrite`.
+/// let mut output = Buffer([0; 32], 0);
+/// uwrite!(output, "{} written", formatted::<Byte>(1_500)).unwrap();
+/// assert_eq!(&output.0[..output.1], b"1.5kB written");
+/// ```
+pub fn formatted<U: Unit>(value: u64) -> Formatted<U> {
+    Formatted {
+        value,
+        unit: PhantomData,
+    }
+}
+
+impl<U: Unit> uDisplay for Formatted<U> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        write_with(f, |writer| U::format_into(writer, self.value))
+    }
+}
+
+/// Format an integer into a [`uWrite`], like `U::format` would format it.
+///
+/// # Examples
+/// ```
+/// # struct Buffer([u8; 32], usize);
+/// # impl ufmt::uWrite for Buffer {
+/// #     type Error = ();
+/// #     fn write_str(&mut self, s: &str) -> Result<(), ()> {
+/// #         let end = self.1 + s.len();
+/// #         self.0.get_mut(self.1..end).ok_or(())?.copy_from_slice(s.as_bytes());
+/// #         self.1 = end;
+/// #         Ok(())
+/// #     }
+/// # }
+/// use bity::{ufmt::uformat, unit::Byte};
+///
+/// // A fixed-size buffer implementing `uWrite`.
+/// let mut output = Buffer([0; 32], 0);
+/// uformat::<Byte, _>(&mut output, 1_500).unwrap();
+/// assert_eq!(&output.0[..output.1], b"1.5kB");
+/// ```
+pub fn uformat<U: Unit, W: uWrite + ?Sized>(output: &mut W, input: u64) -> Result<(), W::Error> {
+    formatted::<U>(input).fmt(&mut Formatter::new(output))
+}
+
+impl<U: Unit> uDisplay for Limit<U> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        match self.value() {
+            Some(value) => formatted::<U>(value).fmt(f),
+            None => f.write_str("unlimited"),
+        }
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Unit> uDisplay for RelativeOrAbsolute<U> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        match (self.as_relative(), self.as_absolute()) {
+            (Some(ppm), _) => write_with(f, |writer| crate::percent::format_into(writer, ppm)),
+            (None, value) => formatted::<U>(value.unwrap_or_default()).fmt(f),
+        }
+    }
+}
+
+impl uDisplay for Prefix {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(self.symbol())
+    }
+}
+
+impl uDisplay for Period {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(self.symbol())
+    }
+}
+
+impl uDisplay for ErrorKind {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+impl uDisplay for Error<'_> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        let (before, input, after) = match self {
+            Error::NotAscii => return f.write_str("input must be ascii"),
+            Error::EmptyInput => return f.write_str("missing number"),
+            Error::InvalidUnit(input) => ("invalid unit \"", input, "\""),
+            Error::MultipleUnits(input) => ("multiple units in \"", input, "\""),
+            Error::NegativeValue(input) => ("negative number \"", input, "\""),
+            Error::FractionTooPrecise(input) => ("too many fraction digits \"", input, "\""),
+            Error::TrailingGarbage(input) => {
+                ("unexpected characters \"", input, "\" after the number")
+            }
+            Error::ParseIntError(input, _) => ("invalid number \"", input, "\""),
+        };
+        f.write_str(before)?;
+        f.write_str(input)?;
+        f.write_str(after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ufmt::{uDisplay, uWrite, uwrite};
+
+    use crate::{
+        limit::Limit,
+        si::Prefix,
+        unit::{Si, Unit},
+        Error, ErrorKind, Period,
+    };
+
+    /// Fixed-size buffer, as used on targets without an allocator.
+    struct Buffer {
+        bytes: [u8; 64],
+        len: usize,
+    }
+
+    impl Buffer {
+        fn new() -> Self {
+            Self {
+                bytes: [0; 64],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl uWrite for Buffer {
+        type Error = ();
+
+        fn write_str(&mut self, s: &str) -> Result<(), ()> {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(())?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    fn write(value: impl uDisplay) -> Buffer {
+        let mut output = Buffer::new();
+        uwrite!(output, "{}", value).unwrap();
+        output
+    }
+
+    #[test]
+    fn uformat() {
+        for input in [0, 1_234, 5_200_000, u64::MAX] {
+            let mut output = Buffer::new();
+            super::uformat::<Si, _>(&mut output, input).unwrap();
+            assert_eq!(output.as_str(), Si::format(input));
+        }
+
+        let mut output = Buffer::new();
+        output.len = 62;
+        assert_eq!(super::uformat::<Si, _>(&mut output, 1_234), Err(()));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(write(super::formatted::<Si>(1_234)).as_str(), "1.23k");
+        assert_eq!(write(Limit::<Si>::new(1_500)).as_str(), "1.5k");
+        assert_eq!(write(Limit::<Si>::unlimited()).as_str(), "unlimited");
+        #[cfg(feature = "percent")]
+        {
+            use crate::relative::RelativeOrAbsolute;
+
+            let value = RelativeOrAbsolute::<Si>::parse("12.5%").unwrap();
+            assert_eq!(write(value).as_str(), "12.5%");
+            let value = RelativeOrAbsolute::<Si>::parse("1.5k").unwrap();
+            assert_eq!(write(value).as_str(), "1.5k");
+        }
+        assert_eq!(write(Prefix::Mega).as_str(), "M");
+        assert_eq!(write(Period::Hour).as_str(), "h");
+        assert_eq!(write(ErrorKind::InvalidUnit).as_str(), ErrorKind::InvalidUnit.as_str());
+        assert_eq!(
+            write(Error::TrailingGarbage(".3")).as_str(),
+            r#"unexpected characters ".3" after the number"#
+        );
+        assert_eq!(write(Error::EmptyInput).as_str(), "missing number");
+    }
+}
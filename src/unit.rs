@@ -0,0 +1,413 @@
+//! Runtime-defined units, and the [`Unit`] trait implemented by the marker
+//! types of the builtin unit modules.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::unit::{Byte, Unit, UnitSystem};
+//!
+//! let messages = UnitSystem::new("msg").with_unit("batch", 100);
+//!
+//! assert_eq!(messages.parse("12msg").unwrap(), 12);
+//! assert_eq!(messages.parse("1.2kmsg").unwrap(), 1_200);
+//! assert_eq!(messages.parse("3batch").unwrap(), 300);
+//! assert_eq!(messages.format(1_200), "1.2kmsg");
+//!
+//! assert_eq!(Byte::parse("1.5kB").unwrap(), 1_500);
+//! assert_eq!(Byte::format(1_500), "1.5kB");
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use crate::{si, Error};
+
+/// A unit defined at runtime, from a format suffix and a `(unit, factor)`
+/// table.
+///
+/// This is the runtime equivalent of the [`define_unit!`](crate::define_unit)
+/// macro, for units that aren't known at compile time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitSystem {
+    suffix: String,
+    units: Vec<(String, u64)>,
+}
+
+impl UnitSystem {
+    /// Create a unit system formatting values with the given suffix.
+    ///
+    /// The suffix (if not empty) is also accepted by [`UnitSystem::parse`],
+    /// with a factor of `1`.
+    pub fn new(suffix: impl Into<String>) -> Self {
+        let suffix = suffix.into();
+        let units = if suffix.is_empty() {
+            Vec::new()
+        } else {
+            vec![(suffix.clone(), 1)]
+        };
+        Self { suffix, units }
+    }
+
+    /// Accept an additional unit when parsing, multiplying the value by
+    /// `factor`.
+    pub fn with_unit(mut self, unit: impl Into<String>, factor: u64) -> Self {
+        self.units.push((unit.into(), factor));
+        self
+    }
+
+    /// Suffix used when formatting.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// Parse a SI prefixed string, optionally followed by one of the units of
+    /// the system, into a number.
+    ///
+    /// Units are matched before the SI prefix, so units starting with a prefix
+    /// letter (like `msg`) can be used without prefix.
+    ///
+    /// Refer to [`si::parse_with_additional_units`] to learn the rules that
+    /// apply.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::{unit::UnitSystem, Error};
+    ///
+    /// let sessions = UnitSystem::new("sess");
+    /// assert_eq!(sessions.parse("12sess").unwrap(), 12);
+    /// assert_eq!(sessions.parse("1.5k").unwrap(), 1_500);
+    /// assert!(matches!(sessions.parse("12req"), Err(Error::InvalidUnit("req"))));
+    /// ```
+    pub fn parse<'a>(&self, input: &'a str) -> Result<u64, Error<'a>> {
+        let units = self
+            .units
+            .iter()
+            .map(|(unit, factor)| (unit.as_str(), *factor))
+            .collect::<Vec<_>>();
+        si::parse_with_additional_units(input, &units)
+    }
+
+    /// Format an integer into a SI prefixed string followed by the suffix of
+    /// the system.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::unit::UnitSystem;
+    ///
+    /// assert_eq!(UnitSystem::new("sess").format(1_234), "1.23ksess");
+    /// ```
+    pub fn format(&self, input: u64) -> String {
+        crate::custom::format(input, &self.suffix)
+    }
+
+    /// Wrap a value so it can be displayed (or serialized) using this unit
+    /// system.
+    ///
+    /// # Examples
+    /// ```
+    /// use bity::unit::UnitSystem;
+    ///
+    /// let sessions = UnitSystem::new("sess");
+    /// assert_eq!(sessions.value(1_234).to_string(), "1.23ksess");
+    /// ```
+    pub fn value(&self, value: u64) -> Value<'_> {
+        Value {
+            system: self,
+            value,
+        }
+    }
+}
+
+/// A value associated with a [`UnitSystem`], created by [`UnitSystem::value`].
+///
+/// It implements [`Display`] and, if the `serde` feature is enabled,
+/// `Serialize`.
+#[derive(Debug, Clone, Copy)]
+pub struct Value<'a> {
+    system: &'a UnitSystem,
+    value: u64,
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.system.format(self.value))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::serde::serialize_with(self.value, serializer, |value| self.system.format(value))
+    }
+}
+
+/// Deserialize an integer or SI prefixed string using the unit system.
+///
+/// # Examples
+/// ```
+/// use bity::unit::UnitSystem;
+/// use serde::de::DeserializeSeed;
+///
+/// let messages = UnitSystem::new("msg");
+/// let deserializer = toml::de::ValueDeserializer::new(r#""1.2kmsg""#);
+/// assert_eq!(messages.deserialize(deserializer).unwrap(), 1_200);
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::de::DeserializeSeed<'de> for &UnitSystem {
+    type Value = u64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde::deserialize_with(
+            deserializer,
+            "an integer or a SI prefixed string",
+            &[],
+            crate::serde::Numbers::Exact,
+            |input| self.parse(input),
+        )
+    }
+}
+
+/// A unit known at compile time, used as a type parameter by typed values
+/// (like [`Limit`](crate::limit::Limit)).
+///
+/// It's implemented by the marker types of this module, one per builtin unit
+/// module, and can be implemented for units generated by
+/// [`define_unit!`](crate::define_unit).
+///
+/// # Examples
+/// ```
+/// use bity::{limit::Limit, unit::Unit};
+///
+/// bity::define_unit! {
+///     /// SI prefixed messages count.
+///     pub mod message {
+///         suffix: "msg",
+///         units: [("msg", 1)],
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// struct Message;
+///
+/// impl Unit for Message {
+///     fn parse(input: &str) -> Result<u64, bity::Error<'_>> {
+///         message::parse(input)
+///     }
+///
+///     fn format(input: u64) -> String {
+///         message::format(input)
+///     }
+/// }
+///
+/// assert_eq!(Limit::<Message>::parse("1.2kmsg").unwrap().value(), Some(1_200));
+/// ```
+pub trait Unit {
+    /// Short description of the accepted inputs, with a few examples, quoted in
+    /// deserialization errors.
+    const EXPECTING: &'static str = "an integer or a SI prefixed string";
+
+    /// Units accepted after the SI prefix, suggested in deserialization
+    /// errors.
+    const UNITS: &'static [&'static str] = &[];
+
+    /// How bare numbers are deserialized.
+    #[cfg(feature = "serde")]
+    #[doc(hidden)]
+    const NUMBERS: crate::__private::Numbers = crate::__private::Numbers::Exact;
+
+    /// Parse a string into a number.
+    fn parse(input: &str) -> Result<u64, Error<'_>>;
+
+    /// Format a number into a string.
+    fn format(input: u64) -> String;
+
+    /// Format a number into a writer.
+    ///
+    /// The builtin units override it to avoid allocating.
+    fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+        output.write_str(&Self::format(input))
+    }
+}
+
+macro_rules! units {
+    ($($(#[doc = $doc:literal])* $(#[cfg($cfg:meta)])? $name:ident => $module:ident,)*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct $name;
+
+            $(#[cfg($cfg)])?
+            impl Unit for $name {
+                #[cfg(feature = "serde")]
+                const EXPECTING: &'static str = crate::$module::EXPECTING;
+
+                const UNITS: &'static [&'static str] = crate::$module::UNITS;
+
+                #[cfg(feature = "serde")]
+                const NUMBERS: crate::__private::Numbers = crate::$module::NUMBERS;
+
+                fn parse(input: &str) -> Result<u64, Error<'_>> {
+                    crate::$module::parse(input)
+                }
+
+                fn format(input: u64) -> String {
+                    crate::$module::format(input)
+                }
+
+                fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+                    crate::$module::format_into(output, input)
+                }
+            }
+
+            $(#[cfg($cfg)])?
+            #[cfg(feature = "schemars")]
+            impl schemars::JsonSchema for $name {
+                fn schema_name() -> std::borrow::Cow<'static, str> {
+                    stringify!($name).into()
+                }
+
+                fn schema_id() -> std::borrow::Cow<'static, str> {
+                    concat!("bity::unit::", stringify!($name)).into()
+                }
+
+                fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                    crate::$module::json_schema(generator)
+                }
+            }
+
+            $(#[cfg($cfg)])?
+            #[cfg(feature = "utoipa")]
+            impl utoipa::PartialSchema for $name {
+                fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+                    crate::$module::openapi_schema()
+                }
+            }
+
+            $(#[cfg($cfg)])?
+            #[cfg(feature = "utoipa")]
+            impl utoipa::ToSchema for $name {}
+        )*
+    };
+}
+
+units! {
+    /// Unit of [`si`].
+    Si => si,
+    /// Unit of [`bit`](crate::bit).
+    #[cfg(feature = "bit")]
+    Bit => bit,
+    /// Unit of [`byte`](crate::byte).
+    #[cfg(feature = "byte")]
+    Byte => byte,
+    /// Unit of [`packet`](crate::packet).
+    #[cfg(feature = "packet")]
+    Packet => packet,
+    /// Unit of [`bps`](crate::bps).
+    #[cfg(feature = "bps")]
+    Bps => bps,
+    /// Unit of [`byteps`](crate::byteps).
+    #[cfg(feature = "byteps")]
+    Byteps => byteps,
+    /// Unit of [`pps`](crate::pps).
+    #[cfg(feature = "pps")]
+    Pps => pps,
+    /// Unit of [`bpp`](crate::bpp).
+    #[cfg(feature = "bpp")]
+    Bpp => bpp,
+    /// Unit of [`hz`](crate::hz).
+    #[cfg(feature = "hz")]
+    Hz => hz,
+    /// Unit of [`watt`](crate::watt).
+    #[cfg(feature = "watt")]
+    Watt => watt,
+    /// Unit of [`ops`](crate::ops).
+    #[cfg(feature = "ops")]
+    Ops => ops,
+    /// Unit of [`percent`](crate::percent).
+    #[cfg(feature = "percent")]
+    Percent => percent,
+    /// Unit of [`compression`](crate::compression).
+    #[cfg(feature = "compression")]
+    Compression => compression,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnitSystem;
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        let system = UnitSystem::new("row").with_unit("page", 50);
+        assert_eq!(system.parse("12").unwrap(), 12);
+        assert_eq!(system.parse("12row").unwrap(), 12);
+        assert_eq!(system.parse("1.2krow").unwrap(), 1_200);
+        assert_eq!(system.parse("2kpage").unwrap(), 100_000);
+        assert!(matches!(system.parse("12rows"), Err(Error::InvalidUnit("rows"))));
+
+        let system = UnitSystem::new("msg").with_unit("batch", u64::MAX);
+        assert_eq!(system.parse("1batch").unwrap(), u64::MAX);
+        assert!(matches!(system.parse("2Ebatch"), Err(Error::ParseIntError("2", None))));
+
+        let system = UnitSystem::new("msg");
+        assert_eq!(system.parse("12msg").unwrap(), 12);
+        assert_eq!(system.parse("1.2kmsg").unwrap(), 1_200);
+        assert_eq!(system.parse(&system.format(12)).unwrap(), 12);
+        let system = UnitSystem::new("events");
+        assert_eq!(system.parse("5events").unwrap(), 5);
+        assert_eq!(system.parse("5 Eevents").unwrap(), 5_000_000_000_000_000_000);
+        assert_eq!(system.parse(&system.format(999)).unwrap(), 999);
+
+        let system = UnitSystem::new("");
+        assert_eq!(system.parse("1.2k").unwrap(), 1_200);
+        assert!(matches!(system.parse("12row"), Err(Error::InvalidUnit("row"))));
+    }
+
+    #[test]
+    fn format() {
+        let system = UnitSystem::new("row");
+        assert_eq!(system.suffix(), "row");
+        assert_eq!(system.format(1_200), "1.2krow");
+        assert_eq!(system.value(0).to_string(), "0row");
+        assert_eq!(UnitSystem::new("").format(1_200), "1.2k");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use serde::de::DeserializeSeed;
+
+        let system = UnitSystem::new("row");
+        assert_eq!(
+            system
+                .deserialize(toml::de::ValueDeserializer::new("12"))
+                .unwrap(),
+            12
+        );
+        assert_eq!(
+            system
+                .deserialize(toml::de::ValueDeserializer::new(r#""1.2krow""#))
+                .unwrap(),
+            1_200
+        );
+        assert!(system
+            .deserialize(toml::de::ValueDeserializer::new(r#""1.2kcol""#))
+            .is_err());
+
+        let messages = UnitSystem::new("msg");
+        let value = toml::Value::try_from(messages.value(12)).unwrap();
+        assert_eq!(value, toml::Value::String("12msg".to_owned()));
+        assert_eq!(messages.deserialize(value).unwrap(), 12);
+        assert_eq!(
+            toml::Value::try_from(system.value(1_200)).unwrap(),
+            toml::Value::String("1.2krow".to_owned())
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! [`uom`](https://docs.rs/uom) conversions between [`Limit`]s and `f64`
+//! quantities: `Information` for bits and bytes, `InformationRate` for
+//! data-rates.
+//!
+//! Unlimited limits are converted into infinite quantities and back, while
+//! other quantities are rounded to the nearest integer of the unit. Values
+//! above `2^53` may lose precision, as they are stored in an `f64`.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{
+//!     limit::{ByteLimit, Limit},
+//!     unit::Byteps,
+//! };
+//! use uom::si::{
+//!     f64::{Information, InformationRate},
+//!     information::kilobit,
+//!     information_rate::megabit_per_second,
+//! };
+//!
+//! let quota = Information::from(ByteLimit::parse("1.5kB").unwrap());
+//! assert_eq!(quota.get::<kilobit>(), 12.0);
+//!
+//! let rate = InformationRate::new::<megabit_per_second>(2.5);
+//! let rate = Limit::<Byteps>::try_from(rate).unwrap();
+//! assert_eq!(rate.to_string(), "312.5kB/s");
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::limit::Limit;
+
+/// Error returned when converting a negative, too large or `NaN` quantity
+/// into a [`Limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutOfRangeError;
+
+impl Display for OutOfRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("quantity out of range")
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+fn to_f64<U>(limit: Limit<U>) -> f64 {
+    limit.value().map_or(f64::INFINITY, |value| value as f64)
+}
+
+fn to_limit<U>(value: f64) -> Result<Limit<U>, OutOfRangeError> {
+    if value == f64::INFINITY {
+        return Ok(Limit::unlimited());
+    }
+    let value = value.round();
+    // `u64::MAX as f64` is `2^64`, which doesn't fit.
+    if value >= 0.0 && value < u64::MAX as f64 {
+        Ok(Limit::new(value as u64))
+    } else {
+        Err(OutOfRangeError)
+    }
+}
+
+macro_rules! conversions {
+    ($(
+        $(#[cfg($cfg:meta)])?
+        $unit:ident => $quantity:ident, $module:ident::$uom_unit:ident,
+    )*) => {
+        $(
+            $(#[cfg($cfg)])?
+            impl From<Limit<crate::unit::$unit>> for uom::si::f64::$quantity {
+                fn from(limit: Limit<crate::unit::$unit>) -> Self {
+                    Self::new::<uom::si::$module::$uom_unit>(to_f64(limit))
+                }
+            }
+
+            $(#[cfg($cfg)])?
+            impl TryFrom<uom::si::f64::$quantity> for Limit<crate::unit::$unit> {
+                type Error = OutOfRangeError;
+
+                fn try_from(quantity: uom::si::f64::$quantity) -> Result<Self, Self::Error> {
+                    to_limit(quantity.get::<uom::si::$module::$uom_unit>())
+                }
+            }
+        )*
+    };
+}
+
+conversions! {
+    #[cfg(feature = "bit")]
+    Bit => Information, information::bit,
+    Byte => Information, information::byte,
+    #[cfg(feature = "bps")]
+    Bps => InformationRate, information_rate::bit_per_second,
+    #[cfg(feature = "byteps")]
+    Byteps => InformationRate, information_rate::byte_per_second,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutOfRangeError;
+    use crate::limit::Limit;
+
+    #[test]
+    fn to_limit() {
+        assert_eq!(super::to_limit::<()>(1_499.5), Ok(Limit::new(1_500)));
+        assert_eq!(super::to_limit::<()>(-0.2), Ok(Limit::new(0)));
+        assert_eq!(super::to_limit::<()>(f64::INFINITY), Ok(Limit::unlimited()));
+        assert_eq!(super::to_limit::<()>(-1.0), Err(OutOfRangeError));
+        assert_eq!(super::to_limit::<()>(f64::NEG_INFINITY), Err(OutOfRangeError));
+        assert_eq!(super::to_limit::<()>(f64::NAN), Err(OutOfRangeError));
+        assert_eq!(super::to_limit::<()>(u64::MAX as f64), Err(OutOfRangeError));
+    }
+
+    #[cfg(feature = "bit")]
+    #[test]
+    fn conversions() {
+        use uom::si::f64::Information;
+
+        use crate::unit::{Bit, Byte};
+
+        let bytes = Information::from(Limit::<Byte>::new(1_500));
+        assert_eq!(Limit::<Bit>::try_from(bytes), Ok(Limit::new(12_000)));
+        assert_eq!(Limit::<Byte>::try_from(bytes), Ok(Limit::new(1_500)));
+        let unlimited = Information::from(Limit::<Bit>::unlimited());
+        assert_eq!(Limit::<Byte>::try_from(unlimited), Ok(Limit::unlimited()));
+    }
+}
@@ -0,0 +1,98 @@
+use utoipa::openapi::{
+    schema::{AnyOfBuilder, ObjectBuilder, Schema, Type},
+    RefOr,
+};
+
+/// Schema of a string matching `pattern` (with a formatted `example`) or an
+/// integer, as accepted by the deserializers of the unit modules.
+pub(crate) fn schema(description: &str, pattern: &str, example: String) -> RefOr<Schema> {
+    AnyOfBuilder::new()
+        .description(Some(description))
+        .item(
+            ObjectBuilder::new()
+                .schema_type(Type::String)
+                .pattern(Some(pattern))
+                .examples([example]),
+        )
+        .item(
+            ObjectBuilder::new()
+                .schema_type(Type::Integer)
+                .minimum(Some(0)),
+        )
+        .into()
+}
+
+#[cfg(all(test, feature = "byteps", feature = "percent"))]
+mod tests {
+    use serde_json::json;
+    use utoipa::PartialSchema;
+
+    #[test]
+    fn schema() {
+        assert_eq!(
+            serde_json::to_value(crate::byteps::openapi_schema()).unwrap(),
+            json!({
+                "description": r#"a data-rate like "1.5kB/s", "12MBps" or 1500"#,
+                "anyOf": [
+                    {
+                        "type": "string",
+                        "pattern": crate::byteps::PATTERN,
+                        "examples": ["5MB/s"],
+                    },
+                    { "type": "integer", "minimum": 0 },
+                ],
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(crate::unit::Percent::schema()).unwrap()["anyOf"][0]["examples"],
+            json!(["12.5%"])
+        );
+    }
+
+    #[cfg(feature = "bps")]
+    #[test]
+    fn derive() {
+        use utoipa::{OpenApi, ToSchema};
+
+        type Watermark = crate::relative::RelativeOrAbsolute<crate::unit::Byte>;
+
+        #[derive(ToSchema)]
+        #[allow(dead_code)]
+        struct Configuration {
+            #[schema(schema_with = crate::bps::openapi_schema)]
+            bandwidth: u64,
+            #[schema(value_type = Option<crate::unit::Byte>)]
+            quota: Option<u64>,
+            limit: crate::limit::ByteLimit,
+            watermark: Watermark,
+        }
+
+        #[derive(OpenApi)]
+        #[openapi(components(schemas(Configuration)))]
+        struct Api;
+
+        let openapi = serde_json::to_value(Api::openapi()).unwrap();
+        let schemas = &openapi["components"]["schemas"];
+        let properties = &schemas["Configuration"]["properties"];
+        assert_eq!(
+            properties["bandwidth"]["description"],
+            r#"a data-rate like "1.5kb/s", "12Mbps" or 1500"#
+        );
+        assert_eq!(properties["bandwidth"]["anyOf"][0]["examples"], json!(["5Mb/s"]));
+        assert_eq!(properties["limit"], json!({ "$ref": "#/components/schemas/ByteLimit" }));
+        assert_eq!(
+            schemas["ByteLimit"]["anyOf"][1],
+            json!({ "type": "string", "enum": ["unlimited", "none"] })
+        );
+        assert_eq!(
+            properties["watermark"],
+            json!({ "$ref": "#/components/schemas/RelativeOrAbsoluteByte" })
+        );
+        assert_eq!(
+            schemas["RelativeOrAbsoluteByte"]["anyOf"][0],
+            json!({ "$ref": "#/components/schemas/Byte" })
+        );
+        assert_eq!(schemas["RelativeOrAbsoluteByte"]["anyOf"][1]["examples"], json!(["10%"]));
+        assert_eq!(schemas["Byte"]["anyOf"][0]["examples"], json!(["5MB"]));
+    }
+}
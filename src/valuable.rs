@@ -0,0 +1,180 @@
+//! [`valuable`](https://docs.rs/valuable) support, to log structured values.
+//!
+//! [`Limit`], [`RelativeOrAbsolute`] and [`Formatted`] integers implement
+//! [`Valuable`] as structures holding both the raw `value` and its `human`
+//! readable string, so log fields don't need to be formatted at every call
+//! site. [`Formatted`] also implements [`Display`], to be recorded using
+//! `tracing`'s `%` sigil when `valuable` support isn't enabled in `tracing`.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::{valuable::formatted, unit::Byteps};
+//! use valuable::Valuable;
+//!
+//! let rate = formatted::<Byteps>(5_200_000);
+//! assert_eq!(rate.to_string(), "5.2MB/s");
+//! assert_eq!(
+//!     format!("{:?}", rate.as_value()),
+//!     r#"Formatted { value: 5200000, human: "5.2MB/s" }"#,
+//! );
+//! ```
+
+use alloc::string::ToString;
+use core::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
+
+use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
+
+#[cfg(feature = "percent")]
+use crate::relative::RelativeOrAbsolute;
+use crate::{limit::Limit, unit::Unit};
+
+static FIELDS: &[NamedField<'static>] = &[NamedField::new("value"), NamedField::new("human")];
+
+#[cfg(feature = "percent")]
+static RELATIVE_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("value"),
+    NamedField::new("relative"),
+    NamedField::new("human"),
+];
+
+/// An integer recorded using the formatting of the unit `U`, created by
+/// [`formatted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Formatted<U> {
+    value: u64,
+    unit: PhantomData<U>,
+}
+
+/// Wrap an integer, so it's recorded along with its formatting by `U::format`.
+///
+/// # Examples
+/// ```
+/// use bity::{valuable::formatted, unit::Byte};
+///
+/// assert_eq!(formatted::<Byte>(1_500).to_string(), "1.5kB");
+/// ```
+pub fn formatted<U: Unit>(value: u64) -> Formatted<U> {
+    Formatted {
+        value,
+        unit: PhantomData,
+    }
+}
+
+impl<U: Unit> Display for Formatted<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        U::format_into(f, self.value)
+    }
+}
+
+impl<U: Unit> Valuable for Formatted<U> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        let human = U::format(self.value);
+        visit.visit_named_fields(&NamedValues::new(
+            FIELDS,
+            &[Value::U64(self.value), Value::String(&human)],
+        ));
+    }
+}
+
+impl<U: Unit> Structable for Formatted<U> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Formatted", Fields::Named(FIELDS))
+    }
+}
+
+/// Unlimited values are recorded as a unit `value`.
+impl<U: Unit> Valuable for Limit<U> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        let human = self.to_string();
+        visit.visit_named_fields(&NamedValues::new(
+            FIELDS,
+            &[self.value().as_value(), Value::String(&human)],
+        ));
+    }
+}
+
+impl<U: Unit> Structable for Limit<U> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Limit", Fields::Named(FIELDS))
+    }
+}
+
+/// Relative values are recorded as ppm, along with a `relative` flag.
+#[cfg(feature = "percent")]
+impl<U: Unit> Valuable for RelativeOrAbsolute<U> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        let (value, relative) = match (self.as_relative(), self.as_absolute()) {
+            (Some(ppm), _) => (ppm, true),
+            (None, value) => (value.unwrap_or_default(), false),
+        };
+        let human = self.to_string();
+        visit.visit_named_fields(&NamedValues::new(
+            RELATIVE_FIELDS,
+            &[
+                Value::U64(value),
+                Value::Bool(relative),
+                Value::String(&human),
+            ],
+        ));
+    }
+}
+
+#[cfg(feature = "percent")]
+impl<U: Unit> Structable for RelativeOrAbsolute<U> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("RelativeOrAbsolute", Fields::Named(RELATIVE_FIELDS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use valuable::Valuable;
+
+    use super::formatted;
+    use crate::{limit::Limit, unit::Si};
+
+    #[test]
+    fn valuable() {
+        assert_eq!(
+            format!("{:?}", formatted::<Si>(1_234).as_value()),
+            r#"Formatted { value: 1234, human: "1.23k" }"#
+        );
+        assert_eq!(
+            format!("{:?}", Limit::<Si>::new(1_500).as_value()),
+            r#"Limit { value: 1500, human: "1.5k" }"#
+        );
+        assert_eq!(
+            format!("{:?}", Limit::<Si>::unlimited().as_value()),
+            r#"Limit { value: (), human: "unlimited" }"#
+        );
+        #[cfg(feature = "percent")]
+        {
+            use crate::relative::RelativeOrAbsolute;
+
+            assert_eq!(
+                format!("{:?}", RelativeOrAbsolute::<Si>::relative(500_000).as_value()),
+                r#"RelativeOrAbsolute { value: 500000, relative: true, human: "50%" }"#
+            );
+            assert_eq!(
+                format!("{:?}", RelativeOrAbsolute::<Si>::absolute(2_000).as_value()),
+                r#"RelativeOrAbsolute { value: 2000, relative: false, human: "2k" }"#
+            );
+        }
+    }
+}
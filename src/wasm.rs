@@ -0,0 +1,211 @@
+//! [`wasm-bindgen`](https://docs.rs/wasm-bindgen) bindings, to share the
+//! parsing rules with web frontends.
+//!
+//! Each unit family exports a `parse` and a `format` function (`parseByte`
+//! and `formatByte` for [`byte`](crate::byte), ...). Integers are exchanged
+//! as `BigInt`s, to avoid any precision loss, and parsing errors are thrown
+//! as [`ParseError`] objects.
+//!
+//! ```js
+//! import { parseByteps, formatByte } from "bity";
+//!
+//! parseByteps("5MB/s"); // 5000000n
+//! formatByte(1500n); // "1.5kB"
+//!
+//! try {
+//!     parseByteps("5XB/s");
+//! } catch (err) {
+//!     err.kind; // "invalid_unit"
+//!     err.fragment; // "XB"
+//!     err.message; // 'invalid unit "XB"'
+//! }
+//! ```
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{Error, ErrorKind};
+
+/// Error thrown by the parsing functions.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ErrorKind,
+    fragment: Option<String>,
+    message: String,
+    suggestion: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ParseError {
+    /// Kind of the error, as returned by [`ErrorKind::as_str`] (like
+    /// `invalid_unit`).
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.as_str().to_owned()
+    }
+
+    /// Part of the input responsible for the error, if any.
+    #[wasm_bindgen(getter)]
+    pub fn fragment(&self) -> Option<String> {
+        self.fragment.clone()
+    }
+
+    /// Human readable description of the error.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Closest valid unit, if the unit of the input is invalid.
+    #[wasm_bindgen(getter)]
+    pub fn suggestion(&self) -> Option<String> {
+        self.suggestion.clone()
+    }
+}
+
+impl ParseError {
+    fn new(err: Error<'_>, units: &[&str]) -> Self {
+        Self {
+            kind: err.kind(),
+            fragment: err.fragment().map(str::to_owned),
+            message: err.to_string(),
+            suggestion: err.suggestion(units),
+        }
+    }
+}
+
+macro_rules! bindings {
+    ($(
+        $(#[cfg($cfg:meta)])?
+        $module:ident: $parse:ident / $parse_js:ident, $format:ident / $format_js:ident,
+    )*) => {
+        $(
+            #[doc = concat!("Parse a string using [`", stringify!($module), "::parse`](crate::", stringify!($module), "::parse).")]
+            $(#[cfg($cfg)])?
+            #[wasm_bindgen(js_name = $parse_js)]
+            pub fn $parse(input: &str) -> Result<u64, ParseError> {
+                crate::$module::parse(input)
+                    .map_err(|err| ParseError::new(err, crate::$module::UNITS))
+            }
+
+            #[doc = concat!("Format an integer using [`", stringify!($module), "::format`](crate::", stringify!($module), "::format).")]
+            $(#[cfg($cfg)])?
+            #[wasm_bindgen(js_name = $format_js)]
+            pub fn $format(input: u64) -> String {
+                crate::$module::format(input)
+            }
+        )*
+    };
+}
+
+/// Parse a string using [`si::parse`](crate::si::parse).
+#[wasm_bindgen(js_name = parseSi)]
+pub fn parse_si(input: &str) -> Result<u64, ParseError> {
+    crate::si::parse(input).map_err(|err| ParseError::new(err, &[]))
+}
+
+/// Format an integer using [`si::format`](crate::si::format).
+#[wasm_bindgen(js_name = formatSi)]
+pub fn format_si(input: u64) -> String {
+    crate::si::format(input)
+}
+
+bindings! {
+    #[cfg(feature = "bit")]
+    bit: parse_bit / parseBit, format_bit / formatBit,
+    #[cfg(feature = "byte")]
+    byte: parse_byte / parseByte, format_byte / formatByte,
+    #[cfg(feature = "packet")]
+    packet: parse_packet / parsePacket, format_packet / formatPacket,
+    #[cfg(feature = "bps")]
+    bps: parse_bps / parseBps, format_bps / formatBps,
+    #[cfg(feature = "byteps")]
+    byteps: parse_byteps / parseByteps, format_byteps / formatByteps,
+    #[cfg(feature = "pps")]
+    pps: parse_pps / parsePps, format_pps / formatPps,
+    #[cfg(feature = "bpp")]
+    bpp: parse_bpp / parseBpp, format_bpp / formatBpp,
+    #[cfg(feature = "hz")]
+    hz: parse_hz / parseHz, format_hz / formatHz,
+    #[cfg(feature = "watt")]
+    watt: parse_watt / parseWatt, format_watt / formatWatt,
+    #[cfg(feature = "ops")]
+    ops: parse_ops / parseOps, format_ops / formatOps,
+    #[cfg(feature = "percent")]
+    percent: parse_percent / parsePercent, format_percent / formatPercent,
+    #[cfg(feature = "compression")]
+    compression: parse_compression / parseCompression, format_compression / formatCompression,
+}
+
+/// Parse a string using [`db::parse`](crate::db::parse).
+#[cfg(feature = "db")]
+#[wasm_bindgen(js_name = parseDb)]
+pub fn parse_db(input: &str) -> Result<i64, ParseError> {
+    crate::db::parse(input).map_err(|err| ParseError::new(err, crate::db::UNITS))
+}
+
+/// Format an integer using [`db::format`](crate::db::format).
+#[cfg(feature = "db")]
+#[wasm_bindgen(js_name = formatDb)]
+pub fn format_db(input: i64) -> String {
+    crate::db::format(input)
+}
+
+/// Parse a string using [`db::parse_dbm`](crate::db::parse_dbm).
+#[cfg(feature = "db")]
+#[wasm_bindgen(js_name = parseDbm)]
+pub fn parse_dbm(input: &str) -> Result<i64, ParseError> {
+    crate::db::parse_dbm(input).map_err(|err| ParseError::new(err, crate::db::UNITS_DBM))
+}
+
+/// Format an integer using [`db::format_dbm`](crate::db::format_dbm).
+#[cfg(feature = "db")]
+#[wasm_bindgen(js_name = formatDbm)]
+pub fn format_dbm(input: i64) -> String {
+    crate::db::format_dbm(input)
+}
+
+/// Parse a string using [`duration::parse`](crate::duration::parse), into
+/// nanoseconds.
+#[cfg(feature = "duration")]
+#[wasm_bindgen(js_name = parseDuration)]
+pub fn parse_duration(input: &str) -> Result<u64, ParseError> {
+    let duration = crate::duration::parse(input)
+        .map_err(|err| ParseError::new(err, crate::duration::UNITS))?;
+    // Durations above `u64::MAX` nanoseconds (~584 years) are saturated.
+    Ok(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX))
+}
+
+/// Format nanoseconds using [`duration::format`](crate::duration::format).
+#[cfg(feature = "duration")]
+#[wasm_bindgen(js_name = formatDuration)]
+pub fn format_duration(input: u64) -> String {
+    crate::duration::format(std::time::Duration::from_nanos(input))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse_byteps("5MB/s").unwrap(), 5_000_000);
+        assert_eq!(super::parse_dbm("-20dBm").unwrap(), -20_000);
+        assert_eq!(super::parse_duration("1m30s").unwrap(), 90_000_000_000);
+        assert_eq!(super::parse_duration("18446744073709551615s").unwrap(), u64::MAX);
+
+        let err = super::parse_byteps("5XB/s").unwrap_err();
+        assert_eq!(err.kind(), "invalid_unit");
+        assert_eq!(err.fragment().as_deref(), Some("XB"));
+        assert_eq!(err.message(), r#"invalid unit "XB""#);
+        let err = super::parse_duration("5hr").unwrap_err();
+        assert_eq!(err.suggestion().as_deref(), Some("h"));
+        let err = super::parse_bps("").unwrap_err();
+        assert_eq!((err.kind().as_str(), err.fragment()), ("empty_input", None));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format_byte(1_500), "1.5kB");
+        assert_eq!(super::format_db(-1_500), "-1.5dB");
+        assert_eq!(super::format_duration(90_000_000_000), "1m30s");
+    }
+}
@@ -0,0 +1,279 @@
+//! SI prefixed power parsing and formatting.
+//!
+//! # Examples
+//!
+//! ```
+//! use bity::watt::{format, parse};
+//!
+//! assert_eq!(parse("750W").unwrap(), 750);
+//! assert_eq!(parse("1.2kW").unwrap(), 1_200);
+//!
+//! assert_eq!(format(1_234), "1.23kW");
+//! assert_eq!(format(3_000_000), "3MW");
+//! ```
+//!
+//! # Serde
+//!
+//! Enabling the `serde` allows the use of `#[serde(serialize_with =
+//! "bity::watt::serialize")]`, `#[serde(deserialize_with =
+//! "bity::watt::deserialize")]` and `#[serde(with = "bity::watt")]` attributes.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use indoc::indoc;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! #[serde(rename_all = "kebab-case")]
+//! struct Configuration {
+//!     #[serde(with = "bity::watt")]
+//!     rack_budget: u64,
+//!     #[serde(with = "bity::watt")]
+//!     psu: u64,
+//! }
+//!
+//! assert_eq!(
+//!     toml::from_str::<Configuration>(indoc! {r#"
+//!         rack-budget = "12.5kW"
+//!         psu = 750
+//!     "#})
+//!     .unwrap(),
+//!     Configuration {
+//!         rack_budget: 12_500,
+//!         psu: 750,
+//!     }
+//! );
+//!
+//! assert_eq!(
+//!     toml::to_string(&Configuration {
+//!         rack_budget: 12_500,
+//!         psu: 750,
+//!     })
+//!     .unwrap(),
+//!     indoc! {r#"
+//!         rack-budget = "12.5kW"
+//!         psu = "750W"
+//!     "#}
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    si::{self, Prefix},
+    Error,
+};
+
+/// Regular expression matching the strings accepted by [`parse`], refer to
+/// [`si::PATTERN`](crate::si::PATTERN) for details.
+pub const PATTERN: &str = r"^\s*(?:\+?[0-9]+(?:\.[0-9]*)?|\.[0-9]+)\s*[kKmMgGtTpPeE]?W?\s*$";
+
+/// Units accepted after the SI prefix, listed in error reports.
+pub const UNITS: &[&str] = &["W"];
+
+/// Upper bound of the length of the strings returned by [`format`] and
+/// [`format_with_prefix`], refer to
+/// [`si::MAX_FORMATTED_LEN`](crate::si::MAX_FORMATTED_LEN) for details.
+pub const MAX_FORMATTED_LEN: usize = crate::si::MAX_FORMATTED_LEN + "W".len();
+
+/// Parse a power SI prefixed string into a number.
+///
+/// This is equivalent to colling `si::parse_with_additional_units(input,
+/// &[("W", 1)])`.
+///
+/// Refer to [`si::parse`] and [`si::parse_with_additional_units`] to learn the
+/// rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::watt::parse;
+///
+/// assert_eq!(parse("750W").unwrap(), 750);
+/// assert_eq!(parse("3MW").unwrap(), 3_000_000);
+/// assert_eq!(parse("12").unwrap(), 12);
+/// ```
+pub fn parse(input: &str) -> Result<u64, Error<'_>> {
+    si::parse_with_additional_units(input, &[("W", 1)])
+}
+
+/// Const version of [`parse`], returning `None` if the input is invalid (or
+/// overflows), mostly used by [`default!`](crate::default).
+///
+/// # Examples
+/// ```
+/// use bity::watt::parse_const;
+///
+/// assert_eq!(parse_const("1.5kW"), Some(1_500));
+/// assert_eq!(parse_const("1.5kx"), None);
+/// ```
+pub const fn parse_const(input: &str) -> Option<u64> {
+    si::parse_const_with_additional_units(input, &[("W", 1)])
+}
+
+/// Format an integer into a power SI prefixed string.
+///
+/// This is equivalent to colling `format!("{}W", si::format(input))`.
+///
+/// Refer to [`si::format`] to learn the rules that apply.
+///
+/// # Examples
+/// ```
+/// use bity::watt::format;
+///
+/// assert_eq!(format(750), "750W");
+/// assert_eq!(format(1_200), "1.2kW");
+/// assert_eq!(format(3_000_000), "3MW");
+/// ```
+pub fn format(input: u64) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = format_into(&mut output, input);
+    output
+}
+
+/// Format an integer into a SI prefixed string (power) using a given SI prefix,
+/// whatever its magnitude.
+///
+/// This is equivalent to colling `format!("{}W", si::format_with_prefix(input,
+/// prefix))`.
+///
+/// # Examples
+/// ```
+/// use bity::{watt::format_with_prefix, si::Prefix};
+///
+/// assert_eq!(format_with_prefix(2_400_000_000, Prefix::Mega), "2400MW");
+/// ```
+pub fn format_with_prefix(input: u64, prefix: Prefix) -> String {
+    let mut output = String::with_capacity(MAX_FORMATTED_LEN);
+    // Writing into a `String` can't fail.
+    let _ = si::format_with_prefix_into(&mut output, input, prefix);
+    output.push('W');
+    output
+}
+
+/// Like [`format`], but writing into an existing writer (like a reused
+/// `String` or a `heapless::String`) instead of allocating.
+///
+/// # Examples
+/// ```
+/// use bity::watt::format_into;
+///
+/// let mut output = String::new();
+/// format_into(&mut output, 1_500).unwrap();
+/// assert_eq!(output, "1.5kW");
+/// ```
+pub fn format_into(output: &mut impl fmt::Write, input: u64) -> fmt::Result {
+    si::format_into(output, input)?;
+    output.write_str("W")
+}
+
+/// Like [`format`], but writing into a caller-provided buffer, returning the
+/// number of bytes written, or `None` if the buffer is too small
+/// ([`MAX_FORMATTED_LEN`] bytes are always enough).
+///
+/// # Examples
+/// ```
+/// use bity::watt::{format_to_slice, MAX_FORMATTED_LEN};
+///
+/// let mut buffer = [0; MAX_FORMATTED_LEN];
+/// let len = format_to_slice(&mut buffer, 1_500).unwrap();
+/// assert_eq!(&buffer[..len], b"1.5kW");
+/// ```
+pub fn format_to_slice(output: &mut [u8], input: u64) -> Option<usize> {
+    si::write_to_slice(output, |writer| format_into(writer, input))
+}
+
+#[cfg(feature = "serde")]
+crate::impl_serde!(
+    expecting: r#"a power like "1.5kW", "12MW" or 1500"#,
+    example: 1_500,
+    ser:
+    /// Serialize a given `u64` into a SI prefixed power string.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(serialize_with = "bity::watt::serialize")]` and `#[serde(with = "bity::watt")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(serialize_with = "bity::watt::serialize")]
+    ///     rack_budget: u64,
+    ///     #[serde(with = "bity::watt")]
+    ///     psu: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::to_string(&Configuration {
+    ///         rack_budget: 12_500,
+    ///         psu: 750,
+    ///     }).unwrap(),
+    ///     indoc! {r#"
+    ///         rack-budget = "12.5kW"
+    ///         psu = "750W"
+    ///     "#}
+    /// );
+    /// ```
+    de:
+    /// Deserialize a given integer or SI prefixed power string into an `u64`.
+    ///
+    /// Enabling the `serde` allows the use of `#[serde(deserialize_with = "bity::watt::deserialize")]` and `#[serde(with = "bity::watt")]` attributes.
+    ///
+    /// ```
+    /// use indoc::indoc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, PartialEq, Debug)]
+    /// #[serde(rename_all = "kebab-case")]
+    /// struct Configuration {
+    ///     #[serde(deserialize_with = "bity::watt::deserialize")]
+    ///     rack_budget: u64,
+    ///     #[serde(with = "bity::watt")]
+    ///     psu: u64,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     toml::from_str::<Configuration>(
+    ///         indoc! {r#"
+    ///             rack-budget = "12.5kW"
+    ///             psu = 750
+    ///         "#}
+    ///     ).unwrap(),
+    ///     Configuration {
+    ///         rack_budget: 12_500,
+    ///         psu: 750,
+    ///     }
+    /// );
+    /// ```
+);
+
+#[cfg(feature = "serde")]
+crate::__serde_prefixed!();
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn parse() {
+        assert_eq!(super::parse("750W").unwrap(), 750);
+        assert_eq!(super::parse("1.2kW").unwrap(), 1_200);
+        assert_eq!(super::parse("3 MW").unwrap(), 3_000_000);
+        assert_eq!(super::parse("3M").unwrap(), 3_000_000);
+        assert_eq!(super::parse("12").unwrap(), 12);
+
+        // The unit is case-sensitive.
+        assert!(matches!(super::parse("12w"), Err(Error::InvalidUnit("w"))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(super::format(123), "123W");
+        assert_eq!(super::format(1_234), "1.23kW");
+        assert_eq!(super::format(3_000_000), "3MW");
+    }
+}